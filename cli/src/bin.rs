@@ -1,27 +1,63 @@
-use qbit_lang::parser::Parser;
+use std::{
+    fs,
+    io::{IsTerminal, Read},
+};
 
-fn main() -> Result<(), String> {
-    let result = Parser::parse_src(
-        r#"let S  = "";
+use qbit_lang::parser::{Diagnostic, Parser};
+
+const DEMO: &str = r#"let S  = "";
 
 fn test() {
    let tt = "";
-}"#,
-    );
+}"#;
+
+fn main() -> Result<(), String> {
+    let path = std::env::args().nth(1);
+    let piped = !std::io::stdin().is_terminal();
 
-    match result {
+    let (name, source) = match path.as_deref() {
+        Some("-") => ("<stdin>", read_stdin()?),
+        Some(path) => (
+            path,
+            fs::read_to_string(path).map_err(|err| format!("cannot read '{path}': {err}"))?,
+        ),
+        None if piped => ("<stdin>", read_stdin()?),
+        None => ("<demo>", DEMO.to_string()),
+    };
+
+    match Parser::parse_src(&source) {
         Ok(res) => {
-            println!("{:#?}", res.statements());
-            println!("{:#?}", res.diagnositcs());
+            if name == "<demo>" {
+                for statement in res.statements() {
+                    println!("{statement}");
+                }
+            }
+
+            report(name, res.diagnostics())
         }
-        Err(err) => println!("{err:?}"),
+        Err(err) => report(name, &[Diagnostic::from(err)]),
     }
+}
 
-    // let engine = qbit_lang::parser::Parser::builder::::new()?;
+fn read_stdin() -> Result<String, String> {
+    let mut source = String::new();
 
-    // // engine.run_file("scripts/core/fib.qb".into())?;
-    // engine.run("print(40 + 2);")?;
-    // engine.run("series ffff; print(ffff);")?;
+    std::io::stdin()
+        .read_to_string(&mut source)
+        .map_err(|err| format!("cannot read stdin: {err}"))?;
 
-    Ok(())
+    Ok(source)
+}
+
+fn report(name: &str, diagnostics: &[Diagnostic]) -> Result<(), String> {
+    for diagnostic in diagnostics {
+        println!("{name}:{diagnostic}");
+    }
+
+    let errors = diagnostics.iter().filter(|d| d.is_error()).count();
+
+    match errors {
+        0 => Ok(()),
+        count => Err(format!("{name}: {count} error(s)")),
+    }
 }
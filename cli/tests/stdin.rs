@@ -0,0 +1,44 @@
+use std::{
+    io::Write,
+    process::{Command, Output, Stdio},
+};
+
+fn run(args: &[&str], source: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_qbit-cli"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to spawn qbit-cli");
+
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(source.as_bytes())
+        .unwrap();
+
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn piped_stdin() {
+    let output = run(&[], "let x = 1;\nlet badName = 2;\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(stdout, "<stdin>:2:1: warning: expected 'bad_name'\n");
+}
+
+#[test]
+fn dash_stdin() {
+    let output = run(&["-"], "let x = 1;\n\nlet y = ;\n");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(
+        stdout,
+        "<stdin>:3:9: error: Expected expression, found Semicolon\n"
+    );
+}
@@ -0,0 +1,322 @@
+use super::{expr::Expr, format::format_expr, stmt::Stmt};
+
+/// Render a parse tree as a Graphviz DOT graph, one node per AST variant under a
+/// `Program` root and edges to children labelled with their role
+pub fn ast_to_dot(stmts: &[Stmt]) -> String {
+    let mut writer = DotWriter {
+        out: String::from("digraph ast {\n    node [shape=box];\n"),
+        next_id: 0,
+    };
+
+    let root = writer.node("Program");
+
+    for stmt in stmts {
+        let child = writer.stmt(stmt);
+        writer.edge(root, child, None);
+    }
+
+    writer.out.push_str("}\n");
+    writer.out
+}
+
+struct DotWriter {
+    out: String,
+    next_id: usize,
+}
+
+impl DotWriter {
+    fn node(&mut self, label: &str) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.out
+            .push_str(&format!("    n{id} [label=\"{}\"];\n", escape(label)));
+        id
+    }
+
+    fn edge(&mut self, from: usize, to: usize, label: Option<&str>) {
+        match label {
+            Some(label) => self.out.push_str(&format!(
+                "    n{from} -> n{to} [label=\"{}\"];\n",
+                escape(label)
+            )),
+            None => self.out.push_str(&format!("    n{from} -> n{to};\n")),
+        }
+    }
+
+    fn stmt_child(&mut self, parent: usize, stmt: &Stmt, label: &str) {
+        let child = self.stmt(stmt);
+        self.edge(parent, child, Some(label));
+    }
+
+    fn expr_child(&mut self, parent: usize, expr: &Expr, label: Option<&str>) {
+        let child = self.expr(expr);
+        self.edge(parent, child, label);
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) -> usize {
+        match stmt {
+            Stmt::Let { name, value, .. } => {
+                let id = self.node(&format!("Let {name}"));
+                self.expr_child(id, value, Some("value"));
+                id
+            }
+            Stmt::LetMulti { bindings, .. } => {
+                let id = self.node("LetMulti");
+
+                for binding in bindings {
+                    self.expr_child(id, &binding.value, Some(&binding.name));
+                }
+
+                id
+            }
+            Stmt::Const { name, value, .. } => {
+                let id = self.node(&format!("Const {name}"));
+                self.expr_child(id, value, Some("value"));
+                id
+            }
+            Stmt::Function {
+                name, params, body, ..
+            } => {
+                let params = params
+                    .iter()
+                    .map(|param| match param.rest {
+                        true => format!("...{}", param.name),
+                        false => param.name.clone(),
+                    })
+                    .collect::<Vec<_>>();
+                let id = self.node(&format!("Function {name}({})", params.join(", ")));
+
+                self.stmt_child(id, body, "body");
+                id
+            }
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                let id = self.node("If");
+                self.expr_child(id, condition, Some("condition"));
+                self.stmt_child(id, then_branch, "then");
+
+                if let Some(else_branch) = else_branch {
+                    self.stmt_child(id, else_branch, "else");
+                }
+
+                id
+            }
+            Stmt::Return { value, .. } => {
+                let id = self.node("Return");
+
+                if let Some(value) = value {
+                    self.expr_child(id, value, None);
+                }
+
+                id
+            }
+            Stmt::Block { statements, .. } => {
+                let id = self.node("Block");
+
+                for statement in statements {
+                    let child = self.stmt(statement);
+                    self.edge(id, child, None);
+                }
+
+                id
+            }
+            Stmt::Expression { expr, .. } => {
+                let id = self.node("Expression");
+                self.expr_child(id, expr, None);
+                id
+            }
+            Stmt::Import { module, .. } => self.node(&format!("Import {module}")),
+            Stmt::Export { statement } => {
+                let id = self.node("Export");
+                let child = self.stmt(statement);
+                self.edge(id, child, None);
+                id
+            }
+            Stmt::ExportDefault { expr, .. } => {
+                let id = self.node("ExportDefault");
+                self.expr_child(id, expr, None);
+                id
+            }
+            Stmt::While {
+                label,
+                condition,
+                body,
+                ..
+            } => {
+                let id = self.node(&labelled("While", label));
+                self.expr_child(id, condition, Some("condition"));
+                self.stmt_child(id, body, "body");
+                id
+            }
+            Stmt::For {
+                label,
+                init,
+                condition,
+                update,
+                body,
+                ..
+            } => {
+                let id = self.node(&labelled("For", label));
+
+                if let Some(init) = init {
+                    self.stmt_child(id, init, "init");
+                }
+
+                if let Some(condition) = condition {
+                    self.expr_child(id, condition, Some("condition"));
+                }
+
+                for update in update {
+                    self.expr_child(id, update, Some("update"));
+                }
+
+                self.stmt_child(id, body, "body");
+                id
+            }
+            Stmt::Break { label, .. } => self.node(&labelled("Break", label)),
+            Stmt::Continue { label, .. } => self.node(&labelled("Continue", label)),
+            Stmt::Empty { .. } => self.node("Empty"),
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) -> usize {
+        match expr {
+            Expr::Literal(_) => self.node(&format!("Literal {}", format_expr(expr))),
+            Expr::Variable(name) => self.node(&format!("Variable {name}")),
+            Expr::Binary { op, left, right } => {
+                let id = self.node(&format!("Binary {}", op.symbol()));
+                self.expr_child(id, left, Some("left"));
+                self.expr_child(id, right, Some("right"));
+                id
+            }
+            Expr::Unary { op, operand } => {
+                let id = self.node(&format!("Unary {}", op.symbol()));
+                self.expr_child(id, operand, None);
+                id
+            }
+            Expr::Group(inner) => {
+                let id = self.node("Group");
+                self.expr_child(id, inner, None);
+                id
+            }
+            Expr::Block { statements, tail } => {
+                let id = self.node("Block");
+
+                for statement in statements {
+                    let child = self.stmt(statement);
+                    self.edge(id, child, None);
+                }
+
+                if let Some(tail) = tail {
+                    self.expr_child(id, tail, Some("tail"));
+                }
+
+                id
+            }
+            Expr::Call {
+                callee,
+                args,
+                optional,
+            } => {
+                let id = self.node(if *optional { "Call ?." } else { "Call" });
+                self.expr_child(id, callee, Some("callee"));
+
+                for (i, arg) in args.iter().enumerate() {
+                    self.expr_child(id, arg, Some(&format!("arg {i}")));
+                }
+
+                id
+            }
+            Expr::Member {
+                object,
+                property,
+                optional,
+            } => {
+                let access = if *optional { "?." } else { "." };
+                let id = self.node(&format!("Member {access}{property}"));
+                self.expr_child(id, object, Some("object"));
+                id
+            }
+            Expr::Index { object, index } => {
+                let id = self.node("Index");
+                self.expr_child(id, object, Some("object"));
+                self.expr_child(id, index, Some("index"));
+                id
+            }
+            Expr::Sequence(exprs) => {
+                let id = self.node("Sequence");
+
+                for (i, expr) in exprs.iter().enumerate() {
+                    self.expr_child(id, expr, Some(&i.to_string()));
+                }
+
+                id
+            }
+            Expr::Array { elements } => {
+                let id = self.node("Array");
+
+                for (i, element) in elements.iter().enumerate() {
+                    self.expr_child(id, element, Some(&i.to_string()));
+                }
+
+                id
+            }
+            Expr::Object { entries } => {
+                let id = self.node("Object");
+
+                for (key, value) in entries {
+                    self.expr_child(id, value, Some(key));
+                }
+
+                id
+            }
+            Expr::Spread { expr } => {
+                let id = self.node("Spread");
+                self.expr_child(id, expr, None);
+                id
+            }
+            Expr::Assignment { target, value } => {
+                let id = self.node("Assignment =");
+                self.expr_child(id, target, Some("target"));
+                self.expr_child(id, value, Some("value"));
+                id
+            }
+            Expr::CompoundAssignment { target, op, value } => {
+                let id = self.node(&format!("Assignment {}=", op.symbol()));
+                self.expr_child(id, target, Some("target"));
+                self.expr_child(id, value, Some("value"));
+                id
+            }
+            Expr::PreIncrement { operand } => self.step("PreIncrement", operand),
+            Expr::PreDecrement { operand } => self.step("PreDecrement", operand),
+            Expr::PostIncrement { operand } => self.step("PostIncrement", operand),
+            Expr::PostDecrement { operand } => self.step("PostDecrement", operand),
+        }
+    }
+
+    fn step(&mut self, label: &str, operand: &Expr) -> usize {
+        let id = self.node(label);
+        self.expr_child(id, operand, None);
+        id
+    }
+}
+
+fn labelled(kind: &str, label: &Option<String>) -> String {
+    match label {
+        Some(label) => format!("{kind} {label}"),
+        None => kind.to_string(),
+    }
+}
+
+/// Escape a label for a double quoted DOT string
+fn escape(label: &str) -> String {
+    label
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
@@ -1,10 +1,13 @@
+use std::ops::Range;
+
 use crate::{
-    lexer::Token,
-    parser::{ParseContext, Parse, ParseError, Parser},
+    lexer::{TemplatePart, Token},
+    parser::{ParseContext, Parse, ParseError, ParseWarning, Parser},
 };
 
 use super::{
-    op::{BinaryOp, Precedence, UnaryOp},
+    op::{Associativity, BinaryOp, Precedence, UnaryOp},
+    stmt::Stmt,
     value::Value,
 };
 
@@ -30,16 +33,28 @@ pub enum Expr {
     // Grouping
     Group(Box<Expr>),
 
-    // Function calls
+    // Comma sequence, evaluates each expression and yields the last. Only parsed
+    // in parentheses and expression statements, commas separate items elsewhere
+    Sequence(Vec<Expr>),
+
+    // Block expression, yields `tail` or null without one
+    Block {
+        statements: Vec<Stmt>,
+        tail: Option<Box<Expr>>,
+    },
+
+    // Function calls, `optional` for `callee?.(args)`
     Call {
         callee: Box<Expr>,
         args: Vec<Expr>,
+        optional: bool,
     },
 
-    // Member access
+    // Member access, `optional` for `object?.property`
     Member {
         object: Box<Expr>,
         property: String,
+        optional: bool,
     },
 
     // Array/object indexing
@@ -53,6 +68,16 @@ pub enum Expr {
         elements: Vec<Expr>,
     },
 
+    // Object literal, keys in source order
+    Object {
+        entries: Vec<(String, Expr)>,
+    },
+
+    // Spread (`...expr`), only inside array literals and call arguments
+    Spread {
+        expr: Box<Expr>,
+    },
+
     // Assignment
     Assignment {
         target: Box<Expr>,
@@ -82,6 +107,24 @@ pub enum Expr {
 }
 
 impl Expr {
+    /// Expression, or a sequence of them when followed by commas
+    pub(crate) fn parse_sequence(parser: &mut Parser) -> Result<Self, ParseError> {
+        let first = Self::parse(parser)?;
+
+        if parser.peek() != Some(&Token::Comma) {
+            return Ok(first);
+        }
+
+        let mut exprs = vec![first];
+
+        while parser.consume(&Token::Comma) {
+            parser.count_expression_node()?;
+            exprs.push(Self::parse(parser)?);
+        }
+
+        Ok(Expr::Sequence(exprs))
+    }
+
     fn parse_expression(parser: &mut Parser, min_precedence: u8) -> Result<Self, ParseError> {
         parser.safe_call(|parser| {
             let mut left = Self::parse_unary(parser)?;
@@ -96,13 +139,17 @@ impl Expr {
                             break;
                         }
 
+                        let start = parser.position();
                         parser.advance(); // consume the operator
+                        parser.count_expression_node()?;
+                        Self::expect_enabled(parser, op, false, start)?;
+                        Self::hint_xor(parser, op, start);
 
                         // For right-associative operators, use same precedence
                         // For left-associative, use precedence + 1
-                        let next_min_precedence = match op.is_right_associative() {
-                            true => precedence,
-                            false => precedence + 1,
+                        let next_min_precedence = match op.associativity() {
+                            Associativity::Right => precedence,
+                            Associativity::Left => precedence + 1,
                         };
 
                         let right = Self::parse_expression(parser, next_min_precedence)?;
@@ -123,12 +170,15 @@ impl Expr {
 
     fn parse_assignment(parser: &mut Parser) -> Result<Self, ParseError> {
         parser.safe_call(|parser| {
+            let start = parser.position();
             let expr = Self::parse_expression(parser, 0)?; // Start with minimum precedence
 
             // Handle assignment operators
             match parser.peek() {
                 Some(Token::Equal) => {
+                    Self::expect_place(parser, &expr, start, "assignment target")?;
                     parser.advance();
+                    parser.count_expression_node()?;
                     let value = Self::parse_assignment(parser)?;
                     Ok(Expr::Assignment {
                         target: Box::new(expr),
@@ -141,28 +191,39 @@ impl Expr {
                     | Token::StarEqual
                     | Token::SlashEqual
                     | Token::ModuloEqual
+                    | Token::DoubleStarEqual
                     | Token::CaretEqual
                     | Token::BitAndEqual
                     | Token::BitOrEqual
                     | Token::ShiftLeftEqual
-                    | Token::ShiftRightEqual,
+                    | Token::ShiftRightEqual
+                    | Token::AndEqual
+                    | Token::OrEqual,
                 ) => {
+                    Self::expect_place(parser, &expr, start, "assignment target")?;
                     let op_token = parser.peek().unwrap().clone();
+                    let op_start = parser.position();
                     parser.advance();
-                    let value = Self::parse_assignment(parser)?;
+                    parser.count_expression_node()?;
                     let binary_op = match op_token {
                         Token::PlusEqual => BinaryOp::Add,
                         Token::MinusEqual => BinaryOp::Sub,
                         Token::StarEqual => BinaryOp::Mul,
                         Token::SlashEqual => BinaryOp::Div,
                         Token::ModuloEqual => BinaryOp::Mod,
-                        Token::CaretEqual => BinaryOp::Pow,
+                        Token::DoubleStarEqual => BinaryOp::Pow,
+                        Token::CaretEqual => BinaryOp::BitXor,
                         Token::BitAndEqual => BinaryOp::BitAnd,
                         Token::BitOrEqual => BinaryOp::BitOr,
                         Token::ShiftLeftEqual => BinaryOp::Shl,
                         Token::ShiftRightEqual => BinaryOp::Shr,
+                        Token::AndEqual => BinaryOp::And,
+                        Token::OrEqual => BinaryOp::Or,
                         _ => unreachable!(),
                     };
+                    Self::expect_enabled(parser, binary_op, true, op_start)?;
+                    Self::hint_xor(parser, binary_op, op_start);
+                    let value = Self::parse_assignment(parser)?;
                     Ok(Expr::CompoundAssignment {
                         target: Box::new(expr),
                         op: binary_op,
@@ -174,27 +235,109 @@ impl Expr {
         })
     }
 
+    /// Only variables, members and indexes can be assigned to or incremented
+    fn expect_place(
+        parser: &Parser,
+        target: &Expr,
+        start: usize,
+        role: &str,
+    ) -> Result<(), ParseError> {
+        match target {
+            Expr::Variable(_) | Expr::Index { .. } => Ok(()),
+            // `a?.b` may not evaluate to a place at all
+            Expr::Member { optional: false, .. } => Ok(()),
+            _ => {
+                let span = parser.span_from(start);
+
+                Err(ParseError::InvalidSyntax {
+                    message: format!("Invalid {role}"),
+                    context: ParseContext::from_span(parser.source, &span),
+                    span,
+                })
+            }
+        }
+    }
+
+    /// Reject an operator the config disabled, `start` being where its token begins
+    fn expect_enabled(
+        parser: &Parser,
+        op: BinaryOp,
+        compound: bool,
+        start: usize,
+    ) -> Result<(), ParseError> {
+        match parser.config.disabled_operators().contains(&op) {
+            true => {
+                let span = parser.span_from(start);
+                let assign = if compound { "=" } else { "" };
+
+                Err(ParseError::InvalidSyntax {
+                    message: format!("Operator '{}{assign}' is disabled", op.symbol()),
+                    context: ParseContext::from_span(parser.source, &span),
+                    span,
+                })
+            }
+            false => Ok(()),
+        }
+    }
+
+    /// Point out a `^` or `^=` operator starting at `start` as XOR, when enabled
+    fn hint_xor(parser: &mut Parser, op: BinaryOp, start: usize) {
+        if op == BinaryOp::BitXor && parser.config.xor_hint() {
+            let span = parser.span_from(start);
+
+            parser.hint(ParseWarning::XorOperator {
+                context: ParseContext::from_span(parser.source, &span),
+                span,
+            });
+        }
+    }
+
+    /// Unary operators bind looser than `**` but tighter than every other binary operator,
+    /// so `-2 ** 2` is `-(2 ** 2)` while `2 ** -2` is `2 ** (-2)`
     fn parse_unary(parser: &mut Parser) -> Result<Self, ParseError> {
         match parser.peek() {
             Some(token) => match UnaryOp::from_token(token) {
                 Some(unary_op) => {
                     parser.advance();
-                    Ok(Expr::Unary {
-                        op: unary_op,
-                        operand: Box::new(Self::parse_unary(parser)?),
-                    })
+                    parser.count_expression_node()?;
+
+                    let literal = matches!(
+                        parser.peek(),
+                        Some(Token::IntLiteral(_) | Token::FloatLiteral(_))
+                    );
+                    let operand = Self::parse_expression(parser, BinaryOp::Pow.precedence())?;
+
+                    // `-5` becomes a negative literal, `-5 ** 2` and `- -5` keep their negation
+                    match (&unary_op, operand) {
+                        (UnaryOp::Neg, Expr::Literal(Value::Int(i)))
+                            if literal && parser.config.fold_negative_literals() =>
+                        {
+                            Ok(Expr::Literal(Value::Int(-i)))
+                        }
+                        (UnaryOp::Neg, Expr::Literal(Value::Float(f)))
+                            if literal && parser.config.fold_negative_literals() =>
+                        {
+                            Ok(Expr::Literal(Value::Float(-f)))
+                        }
+                        (_, operand) => Ok(Expr::Unary {
+                            op: unary_op,
+                            operand: Box::new(operand),
+                        }),
+                    }
                 }
                 None => match token {
                     Token::PlusPlus => {
                         parser.advance();
+                        parser.count_expression_node()?;
                         Ok(Expr::PreIncrement {
-                            operand: Box::new(Self::parse_postfix(parser)?),
+                            operand: Box::new(Self::parse_place(parser, "increment operand")?),
                         })
                     }
                     Token::MinusMinus => {
                         parser.advance();
+                        parser.count_expression_node()?;
                         Ok(Expr::PreDecrement {
-                            operand: Box::new(Self::parse_postfix(parser)?),
+                            operand: Box::new(Self::parse_place(parser, "decrement operand")?),
                         })
                     }
                     _ => Self::parse_postfix(parser),
@@ -204,88 +347,131 @@ impl Expr {
         }
     }
 
+    /// Operand of a prefix `++` or `--`
+    fn parse_place(parser: &mut Parser, role: &str) -> Result<Self, ParseError> {
+        let start = parser.position();
+        let operand = Self::parse_postfix(parser)?;
+
+        Self::expect_place(parser, &operand, start, role)?;
+        Ok(operand)
+    }
+
     fn parse_postfix(parser: &mut Parser) -> Result<Self, ParseError> {
-        let mut expr = Self::parse_call(parser)?;
+        let start = parser.position();
+        let mut expr = Self::parse_primary(parser)?;
 
         loop {
             match parser.peek() {
+                Some(Token::LeftParen) => {
+                    expr = Self::parse_call(parser, expr, false)?;
+                }
                 Some(Token::PlusPlus) => {
+                    Self::expect_place(parser, &expr, start, "increment operand")?;
                     parser.advance();
                     expr = Expr::PostIncrement {
                         operand: Box::new(expr),
                     };
                 }
                 Some(Token::MinusMinus) => {
+                    Self::expect_place(parser, &expr, start, "decrement operand")?;
                     parser.advance();
                     expr = Expr::PostDecrement {
                         operand: Box::new(expr),
                     };
                 }
                 Some(Token::LeftBracket) => {
-                    parser.advance();
-                    let index = Self::parse(parser)?;
-                    parser.expect(Token::RightBracket)?;
+                    let index = parser.delimited(Token::LeftBracket, |parser| {
+                        let index = Self::parse(parser)?;
+                        parser.expect(Token::RightBracket)?;
+                        Ok(index)
+                    })?;
+
                     expr = Expr::Index {
                         object: Box::new(expr),
                         index: Box::new(index),
                     };
                 }
                 Some(Token::Dot) => {
-                    let source = parser.source;
+                    parser.advance();
+
+                    expr = Expr::Member {
+                        object: Box::new(expr),
+                        property: Self::parse_property(parser)?,
+                        optional: false,
+                    };
+                }
+                // `.5` lexes as a float, but after an operand it is a number
+                // written as a property name
+                Some(Token::FloatLiteral(_))
+                    if parser.source[parser.position()..].starts_with('.') =>
+                {
+                    let start = parser.position();
+                    parser.advance();
+                    let span = parser.span_from(start);
 
+                    return Err(ParseError::InvalidSyntax {
+                        message: "Expected property name after '.', found number".to_string(),
+                        context: ParseContext::from_span(parser.source, &span),
+                        span,
+                    });
+                }
+                Some(Token::QuestionDot) => {
                     parser.advance();
 
-                    match parser.advance() {
-                        Some(token_span) => match &token_span.token {
-                            Token::Identifier(name) => {
-                                expr = Expr::Member {
-                                    object: Box::new(expr),
-                                    property: name.clone(),
-                                };
-                            }
-                            _ => {
-                                return Err(ParseError::UnexpectedToken {
-                                    expected: Some("identifier".to_string()),
-                                    found: format!("{:?}", token_span.token),
-                                    span: token_span.span.clone(),
-                                    context: ParseContext::from_span(source, &token_span.span),
-                                });
-                            }
+                    expr = match parser.peek() {
+                        Some(Token::LeftParen) => Self::parse_call(parser, expr, true)?,
+                        _ => Expr::Member {
+                            object: Box::new(expr),
+                            property: Self::parse_property(parser)?,
+                            optional: true,
                         },
-                        None => {
-                            return Err(parser.error("", Some("property name")));
-                        }
-                    }
+                    };
                 }
                 _ => break,
             }
+
+            parser.count_expression_node()?;
         }
 
         Ok(expr)
     }
 
-    fn parse_call(parser: &mut Parser) -> Result<Self, ParseError> {
-        let mut expr = Self::parse_primary(parser)?;
+    fn parse_property(parser: &mut Parser) -> Result<String, ParseError> {
+        let source = parser.source;
 
-        while parser.peek() == Some(&Token::LeftParen) {
-            parser.advance();
+        match parser.advance() {
+            Some(token_span) => match &token_span.token {
+                Token::Identifier(name) => Ok(name.clone()),
+                _ => Err(ParseError::UnexpectedToken {
+                    expected: Some("identifier".to_string()),
+                    found: format!("{:?}", token_span.token),
+                    span: token_span.span.clone(),
+                    context: ParseContext::from_span(source, &token_span.span),
+                }),
+            },
+            None => Err(parser.error("", Some("property name"))),
+        }
+    }
+
+    fn parse_call(parser: &mut Parser, callee: Expr, optional: bool) -> Result<Self, ParseError> {
+        let args = parser.delimited(Token::LeftParen, |parser| {
             let args = Self::parse_argument_list(parser)?;
             parser.expect(Token::RightParen)?;
+            Ok(args)
+        })?;
 
-            expr = Expr::Call {
-                callee: Box::new(expr),
-                args,
-            };
-        }
-
-        Ok(expr)
+        Ok(Expr::Call {
+            callee: Box::new(callee),
+            args,
+            optional,
+        })
     }
 
     fn parse_argument_list(parser: &mut Parser) -> Result<Vec<Expr>, ParseError> {
         let mut args = Vec::new();
 
         while parser.peek() != Some(&Token::RightParen) {
-            args.push(Self::parse(parser)?);
+            args.push(Self::parse_spreadable(parser)?);
 
             match parser.peek() {
                 Some(Token::Comma) => {
@@ -305,8 +491,60 @@ impl Expr {
         Ok(args)
     }
 
+    /// `` `a ${b} c` `` as `"a " + b + " c"`, starting from a string so the result is one
+    fn parse_template(parser: &mut Parser, parts: &[TemplatePart]) -> Result<Self, ParseError> {
+        let mut expr = match parts.first() {
+            Some(TemplatePart::Text(_)) => None,
+            _ => Some(Expr::Literal(Value::Str(String::new()))),
+        };
+
+        for part in parts {
+            let piece = match part {
+                TemplatePart::Text(text) => Expr::Literal(Value::Str(text.clone())),
+                TemplatePart::Expr(range) => Self::parse_interpolation(parser, range.clone())?,
+            };
+
+            expr = Some(match expr {
+                Some(left) => Expr::Binary {
+                    op: BinaryOp::Add,
+                    left: Box::new(left),
+                    right: Box::new(piece),
+                },
+                None => piece,
+            });
+        }
+
+        Ok(expr.unwrap_or(Expr::Literal(Value::Str(String::new()))))
+    }
+
+    fn parse_interpolation(parser: &mut Parser, range: Range<usize>) -> Result<Self, ParseError> {
+        let mut inner = parser.sub_parser(range.clone())?;
+
+        if inner.eof() {
+            // Point at the whole `${}`
+            let span = range.start - 2..range.end + 1;
+
+            return Err(ParseError::InvalidSyntax {
+                message: "empty interpolation in template string".to_string(),
+                context: ParseContext::from_span(parser.source, &span),
+                span,
+            });
+        }
+
+        let result = match inner.safe_call(|parser| Self::parse(parser)) {
+            Ok(expr) if inner.eof() => Ok(expr),
+            Ok(_) => Err(inner.error("", Some("'}'"))),
+            Err(error) => Err(error),
+        };
+
+        parser.merge_sub_parser(inner);
+        result
+    }
+
     fn parse_primary(parser: &mut Parser) -> Result<Self, ParseError> {
+        parser.count_expression_node()?;
         let source = parser.source;
+        let checkpoint = parser.checkpoint();
 
         match parser.advance() {
             Some(token_span) => match &token_span.token {
@@ -314,18 +552,44 @@ impl Expr {
                 Token::FloatLiteral(f) => Ok(Expr::Literal(Value::Float(*f))),
                 Token::BoolTrue => Ok(Expr::Literal(Value::Bool(true))),
                 Token::BoolFalse => Ok(Expr::Literal(Value::Bool(false))),
+                Token::NullLiteral => Ok(Expr::Literal(Value::Null)),
                 Token::StringLiteral(s) => Ok(Expr::Literal(Value::Str(s.clone()))),
+                Token::CharLiteral(c) => Ok(Expr::Literal(Value::Char(*c))),
+                Token::TemplateString(parts) => {
+                    let parts = parts.clone();
+                    Self::parse_template(parser, &parts)
+                }
                 Token::Identifier(name) => Ok(Expr::Variable(name.clone())),
                 Token::LeftParen => {
-                    let expr = Self::parse(parser)?;
+                    // Need to backtrack since we consumed the paren
+                    parser.restore(checkpoint);
+
+                    parser.delimited(Token::LeftParen, |parser| {
+                        let expr = Self::parse_sequence(parser)?;
 
-                    parser.expect(Token::RightParen)?;
+                        parser.expect(Token::RightParen)?;
 
-                    Ok(Expr::Group(Box::new(expr)))
+                        Ok(Expr::Group(Box::new(expr)))
+                    })
                 }
+                Token::LeftBrace => {
+                    // Need to backtrack since we consumed the brace
+                    parser.restore(checkpoint);
+
+                    match Self::is_object_start(parser) {
+                        true => Self::parse_object_literal(parser),
+                        false => Self::parse_block(parser),
+                    }
+                }
+                Token::Ellipsis => Err(ParseError::InvalidSyntax {
+                    message: "spread is only allowed in array literals and call arguments"
+                        .to_string(),
+                    span: token_span.span.clone(),
+                    context: ParseContext::from_span(source, &token_span.span),
+                }),
                 Token::LeftBracket => {
                     // Need to backtrack since we consumed the bracket
-                    parser.pos -= 1;
+                    parser.restore(checkpoint);
 
                     Self::parse_array_literal(parser)
                 }
@@ -340,30 +604,128 @@ impl Expr {
         }
     }
 
+    /// `{ statements; tail }`, the last expression without a semicolon becomes the tail
+    fn parse_block(parser: &mut Parser) -> Result<Self, ParseError> {
+        parser.safe_call(|parser| {
+            parser.delimited(Token::LeftBrace, |parser| {
+                let mut statements = Vec::new();
+                let mut tail = None;
+
+                while parser.peek() != Some(&Token::RightBrace) && !parser.eof() {
+                    let statement = parser.peek().is_some_and(Stmt::is_statement_start)
+                        || Stmt::is_label_start(parser);
+
+                    if statement {
+                        statements.push(Stmt::parse(parser)?);
+                        continue;
+                    }
+
+                    let start = parser.position();
+                    let expr = Self::parse(parser)?;
+
+                    // Without semicolons only a line break before more statements ends one
+                    let terminated = parser.consume(&Token::Semicolon)
+                        || (parser.peek() != Some(&Token::RightBrace)
+                            && parser.implicit_terminator());
+
+                    match terminated {
+                        true => statements.push(Stmt::Expression {
+                            expr,
+                            span: parser.span_from(start).into(),
+                        }),
+                        false => {
+                            tail = Some(Box::new(expr));
+                            break;
+                        }
+                    }
+                }
+
+                parser.expect(Token::RightBrace)?;
+                Ok(Expr::Block { statements, tail })
+            })
+        })
+    }
+
+    fn parse_spreadable(parser: &mut Parser) -> Result<Self, ParseError> {
+        match parser.consume(&Token::Ellipsis) {
+            true => Ok(Expr::Spread {
+                expr: Box::new(Self::parse(parser)?),
+            }),
+            false => Self::parse(parser),
+        }
+    }
+
     fn parse_array_literal(parser: &mut Parser) -> Result<Self, ParseError> {
-        parser.expect(Token::LeftBracket)?;
-        let mut elements = Vec::new();
+        parser.delimited(Token::LeftBracket, |parser| {
+            let mut elements = Vec::new();
 
-        while parser.peek() != Some(&Token::RightBracket) {
-            elements.push(Self::parse(parser)?);
+            while parser.peek() != Some(&Token::RightBracket) {
+                elements.push(Self::parse_spreadable(parser)?);
 
-            match parser.peek() {
-                Some(Token::Comma) => {
-                    parser.advance();
-                    // Handle trailing comma if configured
-                    if parser.config.allow_trailing_commas()
-                        && parser.peek() == Some(&Token::RightBracket)
-                    {
-                        break;
+                match parser.peek() {
+                    Some(Token::Comma) => {
+                        parser.advance();
+                        // Handle trailing comma if configured
+                        if parser.config.allow_trailing_commas()
+                            && parser.peek() == Some(&Token::RightBracket)
+                        {
+                            break;
+                        }
                     }
+                    Some(Token::RightBracket) => break,
+                    _ => return Err(parser.error("", Some("',' or ']'"))),
                 }
-                Some(Token::RightBracket) => break,
-                _ => return Err(parser.error("", Some("',' or ']'"))),
             }
-        }
 
-        parser.expect(Token::RightBracket)?;
-        Ok(Expr::Array { elements })
+            parser.expect(Token::RightBracket)?;
+            Ok(Expr::Array { elements })
+        })
+    }
+
+    /// `{ key: ...` opens an object rather than a block, a label needs a loop after
+    /// it and `{}` stays an empty block
+    fn is_object_start(parser: &Parser) -> bool {
+        parser.peek_nth(0) == Some(&Token::LeftBrace)
+            && matches!(
+                parser.peek_nth(1),
+                Some(Token::Identifier(_) | Token::StringLiteral(_))
+            )
+            && parser.peek_nth(2) == Some(&Token::Colon)
+            && !matches!(parser.peek_nth(3), Some(Token::While | Token::For))
+    }
+
+    fn parse_object_literal(parser: &mut Parser) -> Result<Self, ParseError> {
+        parser.delimited(Token::LeftBrace, |parser| {
+            let mut entries = Vec::new();
+
+            while parser.peek() != Some(&Token::RightBrace) {
+                let key = match parser.peek() {
+                    Some(Token::Identifier(key) | Token::StringLiteral(key)) => key.clone(),
+                    _ => return Err(parser.error("", Some("object key"))),
+                };
+
+                parser.advance();
+                parser.expect(Token::Colon)?;
+                entries.push((key, Self::parse(parser)?));
+
+                match parser.peek() {
+                    Some(Token::Comma) => {
+                        parser.advance();
+                        // Handle trailing comma if configured
+                        if parser.config.allow_trailing_commas()
+                            && parser.peek() == Some(&Token::RightBrace)
+                        {
+                            break;
+                        }
+                    }
+                    Some(Token::RightBrace) => break,
+                    _ => return Err(parser.error("", Some("',' or '}'"))),
+                }
+            }
+
+            parser.expect(Token::RightBrace)?;
+            Ok(Expr::Object { entries })
+        })
     }
 }
 
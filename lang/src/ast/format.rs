@@ -0,0 +1,786 @@
+use std::{collections::VecDeque, ops::Range};
+
+use logos::Logos;
+
+use crate::{lexer::Token, parser::Trivia};
+
+use super::{
+    expr::Expr,
+    op::{BinaryOp, Precedence, UnaryOp},
+    stmt::Stmt,
+    value::Value,
+};
+
+const INDENT: &str = "    ";
+
+/// Precedence of anything that binds tighter than a unary operator
+const POSTFIX_PRECEDENCE: u8 = 20;
+
+/// Format a statement as canonical source
+pub fn format_stmt(stmt: &Stmt) -> String {
+    let mut formatter = Formatter::default();
+    formatter.stmt(stmt);
+    formatter.out
+}
+
+/// Format a program as canonical source, one top-level statement per line
+pub fn format_program(statements: &[Stmt]) -> String {
+    let mut formatter = Formatter::default();
+    formatter.program(statements);
+    formatter.out
+}
+
+/// Format a program like [`format_program`], writing the comments recorded in `trivia`
+/// back between the statements they were found between. `None` when a comment sits
+/// inside an expression or a statement header, where the output has no place for it
+pub fn format_program_with_comments(
+    statements: &[Stmt],
+    source: &str,
+    trivia: &[Trivia],
+) -> Option<String> {
+    let mut formatter = Formatter {
+        trivia: Some(SourceComments {
+            source,
+            pending: trivia.iter().map(|trivia| trivia.span.clone()).collect(),
+            placed: 0,
+            lost: false,
+        }),
+        ..Default::default()
+    };
+    formatter.program(statements);
+
+    match formatter.trivia?.lost {
+        true => None,
+        false => Some(formatter.out),
+    }
+}
+
+/// Format an expression as canonical source, only parenthesizing where precedence requires it
+pub fn format_expr(expr: &Expr) -> String {
+    let mut formatter = Formatter::default();
+    formatter.expr(expr);
+    formatter.out
+}
+
+#[cfg(feature = "pretty-debug")]
+impl Expr {
+    /// Compact single-line source for debugging, unlike [`format_expr`] every
+    /// operator operand is parenthesized so the tree shape shows
+    pub fn to_source_string(&self) -> String {
+        let mut formatter = Formatter::compact();
+        formatter.expr(self);
+        formatter.out
+    }
+}
+
+#[cfg(feature = "pretty-debug")]
+impl Stmt {
+    /// Compact single-line source for debugging, comments left out
+    pub fn to_source_string(&self) -> String {
+        let mut formatter = Formatter::compact();
+        formatter.stmt(self);
+        formatter.out
+    }
+}
+
+#[cfg(feature = "pretty-debug")]
+impl std::fmt::Display for Expr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_source_string())
+    }
+}
+
+#[cfg(feature = "pretty-debug")]
+impl std::fmt::Display for Stmt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.to_source_string())
+    }
+}
+
+#[derive(Default)]
+struct Formatter<'a> {
+    out: String,
+    depth: usize,
+    /// Single line output with explicit grouping, see `Expr::to_source_string`
+    compact: bool,
+    /// Comments of the source, written in place of the ones attached to declarations
+    trivia: Option<SourceComments<'a>>,
+}
+
+/// Comments of the source still to be written, by their range in the source
+struct SourceComments<'a> {
+    source: &'a str,
+    pending: VecDeque<Range<usize>>,
+    /// End of the source the output has caught up with
+    placed: usize,
+    /// Whether a comment was found where the output has no place for it
+    lost: bool,
+}
+
+impl<'a> SourceComments<'a> {
+    /// Take the comments starting before `end`, those before what the output has
+    /// already caught up with are lost
+    fn take_before(&mut self, end: usize) -> Vec<&'a str> {
+        let mut taken = Vec::new();
+
+        while let Some(comment) = self.pending.front().filter(|c| c.start < end).cloned() {
+            self.pending.pop_front();
+
+            match comment.start < self.placed {
+                true => self.lost = true,
+                false => {
+                    taken.push(self.source[comment.clone()].trim_end());
+                    self.placed = comment.end;
+                }
+            }
+        }
+
+        taken
+    }
+
+    /// Take the comments after a statement ending at `end` that share its line,
+    /// anything left inside the statement is lost
+    fn take_trailing(&mut self, end: usize) -> Vec<&'a str> {
+        while self.pending.front().is_some_and(|c| c.start < end) {
+            self.pending.pop_front();
+            self.lost = true;
+        }
+
+        self.placed = self.placed.max(end);
+        let mut taken = Vec::new();
+
+        while let Some(comment) = self.pending.front().cloned() {
+            let gap = &self.source[self.placed..comment.start];
+
+            if !gap.trim().is_empty() || gap.contains('\n') {
+                break;
+            }
+
+            self.pending.pop_front();
+            taken.push(self.source[comment.clone()].trim_end());
+            self.placed = comment.end;
+        }
+
+        taken
+    }
+
+    /// How many of the pending comments come right before the closing brace of the
+    /// block being written, with nothing but whitespace around them
+    fn before_brace(&self) -> usize {
+        let mut end = self.placed;
+        let mut count = 0;
+
+        for comment in &self.pending {
+            if comment.start < end || !self.source[end..comment.start].trim().is_empty() {
+                break;
+            }
+
+            end = comment.end;
+            count += 1;
+        }
+
+        match self.source[end..].trim_start().starts_with('}') {
+            true => count,
+            false => 0,
+        }
+    }
+
+    fn take_before_brace(&mut self) -> Vec<&'a str> {
+        match self.before_brace() {
+            0 => Vec::new(),
+            count => self.take_before(self.pending[count - 1].end),
+        }
+    }
+}
+
+impl Formatter<'_> {
+    #[cfg(feature = "pretty-debug")]
+    fn compact() -> Self {
+        Formatter {
+            compact: true,
+            ..Default::default()
+        }
+    }
+
+    fn indent(&mut self) {
+        for _ in 0..self.depth {
+            self.out.push_str(INDENT);
+        }
+    }
+
+    /// Top-level statements one per line, followed by any comments after the last one
+    fn program(&mut self, statements: &[Stmt]) {
+        for statement in statements {
+            self.statement(statement);
+            self.out.push('\n');
+        }
+
+        if let Some(trivia) = &mut self.trivia {
+            for comment in trivia.take_before(usize::MAX) {
+                self.out.push_str(comment);
+                self.out.push('\n');
+            }
+        }
+    }
+
+    /// A statement of a program or block, along with the source comments around it
+    fn statement(&mut self, stmt: &Stmt) {
+        if let Some(trivia) = &mut self.trivia {
+            for comment in trivia.take_before(stmt.span().start) {
+                self.out.push_str(comment);
+                self.out.push('\n');
+                self.indent();
+            }
+        }
+
+        self.stmt(stmt);
+
+        if let Some(trivia) = &mut self.trivia {
+            for comment in trivia.take_trailing(stmt.span().end) {
+                self.out.push(' ');
+                self.out.push_str(comment);
+            }
+        }
+    }
+
+    fn stmt(&mut self, stmt: &Stmt) {
+        match stmt {
+            Stmt::Let {
+                name,
+                ty,
+                value,
+                leading_comments,
+                ..
+            } => {
+                self.comments(leading_comments);
+                self.out.push_str(&format!("let {}", annotated(name, ty)));
+
+                if *value != Expr::Literal(Value::Null) {
+                    self.out.push_str(" = ");
+                    self.expr(value);
+                }
+
+                self.out.push(';');
+            }
+            Stmt::LetMulti {
+                bindings,
+                leading_comments,
+                ..
+            } => {
+                self.comments(leading_comments);
+                self.out.push_str("let ");
+
+                for (i, binding) in bindings.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push_str(", ");
+                    }
+
+                    self.out.push_str(&annotated(&binding.name, &binding.ty));
+
+                    if binding.value != Expr::Literal(Value::Null) {
+                        self.out.push_str(" = ");
+                        self.expr(&binding.value);
+                    }
+                }
+
+                self.out.push(';');
+            }
+            Stmt::Const {
+                name,
+                ty,
+                value,
+                leading_comments,
+                ..
+            } => {
+                self.comments(leading_comments);
+                self.out
+                    .push_str(&format!("const {} = ", annotated(name, ty)));
+                self.expr(value);
+                self.out.push(';');
+            }
+            Stmt::Function {
+                name,
+                params,
+                return_ty,
+                body,
+                leading_comments,
+                ..
+            } => {
+                self.comments(leading_comments);
+                self.out.push_str(&format!("fn {name}("));
+
+                for (i, param) in params.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push_str(", ");
+                    }
+
+                    if param.rest {
+                        self.out.push_str("...");
+                    }
+
+                    self.out.push_str(&annotated(&param.name, &param.ty));
+
+                    if let Some(default) = &param.default {
+                        self.out.push_str(" = ");
+                        self.expr(default);
+                    }
+                }
+
+                self.out.push(')');
+
+                if let Some(return_ty) = return_ty {
+                    self.out.push_str(&format!(": {return_ty}"));
+                }
+
+                self.out.push(' ');
+                self.stmt(body);
+            }
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => {
+                self.out.push_str("if ");
+                self.expr(condition);
+                self.out.push(' ');
+                self.stmt(then_branch);
+
+                if let Some(else_branch) = else_branch {
+                    self.out.push_str(" else ");
+                    self.stmt(else_branch);
+                }
+            }
+            Stmt::Return { value, .. } => {
+                self.out.push_str("return");
+
+                if let Some(value) = value {
+                    self.out.push(' ');
+                    self.expr(value);
+                }
+
+                self.out.push(';');
+            }
+            Stmt::Block { statements, span } => {
+                if let Some(trivia) = &mut self.trivia {
+                    trivia.placed = trivia.placed.max(span.start + 1);
+                }
+
+                self.block(statements, None);
+            }
+            Stmt::Expression { expr, .. } => {
+                self.expr(expr);
+                self.out.push(';');
+            }
+            Stmt::Import {
+                module,
+                names,
+                alias,
+                ..
+            } => {
+                self.out.push_str("import ");
+
+                if !names.is_empty() {
+                    let names = names
+                        .iter()
+                        .map(|(name, alias)| aliased(name, alias))
+                        .collect::<Vec<_>>();
+
+                    self.out
+                        .push_str(&format!("{{ {} }} from ", names.join(", ")));
+                }
+
+                match is_identifier(module) {
+                    true => self.out.push_str(module),
+                    false => self.out.push_str(&format!("\"{}\"", escape_string(module))),
+                }
+
+                if let Some(alias) = alias {
+                    self.out.push_str(&format!(" as {alias}"));
+                }
+
+                self.out.push(';');
+            }
+            Stmt::Export { statement } => {
+                self.out.push_str("export ");
+                self.stmt(statement);
+            }
+            Stmt::ExportDefault { expr, .. } => {
+                self.out.push_str("export default ");
+                self.expr(expr);
+                self.out.push(';');
+            }
+            Stmt::While {
+                label,
+                condition,
+                body,
+                ..
+            } => {
+                self.label(label);
+                self.out.push_str("while ");
+                self.expr(condition);
+                self.out.push(' ');
+                self.stmt(body);
+            }
+            Stmt::For {
+                label,
+                init,
+                condition,
+                update,
+                body,
+                ..
+            } => {
+                self.label(label);
+                self.out.push_str("for (");
+
+                match init {
+                    Some(init) => self.stmt(init),
+                    None => self.out.push(';'),
+                }
+
+                if let Some(condition) = condition {
+                    self.out.push(' ');
+                    self.expr(condition);
+                }
+
+                self.out.push(';');
+
+                if !update.is_empty() {
+                    self.out.push(' ');
+                    self.list(update);
+                }
+
+                self.out.push_str(") ");
+                self.stmt(body);
+            }
+            Stmt::Break { label, .. } => {
+                self.out.push_str(&format!("break{};", jump_label(label)));
+            }
+            Stmt::Continue { label, .. } => {
+                self.out
+                    .push_str(&format!("continue{};", jump_label(label)));
+            }
+            Stmt::Empty { .. } => self.out.push(';'),
+        }
+    }
+
+    fn label(&mut self, label: &Option<String>) {
+        if let Some(label) = label {
+            self.out.push_str(&format!("{label}: "));
+        }
+    }
+
+    /// Comments each on their own line, leaving the output indented for what follows
+    fn comments(&mut self, comments: &[String]) {
+        if self.compact || self.trivia.is_some() {
+            return;
+        }
+
+        for comment in comments {
+            match comment.contains('\n') {
+                true => self.out.push_str(&format!("/*{comment}*/\n")),
+                false => self.out.push_str(&format!("//{comment}\n")),
+            }
+
+            self.indent();
+        }
+    }
+
+    /// Braced statements, with an optional tail expression for block expressions
+    fn block(&mut self, statements: &[Stmt], tail: Option<&Expr>) {
+        let has_comments = self.trivia.as_ref().is_some_and(|t| t.before_brace() > 0);
+
+        if statements.is_empty() && tail.is_none() && !has_comments {
+            self.out.push_str("{}");
+            return;
+        }
+
+        if self.compact {
+            self.out.push('{');
+
+            for statement in statements {
+                self.out.push(' ');
+                self.stmt(statement);
+            }
+
+            if let Some(tail) = tail {
+                self.out.push(' ');
+                self.expr(tail);
+            }
+
+            self.out.push_str(" }");
+            return;
+        }
+
+        self.out.push_str("{\n");
+        self.depth += 1;
+
+        for statement in statements {
+            self.indent();
+            self.statement(statement);
+            self.out.push('\n');
+        }
+
+        if let Some(tail) = tail {
+            self.indent();
+            self.expr(tail);
+            self.out.push('\n');
+        }
+
+        if let Some(trivia) = &mut self.trivia {
+            for comment in trivia.take_before_brace() {
+                self.indent();
+                self.out.push_str(comment);
+                self.out.push('\n');
+            }
+        }
+
+        self.depth -= 1;
+        self.indent();
+        self.out.push('}');
+    }
+
+    fn operand(&mut self, expr: &Expr, parenthesize: bool) {
+        let operator = matches!(
+            expr,
+            Expr::Binary { .. }
+                | Expr::Unary { .. }
+                | Expr::Assignment { .. }
+                | Expr::CompoundAssignment { .. }
+        );
+
+        match parenthesize || (self.compact && operator) {
+            true => {
+                self.out.push('(');
+                self.expr(expr);
+                self.out.push(')');
+            }
+            false => self.expr(expr),
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Literal(value) => self.literal(value),
+            Expr::Variable(name) => self.out.push_str(name),
+            Expr::Binary { op, left, right } => {
+                let own = op.precedence();
+                let (left_prec, right_prec) = (precedence(left), precedence(right));
+
+                // `(-a) ** b` needs its parentheses, `-a ** b` is `-(a ** b)`
+                let unary_base = *op == BinaryOp::Pow
+                    && (matches!(**left, Expr::Unary { .. }) || is_negative_literal(left));
+
+                self.operand(
+                    left,
+                    left_prec < own
+                        || (left_prec == own && op.is_right_associative())
+                        || unary_base,
+                );
+                self.out.push_str(&format!(" {} ", op.symbol()));
+                self.operand(
+                    right,
+                    right_prec < own || (right_prec == own && !op.is_right_associative()),
+                );
+            }
+            Expr::Unary { op, operand } => {
+                self.out.push_str(op.symbol());
+                let start = self.out.len();
+
+                let power = matches!(
+                    **operand,
+                    Expr::Binary {
+                        op: BinaryOp::Pow,
+                        ..
+                    }
+                );
+
+                self.operand(operand, precedence(operand) < op.precedence() && !power);
+
+                // Keep `- -x` and `+ +x` from collapsing into a decrement or increment
+                let doubled = matches!(op, UnaryOp::Neg | UnaryOp::Pos);
+
+                if doubled && self.out[start..].starts_with(op.symbol()) {
+                    self.out.insert(start, ' ');
+                }
+            }
+            Expr::Group(inner) => {
+                self.out.push('(');
+                self.expr(inner);
+                self.out.push(')');
+            }
+            Expr::Block { statements, tail } => self.block(statements, tail.as_deref()),
+            Expr::Call {
+                callee,
+                args,
+                optional,
+            } => {
+                self.operand(callee, precedence(callee) < POSTFIX_PRECEDENCE);
+
+                if *optional {
+                    self.out.push_str("?.");
+                }
+
+                self.out.push('(');
+                self.list(args);
+                self.out.push(')');
+            }
+            Expr::Member {
+                object,
+                property,
+                optional,
+            } => {
+                self.operand(object, precedence(object) < POSTFIX_PRECEDENCE);
+                self.out.push_str(if *optional { "?." } else { "." });
+                self.out.push_str(property);
+            }
+            Expr::Index { object, index } => {
+                self.operand(object, precedence(object) < POSTFIX_PRECEDENCE);
+                self.out.push('[');
+                self.expr(index);
+                self.out.push(']');
+            }
+            Expr::Sequence(exprs) => self.list(exprs),
+            Expr::Array { elements } => {
+                self.out.push('[');
+                self.list(elements);
+                self.out.push(']');
+            }
+            Expr::Object { entries } => {
+                self.out.push('{');
+
+                for (i, (key, value)) in entries.iter().enumerate() {
+                    if i > 0 {
+                        self.out.push_str(", ");
+                    }
+
+                    match is_identifier(key) {
+                        true => self.out.push_str(key),
+                        false => self.out.push_str(&format!("\"{}\"", escape_string(key))),
+                    }
+
+                    self.out.push_str(": ");
+                    self.expr(value);
+                }
+
+                self.out.push('}');
+            }
+            Expr::Spread { expr } => {
+                self.out.push_str("...");
+                self.expr(expr);
+            }
+            Expr::Assignment { target, value } => {
+                self.operand(target, precedence(target) == 0);
+                self.out.push_str(" = ");
+                self.expr(value);
+            }
+            Expr::CompoundAssignment { target, op, value } => {
+                self.operand(target, precedence(target) == 0);
+                self.out.push_str(&format!(" {}= ", op.symbol()));
+                self.expr(value);
+            }
+            Expr::PreIncrement { operand } => {
+                self.out.push_str("++");
+                self.operand(operand, precedence(operand) < POSTFIX_PRECEDENCE);
+            }
+            Expr::PreDecrement { operand } => {
+                self.out.push_str("--");
+                self.operand(operand, precedence(operand) < POSTFIX_PRECEDENCE);
+            }
+            Expr::PostIncrement { operand } => {
+                self.operand(operand, precedence(operand) < POSTFIX_PRECEDENCE);
+                self.out.push_str("++");
+            }
+            Expr::PostDecrement { operand } => {
+                self.operand(operand, precedence(operand) < POSTFIX_PRECEDENCE);
+                self.out.push_str("--");
+            }
+        }
+    }
+
+    fn list(&mut self, exprs: &[Expr]) {
+        for (i, expr) in exprs.iter().enumerate() {
+            if i > 0 {
+                self.out.push_str(", ");
+            }
+
+            self.expr(expr);
+        }
+    }
+
+    fn literal(&mut self, value: &Value) {
+        match value {
+            // Floats always keep a fractional part so they re-lex as floats
+            Value::Float(f) if f.fract() == 0.0 => self.out.push_str(&format!("{f:.1}")),
+            Value::Str(s) => self.out.push_str(&format!("\"{}\"", escape_string(s))),
+            Value::Char(c) => self.out.push_str(&format!("'{}'", escape_char(*c))),
+            value => self.out.push_str(&value.to_string()),
+        }
+    }
+}
+
+fn annotated(name: &str, ty: &Option<String>) -> String {
+    match ty {
+        Some(ty) => format!("{name}: {ty}"),
+        None => name.to_string(),
+    }
+}
+
+fn aliased(name: &str, alias: &Option<String>) -> String {
+    match alias {
+        Some(alias) => format!("{name} as {alias}"),
+        None => name.to_string(),
+    }
+}
+
+fn jump_label(label: &Option<String>) -> String {
+    match label {
+        Some(label) => format!(" {label}"),
+        None => String::new(),
+    }
+}
+
+fn precedence(expr: &Expr) -> u8 {
+    match expr {
+        Expr::Assignment { .. } | Expr::CompoundAssignment { .. } | Expr::Sequence(_) => 0,
+        Expr::Binary { op, .. } => op.precedence(),
+        Expr::Unary { op, .. } => op.precedence(),
+        // A folded negative literal prints like a negation
+        expr if is_negative_literal(expr) => UnaryOp::Neg.precedence(),
+        Expr::PreIncrement { .. } | Expr::PreDecrement { .. } => POSTFIX_PRECEDENCE - 1,
+        _ => POSTFIX_PRECEDENCE,
+    }
+}
+
+fn is_negative_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(Value::Int(i)) => *i < 0,
+        Expr::Literal(Value::Float(f)) => f.is_sign_negative(),
+        _ => false,
+    }
+}
+
+fn escape_string(s: &str) -> String {
+    s.replace('"', "\\\"")
+}
+
+fn escape_char(c: char) -> String {
+    match c {
+        '\n' => "\\n".to_string(),
+        '\t' => "\\t".to_string(),
+        '\r' => "\\r".to_string(),
+        '\0' => "\\0".to_string(),
+        '\\' => "\\\\".to_string(),
+        '\'' => "\\'".to_string(),
+        c => c.to_string(),
+    }
+}
+
+fn is_identifier(s: &str) -> bool {
+    let mut lexer = Token::lexer(s);
+
+    matches!(
+        (lexer.next(), lexer.next()),
+        (Some(Ok(Token::Identifier(_))), None)
+    )
+}
@@ -4,6 +4,14 @@ pub trait Precedence {
     fn precedence(&self) -> u8;
 }
 
+/// Side a chain of equal precedence operators groups from, `a - b - c` is
+/// `(a - b) - c` while `a ** b ** c` is `a ** (b ** c)`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Associativity {
+    Left,
+    Right,
+}
+
 #[derive(Debug, Clone, PartialEq, Copy)]
 pub enum BinaryOp {
     // Arithmetic
@@ -11,7 +19,10 @@ pub enum BinaryOp {
     Sub,
     Mul,
     Div,
+    /// Division rounded toward negative infinity, `a \ b`
+    FloorDiv,
     Mod,
+    /// Exponentiation, `a ** b`. `^` is bitwise XOR, not a power
     Pow,
 
     // Comparison
@@ -29,13 +40,65 @@ pub enum BinaryOp {
     // Bitwise
     BitAnd,
     BitOr,
+    BitXor,
     Shl,
     Shr,
 }
 
 impl BinaryOp {
+    pub fn associativity(&self) -> Associativity {
+        match self {
+            BinaryOp::Pow => Associativity::Right,
+            _ => Associativity::Left,
+        }
+    }
+
     pub fn is_right_associative(&self) -> bool {
-        matches!(self, BinaryOp::Pow)
+        self.associativity() == Associativity::Right
+    }
+
+    pub fn is_comparison(&self) -> bool {
+        matches!(
+            self,
+            BinaryOp::Eq
+                | BinaryOp::Neq
+                | BinaryOp::Lt
+                | BinaryOp::Le
+                | BinaryOp::Gt
+                | BinaryOp::Ge
+        )
+    }
+
+    /// Canonical source symbol of the operator
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinaryOp::Add => "+",
+            BinaryOp::Sub => "-",
+            BinaryOp::Mul => "*",
+            BinaryOp::Div => "/",
+            BinaryOp::FloorDiv => "\\",
+            BinaryOp::Mod => "%",
+            BinaryOp::Pow => "**",
+            BinaryOp::Eq => "==",
+            BinaryOp::Neq => "!=",
+            BinaryOp::Lt => "<",
+            BinaryOp::Le => "<=",
+            BinaryOp::Gt => ">",
+            BinaryOp::Ge => ">=",
+            BinaryOp::And => "&&",
+            BinaryOp::Or => "||",
+            BinaryOp::BitAnd => "&",
+            BinaryOp::BitOr => "|",
+            BinaryOp::BitXor => "^",
+            BinaryOp::Shl => "<<",
+            BinaryOp::Shr => ">>",
+        }
+    }
+
+    /// Every operator with its binding power, loosest first, for tooling that
+    /// needs precedence without matching on the operators itself
+    pub fn precedence_table() -> &'static [(BinaryOp, u8)] {
+        &PRECEDENCE_TABLE
     }
 
     pub fn from_token(token: &Token) -> Option<Self>{
@@ -44,8 +107,9 @@ impl BinaryOp {
             Token::Minus => Some(BinaryOp::Sub),
             Token::Star => Some(BinaryOp::Mul),
             Token::Slash => Some(BinaryOp::Div),
+            Token::Backslash => Some(BinaryOp::FloorDiv),
             Token::Modulo => Some(BinaryOp::Mod),
-            Token::Caret | Token::DoubleStar => Some(BinaryOp::Pow),
+            Token::DoubleStar => Some(BinaryOp::Pow),
             Token::EqualEqual => Some(BinaryOp::Eq),
             Token::BangEqual => Some(BinaryOp::Neq),
             Token::Less => Some(BinaryOp::Lt),
@@ -56,6 +120,7 @@ impl BinaryOp {
             Token::Or => Some(BinaryOp::Or),
             Token::BitAnd => Some(BinaryOp::BitAnd),
             Token::BitOr => Some(BinaryOp::BitOr),
+            Token::Caret => Some(BinaryOp::BitXor),
             Token::ShiftLeft => Some(BinaryOp::Shl),
             Token::ShiftRight => Some(BinaryOp::Shr),
             _ => None,
@@ -63,20 +128,37 @@ impl BinaryOp {
     }
 }
 
+/// Binding power of every binary operator, the only place it is defined
+const PRECEDENCE_TABLE: [(BinaryOp, u8); 20] = [
+    (BinaryOp::Or, 1),
+    (BinaryOp::And, 2),
+    (BinaryOp::BitOr, 3),
+    (BinaryOp::BitXor, 4),
+    (BinaryOp::BitAnd, 5),
+    (BinaryOp::Eq, 6),
+    (BinaryOp::Neq, 6),
+    (BinaryOp::Lt, 7),
+    (BinaryOp::Le, 7),
+    (BinaryOp::Gt, 7),
+    (BinaryOp::Ge, 7),
+    (BinaryOp::Shl, 8),
+    (BinaryOp::Shr, 8),
+    (BinaryOp::Add, 9),
+    (BinaryOp::Sub, 9),
+    (BinaryOp::Mul, 10),
+    (BinaryOp::Div, 10),
+    (BinaryOp::FloorDiv, 10),
+    (BinaryOp::Mod, 10),
+    (BinaryOp::Pow, 11),
+];
+
 impl Precedence for BinaryOp {
     fn precedence(&self) -> u8 {
-        match self {
-            BinaryOp::Or => 1,
-            BinaryOp::And => 2,
-            BinaryOp::BitOr => 3,
-            BinaryOp::BitAnd => 4,
-            BinaryOp::Eq | BinaryOp::Neq => 5,
-            BinaryOp::Lt | BinaryOp::Le | BinaryOp::Gt | BinaryOp::Ge => 6,
-            BinaryOp::Shl | BinaryOp::Shr => 7,
-            BinaryOp::Add | BinaryOp::Sub => 8,
-            BinaryOp::Mul | BinaryOp::Div | BinaryOp::Mod => 9,
-            BinaryOp::Pow => 10,
-        }
+        PRECEDENCE_TABLE
+            .iter()
+            .find(|(op, _)| op == self)
+            .map(|(_, precedence)| *precedence)
+            .expect("every binary operator is in the precedence table")
     }
 }
 
@@ -84,6 +166,8 @@ impl Precedence for BinaryOp {
 pub enum UnaryOp {
     Not, // !
     Neg, // -
+    Pos, // +
+    BitNot, // ~
 }
 
 impl UnaryOp{
@@ -91,9 +175,21 @@ impl UnaryOp{
          match token {
             Token::Bang => Some(UnaryOp::Not),
             Token::Minus => Some(UnaryOp::Neg),
+            Token::Plus => Some(UnaryOp::Pos),
+            Token::Tilde => Some(UnaryOp::BitNot),
             _ => None,
         }
     }
+
+    /// Canonical source symbol of the operator
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            UnaryOp::Not => "!",
+            UnaryOp::Neg => "-",
+            UnaryOp::Pos => "+",
+            UnaryOp::BitNot => "~",
+        }
+    }
 }
 
 impl Precedence for UnaryOp {
@@ -0,0 +1,95 @@
+use super::{
+    expr::Expr,
+    format::{format_expr, format_stmt},
+    stmt::Stmt,
+};
+
+impl Expr {
+    /// Fully parenthesized prefix form such as `(+ 2 (* 3 4))`, groups leave no trace
+    /// so only the tree shape shows
+    pub fn to_sexpr(&self) -> String {
+        match self {
+            Expr::Literal(_) => format_expr(self),
+            Expr::Variable(name) => name.clone(),
+            Expr::Binary { op, left, right } => {
+                format!("({} {} {})", op.symbol(), left.to_sexpr(), right.to_sexpr())
+            }
+            Expr::Unary { op, operand } => format!("({} {})", op.symbol(), operand.to_sexpr()),
+            Expr::Group(inner) => inner.to_sexpr(),
+            Expr::Block { statements, tail } => {
+                let items = statements
+                    .iter()
+                    .map(stmt_sexpr)
+                    .chain(tail.iter().map(|tail| tail.to_sexpr()));
+
+                list("block", items)
+            }
+            Expr::Call {
+                callee,
+                args,
+                optional,
+            } => {
+                let head = if *optional { "?.call" } else { "call" };
+                list(
+                    head,
+                    std::iter::once(&**callee).chain(args).map(Expr::to_sexpr),
+                )
+            }
+            Expr::Member {
+                object,
+                property,
+                optional,
+            } => {
+                let head = if *optional { "?." } else { "." };
+                format!("({head} {} {property})", object.to_sexpr())
+            }
+            Expr::Index { object, index } => {
+                format!("([] {} {})", object.to_sexpr(), index.to_sexpr())
+            }
+            Expr::Sequence(exprs) => list("sequence", exprs.iter().map(Expr::to_sexpr)),
+            Expr::Array { elements } => list("array", elements.iter().map(Expr::to_sexpr)),
+            Expr::Object { entries } => list(
+                "object",
+                entries
+                    .iter()
+                    .map(|(key, value)| format!("({key:?} {})", value.to_sexpr())),
+            ),
+            Expr::Spread { expr } => format!("(... {})", expr.to_sexpr()),
+            Expr::Assignment { target, value } => {
+                format!("(= {} {})", target.to_sexpr(), value.to_sexpr())
+            }
+            Expr::CompoundAssignment { target, op, value } => {
+                format!(
+                    "({}= {} {})",
+                    op.symbol(),
+                    target.to_sexpr(),
+                    value.to_sexpr()
+                )
+            }
+            Expr::PreIncrement { operand } => format!("(pre++ {})", operand.to_sexpr()),
+            Expr::PreDecrement { operand } => format!("(pre-- {})", operand.to_sexpr()),
+            Expr::PostIncrement { operand } => format!("(post++ {})", operand.to_sexpr()),
+            Expr::PostDecrement { operand } => format!("(post-- {})", operand.to_sexpr()),
+        }
+    }
+}
+
+/// Expression statements as s-expressions, anything else as canonical source
+fn stmt_sexpr(stmt: &Stmt) -> String {
+    match stmt {
+        Stmt::Expression { expr, .. } => expr.to_sexpr(),
+        stmt => format_stmt(stmt),
+    }
+}
+
+fn list(head: &str, items: impl Iterator<Item = String>) -> String {
+    let mut out = format!("({head}");
+
+    for item in items {
+        out.push(' ');
+        out.push_str(&item);
+    }
+
+    out.push(')');
+    out
+}
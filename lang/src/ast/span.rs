@@ -0,0 +1,26 @@
+use std::ops::{Deref, Range};
+
+/// Byte range of a node in the source. Spans never affect equality, so trees
+/// parsed from differently formatted sources still compare equal
+#[derive(Debug, Clone, Default)]
+pub struct Span(pub Range<usize>);
+
+impl PartialEq for Span {
+    fn eq(&self, _: &Self) -> bool {
+        true
+    }
+}
+
+impl Deref for Span {
+    type Target = Range<usize>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Range<usize>> for Span {
+    fn from(range: Range<usize>) -> Self {
+        Span(range)
+    }
+}
@@ -1,24 +1,71 @@
 use crate::{
     ast::{expr::Expr},
     lexer::Token,
-    parser::{ParseContext, Parse, ParseError, Parser},
+    parser::{ParseContext, Parse, ParseError, Parser, TokenSpan},
 };
 
-use super::value::Value;
+use super::{span::Span, value::Value};
+
+/// Function parameter, optionally with a default value
+#[derive(Debug, Clone, PartialEq)]
+pub struct Param {
+    pub name: String,
+    pub ty: Option<String>,
+    pub default: Option<Expr>,
+    /// `...name` collects the remaining arguments, only allowed last
+    pub rest: bool,
+    /// Location of the name
+    pub span: Span,
+}
+
+/// One `name: ty = value` of a `let` declaring several names
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binding {
+    pub name: String,
+    pub ty: Option<String>,
+    pub value: Expr,
+    /// Location of the name
+    pub span: Span,
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Stmt {
-    /// let name = value;
-    Let { name: String, value: Expr },
+    /// let name: ty = value;
+    Let {
+        name: String,
+        ty: Option<String>,
+        value: Expr,
+        /// Comments directly above the declaration, without their delimiters
+        leading_comments: Vec<String>,
+        /// Whole declaration, from `let` to the terminator
+        span: Span,
+    },
 
-    /// const name = value;
-    Const { name: String, value: Expr },
+    /// let a = 1, b: ty = 2, c;
+    LetMulti {
+        /// At least two bindings, a single one is parsed as `Let`
+        bindings: Vec<Binding>,
+        leading_comments: Vec<String>,
+        span: Span,
+    },
 
-    /// fn name(params) { body }
+    /// const name: ty = value;
+    Const {
+        name: String,
+        ty: Option<String>,
+        value: Expr,
+        leading_comments: Vec<String>,
+        span: Span,
+    },
+
+    /// fn name(params): return_ty { body }
     Function {
         name: String,
-        params: Vec<String>,
+        params: Vec<Param>,
+        return_ty: Option<String>,
         body: Box<Stmt>,
+        leading_comments: Vec<String>,
+        span: Span,
     },
 
     /// if condition { then_branch } else { else_branch }
@@ -26,74 +73,239 @@ pub enum Stmt {
         condition: Expr,
         then_branch: Box<Stmt>,
         else_branch: Option<Box<Stmt>>,
+        /// From `if` to the end of the last branch
+        span: Span,
     },
 
     /// return value;
-    Return { value: Option<Expr> },
+    Return { value: Option<Expr>, span: Span },
 
     /// { statements }
-    Block { statements: Vec<Stmt> },
+    Block { statements: Vec<Stmt>, span: Span },
 
     /// expr;
-    Expression { expr: Expr },
-
-    /// import "module" or import module;
-    Import { module: String },
+    Expression { expr: Expr, span: Span },
+
+    /// import "module" as alias; or import { name as alias } from "module";
+    Import {
+        module: String,
+        /// Imported names with the local alias they are bound to, if any
+        names: Vec<(String, Option<String>)>,
+        alias: Option<String>,
+        span: Span,
+    },
 
     /// export statement;
     Export { statement: Box<Stmt> },
 
-    /// for future loop constructs
-    While { condition: Expr, body: Box<Stmt> },
+    /// export default expr;
+    ExportDefault { expr: Expr, span: Span },
 
-    /// for future loop constructs
+    /// label: while condition { body }
+    While {
+        /// Name that `break` and `continue` can target from nested loops
+        label: Option<String>,
+        condition: Expr,
+        body: Box<Stmt>,
+        /// From the label, or `while` without one, to the end of the body
+        span: Span,
+    },
+
+    /// label: for (init; condition; update, ...) { body }
     For {
+        label: Option<String>,
         init: Option<Box<Stmt>>,
         condition: Option<Expr>,
-        update: Option<Expr>,
+        /// Evaluated in order after each iteration, empty when omitted
+        update: Vec<Expr>,
         body: Box<Stmt>,
+        span: Span,
     },
 
-    /// break;
-    Break,
+    /// break label;
+    Break { label: Option<String>, span: Span },
 
-    /// continue;
-    Continue,
+    /// continue label;
+    Continue { label: Option<String>, span: Span },
+
+    /// A lone `;`
+    Empty { span: Span },
 }
 
 impl Stmt {
-    fn parse_let(parser: &mut Parser) -> Result<Self, ParseError> {
-        let source = parser.source;
+    /// Where the statement is in the source, an export is located by what it exports
+    pub fn span(&self) -> &Span {
+        match self {
+            Stmt::Let { span, .. }
+            | Stmt::LetMulti { span, .. }
+            | Stmt::Const { span, .. }
+            | Stmt::Function { span, .. }
+            | Stmt::If { span, .. }
+            | Stmt::Return { span, .. }
+            | Stmt::Block { span, .. }
+            | Stmt::Expression { span, .. }
+            | Stmt::Import { span, .. }
+            | Stmt::ExportDefault { span, .. }
+            | Stmt::While { span, .. }
+            | Stmt::For { span, .. }
+            | Stmt::Break { span, .. }
+            | Stmt::Continue { span, .. }
+            | Stmt::Empty { span } => span,
+            Stmt::Export { statement } => statement.span(),
+        }
+    }
 
+    /// Attach comments to a declaration, statements without a place for them drop them
+    fn with_leading_comments(mut self, mut comments: Vec<String>) -> Self {
+        match &mut self {
+            Stmt::Let {
+                leading_comments, ..
+            }
+            | Stmt::LetMulti {
+                leading_comments, ..
+            }
+            | Stmt::Const {
+                leading_comments, ..
+            }
+            | Stmt::Function {
+                leading_comments, ..
+            } => {
+                comments.append(leading_comments);
+                *leading_comments = comments;
+                self
+            }
+            Stmt::Export { statement } => {
+                let empty = Stmt::Empty {
+                    span: Span::default(),
+                };
+                let inner = std::mem::replace(&mut **statement, empty);
+                **statement = inner.with_leading_comments(comments);
+                self
+            }
+            _ => self,
+        }
+    }
+
+    /// Whether the parser is at `label:`, which can only prefix a loop
+    pub(crate) fn is_label_start(parser: &Parser) -> bool {
+        matches!(parser.peek_nth(0), Some(Token::Identifier(_)))
+            && parser.peek_nth(1) == Some(&Token::Colon)
+    }
+
+    /// Whether `token` starts a statement other than an expression statement
+    pub(crate) fn is_statement_start(token: &Token) -> bool {
+        matches!(
+            token,
+            Token::Let
+                | Token::Const
+                | Token::Fn
+                | Token::If
+                | Token::While
+                | Token::For
+                | Token::Break
+                | Token::Continue
+                | Token::Return
+                | Token::LeftBrace
+                | Token::Import
+                | Token::Export
+        )
+    }
+
+    fn parse_let(parser: &mut Parser) -> Result<Self, ParseError> {
         parser.safe_call(|parser| {
+            let start = parser.position();
             parser.expect(Token::Let)?;
 
-            let name = match parser.advance() {
-                Some(token_span) => match &token_span.token {
-                    Token::Identifier(name) => name.clone(),
-                    _ => {
-                        return Err(ParseError::UnexpectedToken {
-                            expected: Some("identifier".to_string()),
-                            found: format!("{:?}", token_span.token),
-                            span: token_span.span.clone(),
-                            context: ParseContext::from_span(source, &token_span.span),
-                        });
-                    }
-                },
-                None => return Err(parser.error("", Some("identifier"))),
-            };
+            let mut bindings = Vec::new();
 
-            let value = match parser.peek() {
-                Some(Token::Equal) => {
-                    parser.expect(Token::Equal)?;
-                    Expr::parse(parser)?
+            loop {
+                bindings.push(Self::parse_binding(parser)?);
+
+                if !parser.consume(&Token::Comma) {
+                    break;
                 }
-                _ => Expr::Literal(Value::Null),
-            };
 
-            parser.expect(Token::Semicolon)?;
+                // Handle trailing comma if configured
+                if parser.config.allow_trailing_commas() && parser.peek() == Some(&Token::Semicolon)
+                {
+                    break;
+                }
+            }
+
+            parser.expect_terminator()?;
+
+            let span = parser.span_from(start).into();
+
+            match <[Binding; 1]>::try_from(bindings) {
+                Ok([binding]) => Ok(Stmt::Let {
+                    name: binding.name,
+                    ty: binding.ty,
+                    value: binding.value,
+                    leading_comments: Vec::new(),
+                    span,
+                }),
+                Err(bindings) => Ok(Stmt::LetMulti {
+                    bindings,
+                    leading_comments: Vec::new(),
+                    span,
+                }),
+            }
+        })
+    }
+
+    /// Error for a token where a name was expected, explaining reserved keywords
+    fn name_error(source: &str, token_span: &TokenSpan, expected: &str) -> ParseError {
+        match token_span.token.keyword() {
+            Some(keyword) => ParseError::InvalidSyntax {
+                message: format!(
+                    "'{keyword}' is a reserved keyword and cannot be used as an identifier"
+                ),
+                span: token_span.span.clone(),
+                context: ParseContext::from_span(source, &token_span.span),
+            },
+            None => ParseError::UnexpectedToken {
+                expected: Some(expected.to_string()),
+                found: format!("{:?}", token_span.token),
+                span: token_span.span.clone(),
+                context: ParseContext::from_span(source, &token_span.span),
+            },
+        }
+    }
+
+    /// `name: ty = value` of a `let`, defaulting to `null` without an initializer
+    fn parse_binding(parser: &mut Parser) -> Result<Binding, ParseError> {
+        let source = parser.source;
+
+        let (name, span) = match parser.advance() {
+            Some(token_span) => match &token_span.token {
+                Token::Identifier(name) => (name.clone(), token_span.span.clone()),
+                _ => return Err(Self::name_error(source, token_span, "identifier")),
+            },
+            None => return Err(parser.error("", Some("identifier"))),
+        };
 
-            Ok(Stmt::Let { name, value })
+        let ty = Self::parse_type_annotation(parser)?;
+
+        let value = match parser.peek() {
+            Some(Token::Equal) => {
+                parser.expect(Token::Equal)?;
+                Expr::parse(parser)?
+            }
+            _ if parser.config.require_let_initializer() => {
+                return Err(ParseError::InvalidSyntax {
+                    message: format!("variable '{name}' must be initialized"),
+                    context: ParseContext::from_span(source, &span),
+                    span,
+                });
+            }
+            _ => Expr::Literal(Value::Null),
+        };
+
+        Ok(Binding {
+            name,
+            ty,
+            value,
+            span: span.into(),
         })
     }
 
@@ -101,29 +313,31 @@ impl Stmt {
         let source = parser.source;
 
         parser.safe_call(|parser| {
+            let start = parser.position();
             parser.expect(Token::Const)?;
 
             let name = match parser.advance() {
                 Some(token_span) => match &token_span.token {
                     Token::Identifier(name) => name.clone(),
-                    _ => {
-                        return Err(ParseError::UnexpectedToken {
-                            expected: Some("identifier".to_string()),
-                            found: format!("{:?}", token_span.token),
-                            span: token_span.span.clone(),
-                            context: ParseContext::from_span(source, &token_span.span),
-                        });
-                    }
+                    _ => return Err(Self::name_error(source, token_span, "identifier")),
                 },
                 None => return Err(parser.error("", Some("identifier"))),
             };
 
+            let ty = Self::parse_type_annotation(parser)?;
+
             parser.expect(Token::Equal)?;
             let value = Expr::parse(parser)?;
 
-            parser.expect(Token::Semicolon)?;
+            parser.expect_terminator()?;
 
-            Ok(Stmt::Const { name, value })
+            Ok(Stmt::Const {
+                name,
+                ty,
+                value,
+                leading_comments: Vec::new(),
+                span: parser.span_from(start).into(),
+            })
         })
     }
 
@@ -131,59 +345,104 @@ impl Stmt {
         let source = parser.source;
 
         parser.safe_call(|parser| {
+            let start = parser.position();
             parser.expect(Token::Fn)?;
 
             let name = match parser.advance() {
                 Some(token_span) => match &token_span.token {
                     Token::Identifier(name) => name.clone(),
-                    _ => {
-                        return Err(ParseError::UnexpectedToken {
-                            expected: Some("function name".to_string()),
-                            found: format!("{:?}", token_span.token),
-                            span: token_span.span.clone(),
-                            context: ParseContext::from_span(source, &token_span.span),
-                        });
-                    }
+                    _ => return Err(Self::name_error(source, token_span, "function name")),
                 },
                 None => return Err(parser.error("", Some("function name"))),
             };
 
-            parser.expect(Token::LeftParen)?;
-            let params = Self::parse_parameter_list(parser)?;
-
-            parser.expect(Token::RightParen)?;
-            let body = Self::parse_block(parser)?;
+            let params = parser.delimited(Token::LeftParen, |parser| {
+                let params = Self::parse_parameter_list(parser)?;
+
+                parser.expect(Token::RightParen)?;
+                Ok(params)
+            })?;
+            let return_ty = Self::parse_type_annotation(parser)?;
+
+            // `fn double(x) => x * 2;` is shorthand for a body that returns `x * 2`
+            let body = match parser.consume(&Token::FatArrow) {
+                true => {
+                    let start = parser.position();
+                    let value = Expr::parse(parser)?;
+                    parser.expect_terminator()?;
+                    let span = Span::from(parser.span_from(start));
+
+                    Stmt::Block {
+                        statements: vec![Stmt::Return {
+                            value: Some(value),
+                            span: span.clone(),
+                        }],
+                        span,
+                    }
+                }
+                false => Self::parse_block(parser)?,
+            };
 
             Ok(Stmt::Function {
                 name,
                 params,
+                return_ty,
                 body: Box::new(body),
+                leading_comments: Vec::new(),
+                span: parser.span_from(start).into(),
             })
         })
     }
 
-    fn parse_parameter_list(parser: &mut Parser) -> Result<Vec<String>, ParseError> {
-        let mut params = Vec::new();
+    fn parse_parameter_list(parser: &mut Parser) -> Result<Vec<Param>, ParseError> {
+        let mut params: Vec<Param> = Vec::new();
         let source = parser.source;
 
         while parser.peek() != Some(&Token::RightParen) {
-            match parser.advance() {
+            let rest = parser.consume(&Token::Ellipsis);
+
+            let (name, span) = match parser.advance() {
                 Some(token_span) => match &token_span.token {
-                    Token::Identifier(param) => {
-                        params.push(param.clone());
-                    }
-                    _ => {
-                        return Err(ParseError::UnexpectedToken {
-                            expected: Some("parameter name".to_string()),
-                            found: format!("{:?}", token_span.token),
-                            span: token_span.span.clone(),
-                            context: ParseContext::from_span(source, &token_span.span),
-                        });
-                    }
+                    Token::Identifier(param) => (param.clone(), token_span.span.clone()),
+                    _ => return Err(Self::name_error(source, token_span, "parameter name")),
                 },
                 None => return Err(parser.error("", Some("parameter name"))),
+            };
+
+            if params.last().is_some_and(|param| param.rest) {
+                return Err(ParseError::InvalidSyntax {
+                    message: format!("parameter '{name}' cannot follow a rest parameter"),
+                    context: ParseContext::from_span(source, &span),
+                    span,
+                });
             }
 
+            let ty = Self::parse_type_annotation(parser)?;
+
+            let default = match !rest && parser.consume(&Token::Equal) {
+                true => Some(Expr::parse(parser)?),
+                false => None,
+            };
+
+            // Defaults are only allowed on trailing parameters
+            if default.is_none() && !rest && params.iter().any(|param| param.default.is_some()) {
+                return Err(ParseError::InvalidSyntax {
+                    message: format!(
+                        "parameter '{name}' without a default cannot follow parameters with defaults"
+                    ),
+                    context: ParseContext::from_span(source, &span),
+                    span,
+                });
+            }
+
+            params.push(Param {
+                name,
+                ty,
+                default,
+                rest,
+                span: span.into(),
+            });
+
             match parser.peek() {
                 Some(Token::Comma) => {
                     parser.advance();
@@ -202,8 +461,31 @@ impl Stmt {
         Ok(params)
     }
 
+    /// Optional `: type` following a declared name
+    fn parse_type_annotation(parser: &mut Parser) -> Result<Option<String>, ParseError> {
+        let source = parser.source;
+
+        if !parser.consume(&Token::Colon) {
+            return Ok(None);
+        }
+
+        match parser.advance() {
+            Some(token_span) => match &token_span.token {
+                Token::Identifier(ty) => Ok(Some(ty.clone())),
+                _ => Err(ParseError::UnexpectedToken {
+                    expected: Some("type name".to_string()),
+                    found: format!("{:?}", token_span.token),
+                    span: token_span.span.clone(),
+                    context: ParseContext::from_span(source, &token_span.span),
+                }),
+            },
+            None => Err(parser.error("", Some("type name"))),
+        }
+    }
+
     fn parse_if(parser: &mut Parser) -> Result<Self, ParseError> {
         parser.safe_call(|parser| {
+            let start = parser.position();
             parser.expect(Token::If)?;
 
             let condition = Expr::parse(parser)?;
@@ -221,36 +503,48 @@ impl Stmt {
                 condition,
                 then_branch: Box::new(then_branch),
                 else_branch,
+                span: parser.span_from(start).into(),
             })
         })
     }
 
     fn parse_return(parser: &mut Parser) -> Result<Self, ParseError> {
         parser.safe_call(|parser| {
+            let start = parser.position();
             parser.expect(Token::Return)?;
 
             let value = match parser.peek() {
                 Some(Token::Semicolon) => None,
+                Some(_) if parser.implicit_terminator() => None,
                 Some(_) => Some(Expr::parse(parser)?),
                 None => None,
             };
 
-            parser.expect(Token::Semicolon)?;
-            Ok(Stmt::Return { value })
+            parser.expect_terminator()?;
+            Ok(Stmt::Return {
+                value,
+                span: parser.span_from(start).into(),
+            })
         })
     }
 
     fn parse_block(parser: &mut Parser) -> Result<Self, ParseError> {
         parser.safe_call(|parser| {
-            parser.expect(Token::LeftBrace)?;
-            let mut statements = Vec::new();
+            let start = parser.position();
 
-            while parser.peek() != Some(&Token::RightBrace) && !parser.eof() {
-                statements.push(Self::parse(parser)?);
-            }
+            parser.delimited(Token::LeftBrace, |parser| {
+                let mut statements = Vec::new();
 
-            parser.expect(Token::RightBrace)?;
-            Ok(Stmt::Block { statements })
+                while parser.peek() != Some(&Token::RightBrace) && !parser.eof() {
+                    statements.push(Self::parse(parser)?);
+                }
+
+                parser.expect(Token::RightBrace)?;
+                Ok(Stmt::Block {
+                    statements,
+                    span: parser.span_from(start).into(),
+                })
+            })
         })
     }
 
@@ -258,8 +552,18 @@ impl Stmt {
         let source = parser.source;
 
         parser.safe_call(|parser| {
+            let start = parser.position();
             parser.expect(Token::Import)?;
 
+            let names = match parser.peek() {
+                Some(Token::LeftBrace) => {
+                    let names = Self::parse_import_names(parser)?;
+                    parser.expect(Token::From)?;
+                    names
+                }
+                _ => Vec::new(),
+            };
+
             let module = match parser.advance() {
                 Some(token_span) => match &token_span.token {
                     Token::StringLiteral(module) => module.clone(),
@@ -276,15 +580,111 @@ impl Stmt {
                 None => return Err(parser.error("", Some("module name"))),
             };
 
-            parser.expect(Token::Semicolon)?;
-            Ok(Stmt::Import { module })
+            // Only a whole-module import can be aliased
+            let alias = match names.is_empty() && parser.consume(&Token::As) {
+                true => Some(Self::parse_import_alias(parser)?),
+                false => None,
+            };
+
+            parser.expect_terminator()?;
+            Ok(Stmt::Import {
+                module,
+                names,
+                alias,
+                span: parser.span_from(start).into(),
+            })
         })
     }
 
+    /// `{ name, ... }`, at least one name is required
+    fn parse_import_names(
+        parser: &mut Parser,
+    ) -> Result<Vec<(String, Option<String>)>, ParseError> {
+        let source = parser.source;
+        let mut names = Vec::new();
+
+        parser.expect(Token::LeftBrace)?;
+
+        loop {
+            match parser.advance() {
+                Some(token_span) => match &token_span.token {
+                    Token::Identifier(name) => {
+                        let name = name.clone();
+
+                        let alias = match parser.consume(&Token::As) {
+                            true => Some(Self::parse_import_alias(parser)?),
+                            false => None,
+                        };
+
+                        names.push((name, alias));
+                    }
+                    _ => {
+                        return Err(ParseError::UnexpectedToken {
+                            expected: Some("imported name".to_string()),
+                            found: format!("{:?}", token_span.token),
+                            span: token_span.span.clone(),
+                            context: ParseContext::from_span(source, &token_span.span),
+                        });
+                    }
+                },
+                None => return Err(parser.error("", Some("imported name"))),
+            }
+
+            match parser.peek() {
+                Some(Token::Comma) => {
+                    parser.advance();
+                    // Handle trailing comma if configured
+                    if parser.config.allow_trailing_commas()
+                        && parser.peek() == Some(&Token::RightBrace)
+                    {
+                        break;
+                    }
+                }
+                Some(Token::RightBrace) => break,
+                _ => return Err(parser.error("", Some("',' or '}'"))),
+            }
+        }
+
+        parser.expect(Token::RightBrace)?;
+        Ok(names)
+    }
+
+    fn parse_import_alias(parser: &mut Parser) -> Result<String, ParseError> {
+        let source = parser.source;
+
+        match parser.advance() {
+            Some(token_span) => match &token_span.token {
+                Token::Identifier(alias) => Ok(alias.clone()),
+                _ => Err(ParseError::UnexpectedToken {
+                    expected: Some("alias".to_string()),
+                    found: format!("{:?}", token_span.token),
+                    span: token_span.span.clone(),
+                    context: ParseContext::from_span(source, &token_span.span),
+                }),
+            },
+            None => Err(parser.error("", Some("alias"))),
+        }
+    }
+
     fn parse_export(parser: &mut Parser) -> Result<Self, ParseError> {
         parser.safe_call(|parser| {
+            let start = parser.position();
             parser.expect(Token::Export)?;
-            let statement = Self::parse(parser)?;
+
+            if parser.consume(&Token::Default) {
+                let expr = Expr::parse(parser)?;
+                parser.expect_terminator()?;
+                return Ok(Stmt::ExportDefault {
+                    expr,
+                    span: parser.span_from(start).into(),
+                });
+            }
+
+            // A lone `;` has nothing to export, so it is not an empty statement here
+            let statement = match parser.peek() {
+                Some(Token::Semicolon) => Self::parse_expression_stmt(parser)?,
+                _ => Self::parse(parser)?,
+            };
             Ok(Stmt::Export {
                 statement: Box::new(statement),
             })
@@ -293,27 +693,72 @@ impl Stmt {
 
     fn parse_expression_stmt(parser: &mut Parser) -> Result<Self, ParseError> {
         parser.safe_call(|parser| {
-            let expr = Expr::parse(parser)?;
-            parser.expect(Token::Semicolon)?;
-            Ok(Stmt::Expression { expr })
+            let start = parser.position();
+            let expr = Expr::parse_sequence(parser)?;
+            parser.expect_terminator()?;
+            Ok(Stmt::Expression {
+                expr,
+                span: parser.span_from(start).into(),
+            })
+        })
+    }
+
+    fn parse_labeled(parser: &mut Parser) -> Result<Self, ParseError> {
+        parser.safe_call(|parser| {
+            let start = parser.position();
+            let name = match parser.advance().map(|token_span| &token_span.token) {
+                Some(Token::Identifier(name)) => name.clone(),
+                _ => return Err(parser.error("", Some("label"))),
+            };
+
+            parser.expect(Token::Colon)?;
+
+            let mut stmt = match parser.peek() {
+                Some(Token::While) => Self::parse_while(parser)?,
+                Some(Token::For) => Self::parse_for(parser)?,
+                _ => return Err(parser.error("", Some("loop after label"))),
+            };
+
+            if let Stmt::While { label, span, .. } | Stmt::For { label, span, .. } = &mut stmt {
+                *label = Some(name);
+                *span = parser.span_from(start).into();
+            }
+
+            Ok(stmt)
         })
     }
 
+    /// Optional label after `break` or `continue`
+    fn parse_jump_label(parser: &mut Parser) -> Option<String> {
+        match parser.peek() {
+            Some(Token::Identifier(label)) if !parser.implicit_terminator() => {
+                let label = label.clone();
+                parser.advance();
+                Some(label)
+            }
+            _ => None,
+        }
+    }
+
     fn parse_while(parser: &mut Parser) -> Result<Self, ParseError> {
         parser.safe_call(|parser| {
+            let start = parser.position();
             parser.expect(Token::While)?;
             let condition = Expr::parse(parser)?;
             let body = Self::parse_block(parser)?;
 
             Ok(Stmt::While {
+                label: None,
                 condition,
                 body: Box::new(body),
+                span: parser.span_from(start).into(),
             })
         })
     }
 
     fn parse_for(parser: &mut Parser) -> Result<Self, ParseError> {
         parser.safe_call(|parser| {
+            let start = parser.position();
             parser.expect(Token::For)?;
             parser.expect(Token::LeftParen)?;
 
@@ -342,41 +787,69 @@ impl Stmt {
                 }
             };
 
-            // Parse update (optional)
-            let update = match parser.peek() {
-                Some(Token::RightParen) => None,
-                _ => Some(Expr::parse(parser)?),
-            };
+            // Parse updates (optional), separated by commas
+            let mut update = Vec::new();
+
+            if parser.peek() != Some(&Token::RightParen) {
+                update.push(Expr::parse(parser)?);
+
+                while parser.consume(&Token::Comma) {
+                    update.push(Expr::parse(parser)?);
+                }
+            }
 
             parser.expect(Token::RightParen)?;
             let body = Self::parse_block(parser)?;
 
             Ok(Stmt::For {
+                label: None,
                 init,
                 condition,
                 update,
                 body: Box::new(body),
+                span: parser.span_from(start).into(),
             })
         })
     }
 
     fn parse_break(parser: &mut Parser) -> Result<Self, ParseError> {
+        let start = parser.position();
         parser.expect(Token::Break)?;
-        parser.expect(Token::Semicolon)?;
-        Ok(Stmt::Break)
+        let label = Self::parse_jump_label(parser);
+        parser.expect_terminator()?;
+        Ok(Stmt::Break {
+            label,
+            span: parser.span_from(start).into(),
+        })
     }
 
     fn parse_continue(parser: &mut Parser) -> Result<Self, ParseError> {
+        let start = parser.position();
         parser.expect(Token::Continue)?;
+        let label = Self::parse_jump_label(parser);
+        parser.expect_terminator()?;
+        Ok(Stmt::Continue {
+            label,
+            span: parser.span_from(start).into(),
+        })
+    }
+
+    fn parse_empty(parser: &mut Parser) -> Result<Self, ParseError> {
+        let start = parser.position();
         parser.expect(Token::Semicolon)?;
-        Ok(Stmt::Continue)
+        Ok(Stmt::Empty {
+            span: parser.span_from(start).into(),
+        })
     }
 }
 
 // Implement Parse for Stmt enum
 impl Parse for Stmt {
     fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
-        match parser.peek() {
+        parser.count_statement()?;
+        let comments = parser.leading_comments();
+
+        let stmt = match parser.peek() {
             Some(Token::Let) => Self::parse_let(parser),
             Some(Token::Const) => Self::parse_const(parser),
             Some(Token::Fn) => Self::parse_function(parser),
@@ -387,9 +860,34 @@ impl Parse for Stmt {
             Some(Token::Continue) => Self::parse_continue(parser),
             Some(Token::Return) => Self::parse_return(parser),
             Some(Token::LeftBrace) => Self::parse_block(parser),
+            Some(Token::Semicolon) => Self::parse_empty(parser),
             Some(Token::Import) => Self::parse_import(parser),
             Some(Token::Export) => Self::parse_export(parser),
+            Some(Token::Identifier(_)) if Self::is_label_start(parser) => {
+                Self::parse_labeled(parser)
+            }
             _ => Self::parse_expression_stmt(parser),
+        }?;
+
+        Ok(stmt.with_leading_comments(comments))
+    }
+}
+
+/// Every statement of a source, in order
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Program(pub Vec<Stmt>);
+
+impl Parse for Program {
+    fn parse(parser: &mut Parser) -> Result<Self, ParseError> {
+        let mut statements = Vec::new();
+
+        while !parser.eof() {
+            statements.push(parser.next_statement()?);
+        }
+
+        match parser.take_halt_error() {
+            Some(error) => Err(error),
+            None => Ok(Program(statements)),
         }
     }
 }
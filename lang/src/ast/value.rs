@@ -1,9 +1,24 @@
+use std::{cmp::Ordering, collections::BTreeMap};
+
+/// Runtime value
+///
+/// `==` compares floats like IEEE 754 does: NaN is unequal to everything, itself
+/// included, and `0.0 == -0.0`. [`Value::is_identical`] tells those cases apart.
+/// Floats display as `NaN`, `inf`, `-inf` and `-0` for the special values, and
+/// NaN is falsy like both zeros.
+///
+/// Reading a key an object does not have is an error, through `.key` and `["key"]`
+/// alike, the same as indexing past the end of an array. Objects have no built-in
+/// members, so `.length` on one is an ordinary key lookup.
 #[derive(Debug, Clone, PartialEq)]
 pub enum Value {
     Int(i64),
     Float(f64),
     Bool(bool),
     Str(String),
+    Char(char),
+    Array(Vec<Value>),
+    Object(BTreeMap<String, Value>),
     Null,
 }
 
@@ -15,6 +30,9 @@ impl Value {
             Value::Float(_) => "float",
             Value::Bool(_) => "bool",
             Value::Str(_) => "string",
+            Value::Char(_) => "char",
+            Value::Array(_) => "array",
+            Value::Object(_) => "object",
             Value::Null => "null",
         }
     }
@@ -25,8 +43,12 @@ impl Value {
             Value::Bool(b) => *b,
             Value::Null => false,
             Value::Int(i) => *i != 0,
-            Value::Float(f) => *f != 0.0,
+            // NaN is no more a real number than zero is
+            Value::Float(f) => *f != 0.0 && !f.is_nan(),
             Value::Str(s) => !s.is_empty(),
+            Value::Char(c) => *c != '\0',
+            Value::Array(elements) => !elements.is_empty(),
+            Value::Object(fields) => !fields.is_empty(),
         }
     }
 
@@ -35,19 +57,26 @@ impl Value {
         Value::Bool(self.is_truthy())
     }
 
-    /// Try to convert to integer
+    /// Try to convert to integer, floats only when they are whole and within range
     pub fn to_int(&self) -> Option<i64> {
         match self {
             Value::Int(i) => Some(*i),
-            Value::Float(f) => Some(*f as i64),
+            // `i64::MAX as f64` rounds up to 2^63, which is already out of range
+            Value::Float(f)
+                if f.fract() == 0.0 && (i64::MIN as f64..i64::MAX as f64).contains(f) =>
+            {
+                Some(*f as i64)
+            }
+            Value::Float(_) => None,
             Value::Bool(true) => Some(1),
             Value::Bool(false) => Some(0),
             Value::Str(s) => s.parse().ok(),
-            Value::Null => None,
+            Value::Char(c) => Some(*c as i64),
+            Value::Array(_) | Value::Object(_) | Value::Null => None,
         }
     }
 
-    /// Try to convert to float
+    /// Try to convert to float, a char converts to its code point like in [`Value::to_int`]
     pub fn to_float(&self) -> Option<f64> {
         match self {
             Value::Int(i) => Some(*i as f64),
@@ -55,10 +84,52 @@ impl Value {
             Value::Bool(true) => Some(1.0),
             Value::Bool(false) => Some(0.0),
             Value::Str(s) => s.parse().ok(),
+            Value::Char(c) => Some(*c as u32 as f64),
+            Value::Array(_) => None,
+            Value::Object(_) => None,
             Value::Null => None,
         }
     }
 
+    /// Order two values like `<` and `>` do, failing for pairs that have no order,
+    /// such as mixed types, `null` or NaN
+    pub fn compare(&self, other: &Value) -> Result<Ordering, String> {
+        match self.partial_cmp(other) {
+            Some(ordering) => Ok(ordering),
+            None if self.is_nan() || other.is_nan() => Err("Cannot compare NaN".to_string()),
+            None => Err(format!(
+                "Cannot compare {} and {}",
+                self.type_name(),
+                other.type_name()
+            )),
+        }
+    }
+
+    /// Whether the value is a float NaN
+    pub fn is_nan(&self) -> bool {
+        matches!(self, Value::Float(f) if f.is_nan())
+    }
+
+    /// Whether two values cannot be told apart, unlike `==` every NaN is identical
+    /// to every other and `0.0` is not identical to `-0.0`
+    pub fn is_identical(&self, other: &Value) -> bool {
+        match (self, other) {
+            (Value::Float(a), Value::Float(b)) => {
+                (a.is_nan() && b.is_nan()) || a.to_bits() == b.to_bits()
+            }
+            (Value::Array(a), Value::Array(b)) => {
+                a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.is_identical(b))
+            }
+            (Value::Object(a), Value::Object(b)) => {
+                a.len() == b.len()
+                    && a.iter()
+                        .zip(b)
+                        .all(|((ka, a), (kb, b))| ka == kb && a.is_identical(b))
+            }
+            (a, b) => a == b,
+        }
+    }
+
     /// Convert to string representation
     pub fn to_string(&self) -> String {
         match self {
@@ -66,6 +137,8 @@ impl Value {
             Value::Float(f) => f.to_string(),
             Value::Bool(b) => b.to_string(),
             Value::Str(s) => s.clone(),
+            Value::Char(c) => c.to_string(),
+            Value::Array(_) | Value::Object(_) => format!("{self}"),
             Value::Null => "null".to_string(),
         }
     }
@@ -78,6 +151,27 @@ impl std::fmt::Display for Value {
             Value::Float(fl) => write!(f, "{}", fl),
             Value::Bool(b) => write!(f, "{}", b),
             Value::Str(s) => write!(f, "{}", s),
+            Value::Char(c) => write!(f, "{}", c),
+            Value::Array(elements) => {
+                write!(f, "[")?;
+                for (i, element) in elements.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}", element)?;
+                }
+                write!(f, "]")
+            }
+            Value::Object(fields) => {
+                write!(f, "{{")?;
+                for (i, (key, value)) in fields.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", key, value)?;
+                }
+                write!(f, "}}")
+            }
             Value::Null => write!(f, "null"),
         }
     }
@@ -89,11 +183,21 @@ impl std::ops::Add for Value {
 
     fn add(self, other: Value) -> Self::Output {
         match (self, other) {
-            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a + b)),
+            (Value::Int(a), Value::Int(b)) => a
+                .checked_add(b)
+                .map(Value::Int)
+                .ok_or_else(|| "Integer overflow".to_string()),
             (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a + b)),
             (Value::Int(a), Value::Float(b)) => Ok(Value::Float(a as f64 + b)),
             (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a + b as f64)),
             (Value::Str(a), Value::Str(b)) => Ok(Value::Str(a + &b)),
+            // Characters taken out of a string concatenate back into one
+            (Value::Str(mut a), Value::Char(b)) => {
+                a.push(b);
+                Ok(Value::Str(a))
+            }
+            (Value::Char(a), Value::Str(b)) => Ok(Value::Str(format!("{a}{b}"))),
+            (Value::Char(a), Value::Char(b)) => Ok(Value::Str(format!("{a}{b}"))),
             (a, b) => Err(format!(
                 "Cannot add {} and {}",
                 a.type_name(),
@@ -108,7 +212,10 @@ impl std::ops::Sub for Value {
 
     fn sub(self, other: Value) -> Self::Output {
         match (self, other) {
-            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a - b)),
+            (Value::Int(a), Value::Int(b)) => a
+                .checked_sub(b)
+                .map(Value::Int)
+                .ok_or_else(|| "Integer overflow".to_string()),
             (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a - b)),
             (Value::Int(a), Value::Float(b)) => Ok(Value::Float(a as f64 - b)),
             (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a - b as f64)),
@@ -126,7 +233,10 @@ impl std::ops::Mul for Value {
 
     fn mul(self, other: Value) -> Self::Output {
         match (self, other) {
-            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a * b)),
+            (Value::Int(a), Value::Int(b)) => a
+                .checked_mul(b)
+                .map(Value::Int)
+                .ok_or_else(|| "Integer overflow".to_string()),
             (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a * b)),
             (Value::Int(a), Value::Float(b)) => Ok(Value::Float(a as f64 * b)),
             (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a * b as f64)),
@@ -147,8 +257,10 @@ impl std::ops::Div for Value {
             (Value::Int(a), Value::Int(b)) => {
                 if b == 0 {
                     Err("Division by zero".to_string())
-                } else if a % b == 0 {
-                    Ok(Value::Int(a / b))
+                } else if a.wrapping_rem(b) == 0 {
+                    a.checked_div(b)
+                        .map(Value::Int)
+                        .ok_or_else(|| "Integer overflow".to_string())
                 } else {
                     Ok(Value::Float(a as f64 / b as f64))
                 }
@@ -183,15 +295,271 @@ impl std::ops::Div for Value {
     }
 }
 
+impl std::ops::Rem for Value {
+    type Output = Result<Value, String>;
+
+    fn rem(self, other: Value) -> Self::Output {
+        match (self, other) {
+            (Value::Int(_), Value::Int(0)) => Err("Division by zero".to_string()),
+            // `i64::MIN % -1` is 0 even though `i64::MIN / -1` overflows
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a.wrapping_rem(b))),
+            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a % b)),
+            (Value::Int(a), Value::Float(b)) => Ok(Value::Float(a as f64 % b)),
+            (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a % b as f64)),
+            (a, b) => Err(format!(
+                "Cannot take the remainder of {} by {}",
+                a.type_name(),
+                b.type_name()
+            )),
+        }
+    }
+}
+
+impl std::ops::Neg for Value {
+    type Output = Result<Value, String>;
+
+    fn neg(self) -> Self::Output {
+        match self {
+            Value::Int(a) => a
+                .checked_neg()
+                .map(Value::Int)
+                .ok_or_else(|| "Integer overflow".to_string()),
+            Value::Float(a) => Ok(Value::Float(-a)),
+            a => Err(format!("Cannot negate {}", a.type_name())),
+        }
+    }
+}
+
+impl std::ops::Not for Value {
+    type Output = Result<Value, String>;
+
+    /// Bitwise complement, logical negation goes through `is_truthy`
+    fn not(self) -> Self::Output {
+        match self {
+            Value::Int(a) => Ok(Value::Int(!a)),
+            a => Err(format!("Cannot apply '~' to {}", a.type_name())),
+        }
+    }
+}
+
+impl Value {
+    /// `+` with integers wrapping around on overflow instead of failing
+    pub fn wrapping_add(self, other: Value) -> Result<Value, String> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a.wrapping_add(b))),
+            (a, b) => a + b,
+        }
+    }
+
+    /// `-` with integers wrapping around on overflow instead of failing
+    pub fn wrapping_sub(self, other: Value) -> Result<Value, String> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a.wrapping_sub(b))),
+            (a, b) => a - b,
+        }
+    }
+
+    /// `*` with integers wrapping around on overflow instead of failing
+    pub fn wrapping_mul(self, other: Value) -> Result<Value, String> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a.wrapping_mul(b))),
+            (a, b) => a * b,
+        }
+    }
+
+    /// Element at `index`, counting characters for strings
+    pub fn index(&self, index: &Value) -> Result<Value, String> {
+        match (self, index) {
+            (Value::Array(elements), Value::Int(i)) => usize::try_from(*i)
+                .ok()
+                .and_then(|i| elements.get(i))
+                .cloned()
+                .ok_or_else(|| format!("Index {i} is out of bounds for length {}", elements.len())),
+            (Value::Object(_), Value::Str(key)) => self.member(key),
+            (Value::Object(_), index) => {
+                Err(format!("Cannot index object with {}", index.type_name()))
+            }
+            (Value::Array(_), index) => {
+                Err(format!("Cannot index array with {}", index.type_name()))
+            }
+            (Value::Str(s), Value::Int(i)) => usize::try_from(*i)
+                .ok()
+                .and_then(|i| s.chars().nth(i))
+                .map(Value::Char)
+                .ok_or_else(|| {
+                    format!(
+                        "Index {i} is out of bounds for length {}",
+                        s.chars().count()
+                    )
+                }),
+            (Value::Str(_), index) => {
+                Err(format!("Cannot index string with {}", index.type_name()))
+            }
+            (object, _) => Err(format!("Cannot index {}", object.type_name())),
+        }
+    }
+
+    /// Value of the `.name` member
+    pub fn member(&self, name: &str) -> Result<Value, String> {
+        match (self, name) {
+            (Value::Str(s), "length") => Ok(Value::Int(s.chars().count() as i64)),
+            (Value::Array(elements), "length") => Ok(Value::Int(elements.len() as i64)),
+            (Value::Object(fields), key) => fields
+                .get(key)
+                .cloned()
+                .ok_or_else(|| format!("Object has no key '{key}'")),
+            (object, name) => Err(format!("{} has no member '{name}'", object.type_name())),
+        }
+    }
+
+    /// Unary plus, numbers pass through and strings are parsed as numbers
+    pub fn to_number(self) -> Result<Value, String> {
+        match self {
+            Value::Int(_) | Value::Float(_) => Ok(self),
+            Value::Str(s) => match (s.trim().parse::<i64>(), s.trim().parse::<f64>()) {
+                (Ok(i), _) => Ok(Value::Int(i)),
+                (_, Ok(f)) => Ok(Value::Float(f)),
+                _ => Err(format!("Cannot convert string '{s}' to a number")),
+            },
+            a => Err(format!("Cannot apply '+' to {}", a.type_name())),
+        }
+    }
+
+    /// Divide and round toward negative infinity, integers stay integers
+    pub fn floor_div(self, other: Value) -> Result<Value, String> {
+        match (self, other) {
+            (Value::Int(_), Value::Int(0)) => Err("Division by zero".to_string()),
+            (Value::Int(a), Value::Int(b)) => {
+                let quotient = a.checked_div(b).ok_or("Integer overflow")?;
+
+                // Truncation rounds toward zero, step down when the signs differ
+                match a % b != 0 && (a < 0) != (b < 0) {
+                    true => Ok(Value::Int(quotient - 1)),
+                    false => Ok(Value::Int(quotient)),
+                }
+            }
+            (a, b) => match (a / b)? {
+                Value::Float(quotient) => Ok(Value::Float(quotient.floor())),
+                quotient => Ok(quotient),
+            },
+        }
+    }
+
+    /// Raise to a power, integers stay integers unless the exponent is negative
+    pub fn pow(self, other: Value) -> Result<Value, String> {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) if b >= 0 => u32::try_from(b)
+                .ok()
+                .and_then(|b| a.checked_pow(b))
+                .map(Value::Int)
+                .ok_or_else(|| "Integer overflow".to_string()),
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Float((a as f64).powf(b as f64))),
+            (Value::Float(a), Value::Float(b)) => Ok(Value::Float(a.powf(b))),
+            (Value::Int(a), Value::Float(b)) => Ok(Value::Float((a as f64).powf(b))),
+            (Value::Float(a), Value::Int(b)) => Ok(Value::Float(a.powf(b as f64))),
+            (a, b) => Err(format!(
+                "Cannot raise {} to {}",
+                a.type_name(),
+                b.type_name()
+            )),
+        }
+    }
+}
+
+// Bitwise operations
+impl std::ops::BitAnd for Value {
+    type Output = Result<Value, String>;
+
+    fn bitand(self, other: Value) -> Self::Output {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a & b)),
+            (a, b) => Err(format!(
+                "Cannot apply '&' to {} and {}",
+                a.type_name(),
+                b.type_name()
+            )),
+        }
+    }
+}
+
+impl std::ops::BitOr for Value {
+    type Output = Result<Value, String>;
+
+    fn bitor(self, other: Value) -> Self::Output {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a | b)),
+            (a, b) => Err(format!(
+                "Cannot apply '|' to {} and {}",
+                a.type_name(),
+                b.type_name()
+            )),
+        }
+    }
+}
+
+impl std::ops::BitXor for Value {
+    type Output = Result<Value, String>;
+
+    fn bitxor(self, other: Value) -> Self::Output {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => Ok(Value::Int(a ^ b)),
+            (a, b) => Err(format!(
+                "Cannot apply '^' to {} and {}",
+                a.type_name(),
+                b.type_name()
+            )),
+        }
+    }
+}
+
+impl std::ops::Shl for Value {
+    type Output = Result<Value, String>;
+
+    fn shl(self, other: Value) -> Self::Output {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => u32::try_from(b)
+                .ok()
+                .and_then(|b| a.checked_shl(b))
+                .map(Value::Int)
+                .ok_or_else(|| format!("Shift amount {b} is out of range")),
+            (a, b) => Err(format!(
+                "Cannot shift {} by {}",
+                a.type_name(),
+                b.type_name()
+            )),
+        }
+    }
+}
+
+impl std::ops::Shr for Value {
+    type Output = Result<Value, String>;
+
+    fn shr(self, other: Value) -> Self::Output {
+        match (self, other) {
+            (Value::Int(a), Value::Int(b)) => u32::try_from(b)
+                .ok()
+                .and_then(|b| a.checked_shr(b))
+                .map(Value::Int)
+                .ok_or_else(|| format!("Shift amount {b} is out of range")),
+            (a, b) => Err(format!(
+                "Cannot shift {} by {}",
+                a.type_name(),
+                b.type_name()
+            )),
+        }
+    }
+}
+
 // Comparison operations
 impl std::cmp::PartialOrd for Value {
-    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         match (self, other) {
             (Value::Int(a), Value::Int(b)) => a.partial_cmp(b),
             (Value::Float(a), Value::Float(b)) => a.partial_cmp(b),
             (Value::Int(a), Value::Float(b)) => (*a as f64).partial_cmp(b),
             (Value::Float(a), Value::Int(b)) => a.partial_cmp(&(*b as f64)),
             (Value::Str(a), Value::Str(b)) => a.partial_cmp(b),
+            (Value::Char(a), Value::Char(b)) => a.partial_cmp(b),
             (Value::Bool(a), Value::Bool(b)) => a.partial_cmp(b),
             _ => None,
         }
@@ -0,0 +1,625 @@
+use std::{
+    cell::RefCell,
+    collections::{BTreeMap, HashMap},
+    io::Write,
+    rc::Rc,
+};
+
+use crate::{
+    ast::{
+        expr::Expr,
+        op::{BinaryOp, UnaryOp},
+        stmt::{Param, Stmt},
+        value::Value,
+    },
+    parser::ParserConfig,
+};
+
+/// Evaluate an expression to a value in an empty environment
+pub fn eval_expr(expr: &Expr) -> Result<Value, String> {
+    Environment::new().eval_expr(expr)
+}
+
+/// How control leaves a statement
+#[derive(Debug, Clone, PartialEq)]
+pub enum Flow {
+    Normal,
+    /// Leave the loop with the given label, or the innermost one
+    Break(Option<String>),
+    Continue(Option<String>),
+    Return(Value),
+}
+
+impl Flow {
+    /// Whether a break or continue is aimed at a loop labeled `label`
+    fn targets(target: &Option<String>, label: &Option<String>) -> bool {
+        target.is_none() || target == label
+    }
+}
+
+#[derive(Debug)]
+struct Binding {
+    value: Value,
+    constant: bool,
+}
+
+#[derive(Debug)]
+struct Function {
+    params: Vec<Param>,
+    body: Stmt,
+}
+
+/// Function implemented in Rust, receiving the evaluated arguments
+pub type NativeFn = dyn Fn(&[Value]) -> Result<Value, String>;
+
+#[derive(Clone)]
+enum Callable {
+    Script(Rc<Function>),
+    Native(Rc<NativeFn>),
+}
+
+impl std::fmt::Debug for Callable {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Callable::Script(function) => function.fmt(f),
+            Callable::Native(_) => write!(f, "<native>"),
+        }
+    }
+}
+
+/// Scoped variable storage the evaluator runs against, innermost scope last
+///
+/// Functions live in a single global table, and a call only sees the global
+/// scope plus its own parameters.
+#[derive(Debug)]
+pub struct Environment {
+    scopes: Vec<HashMap<String, Binding>>,
+    functions: HashMap<String, Callable>,
+    /// Script function calls currently running
+    depth: usize,
+    max_depth: usize,
+    /// Let `+`, `-`, `*`, `++` and `--` wrap around on integer overflow instead of failing
+    wrapping: bool,
+}
+
+impl Default for Environment {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Environment {
+    /// Environment whose `print` writes to stdout
+    pub fn new() -> Self {
+        Self::with_output(Rc::new(RefCell::new(std::io::stdout())))
+    }
+
+    /// Environment whose `print` writes to `output`, e.g. a shared buffer in tests
+    pub fn with_output<W: Write + 'static>(output: Rc<RefCell<W>>) -> Self {
+        let mut env = Self {
+            scopes: vec![HashMap::new()],
+            functions: HashMap::new(),
+            depth: 0,
+            max_depth: ParserConfig::default().max_recursion_depth(),
+            wrapping: false,
+        };
+
+        env.register("print", move |args| {
+            let line = args
+                .iter()
+                .map(|arg| arg.to_string())
+                .collect::<Vec<_>>()
+                .join(" ");
+
+            writeln!(output.borrow_mut(), "{line}")
+                .map_err(|err| format!("print failed: {err}"))?;
+            Ok(Value::Null)
+        });
+
+        env
+    }
+
+    /// Wrap integer `+`, `-`, `*`, `++` and `--` around on overflow, rather than
+    /// failing with "Integer overflow"
+    pub fn wrapping_arithmetic(mut self, wrapping: bool) -> Self {
+        self.wrapping = wrapping;
+        self
+    }
+
+    /// Fail with "Maximum recursion depth exceeded" once script function calls nest
+    /// deeper than `depth`, by default the parser's recursion limit. Every call takes
+    /// several kilobytes of native stack, so lower it for threads with a small stack
+    pub fn max_recursion_depth(mut self, depth: usize) -> Self {
+        self.max_depth = depth;
+        self
+    }
+
+    /// Make a Rust function callable from scripts, replacing any function of the same name
+    pub fn register(
+        &mut self,
+        name: &str,
+        function: impl Fn(&[Value]) -> Result<Value, String> + 'static,
+    ) {
+        self.functions
+            .insert(name.to_string(), Callable::Native(Rc::new(function)));
+    }
+
+    /// Look up a variable, searching from the innermost scope outwards
+    pub fn get(&self, name: &str) -> Option<&Value> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.get(name))
+            .map(|binding| &binding.value)
+    }
+
+    /// Declare a variable in the innermost scope, shadowing any outer one
+    pub fn define(&mut self, name: &str, value: Value, constant: bool) {
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.insert(name.to_string(), Binding { value, constant });
+        }
+    }
+
+    /// Update the nearest declaration of a variable
+    pub fn assign(&mut self, name: &str, value: Value) -> Result<(), String> {
+        match self
+            .scopes
+            .iter_mut()
+            .rev()
+            .find_map(|scope| scope.get_mut(name))
+        {
+            Some(binding) if binding.constant => Err(format!("Cannot assign to constant '{name}'")),
+            Some(binding) => {
+                binding.value = value;
+                Ok(())
+            }
+            None => Err(format!("Undefined variable '{name}'")),
+        }
+    }
+
+    /// Run `f` in a fresh scope that is dropped afterwards, even on error
+    fn scoped<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T, String>) -> Result<T, String> {
+        self.scopes.push(HashMap::new());
+        let result = f(self);
+        self.scopes.pop();
+        result
+    }
+
+    /// Execute a statement, reporting how control left it
+    pub fn exec(&mut self, stmt: &Stmt) -> Result<Flow, String> {
+        match stmt {
+            Stmt::Let { name, value, .. } => self.exec_declaration(name, value, false),
+            Stmt::LetMulti { bindings, .. } => {
+                for binding in bindings {
+                    self.exec_declaration(&binding.name, &binding.value, false)?;
+                }
+
+                Ok(Flow::Normal)
+            }
+            Stmt::Const { name, value, .. } => self.exec_declaration(name, value, true),
+            Stmt::Function {
+                name, params, body, ..
+            } => {
+                let function = Function {
+                    params: params.clone(),
+                    body: (**body).clone(),
+                };
+
+                self.functions
+                    .insert(name.clone(), Callable::Script(Rc::new(function)));
+                Ok(Flow::Normal)
+            }
+            Stmt::Expression { expr, .. } => self.eval_expr(expr).map(|_| Flow::Normal),
+            Stmt::Block { statements, .. } => self.scoped(|env| env.exec_all(statements)),
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+                ..
+            } => self.exec_if(condition, then_branch, else_branch.as_deref()),
+            Stmt::While {
+                label,
+                condition,
+                body,
+                ..
+            } => self.exec_while(label, condition, body),
+            Stmt::For {
+                label,
+                init,
+                condition,
+                update,
+                body,
+                ..
+            } => self.scoped(|env| {
+                env.exec_for(label, init.as_deref(), condition.as_ref(), update, body)
+            }),
+            Stmt::Break { label, .. } => Ok(Flow::Break(label.clone())),
+            Stmt::Continue { label, .. } => Ok(Flow::Continue(label.clone())),
+            Stmt::Empty { .. } => Ok(Flow::Normal),
+            Stmt::Return { value, .. } => match value {
+                Some(value) => self.eval_expr(value).map(Flow::Return),
+                None => Ok(Flow::Return(Value::Null)),
+            },
+            Stmt::Export { statement } => self.exec(statement),
+            Stmt::ExportDefault { expr, .. } => self.eval_expr(expr).map(|_| Flow::Normal),
+            Stmt::Import { .. } => Err("Imports are not supported by the evaluator".to_string()),
+        }
+    }
+
+    /// Execute statements in order, stopping at the first that leaves abnormally
+    pub fn exec_all(&mut self, statements: &[Stmt]) -> Result<Flow, String> {
+        for stmt in statements {
+            match self.exec(stmt)? {
+                Flow::Normal => (),
+                flow => return Ok(flow),
+            }
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    fn exec_declaration(
+        &mut self,
+        name: &str,
+        value: &Expr,
+        constant: bool,
+    ) -> Result<Flow, String> {
+        let value = self.eval_expr(value)?;
+        self.define(name, value, constant);
+        Ok(Flow::Normal)
+    }
+
+    fn exec_if(
+        &mut self,
+        condition: &Expr,
+        then_branch: &Stmt,
+        else_branch: Option<&Stmt>,
+    ) -> Result<Flow, String> {
+        match (self.eval_expr(condition)?.is_truthy(), else_branch) {
+            (true, _) => self.exec(then_branch),
+            (false, Some(else_branch)) => self.exec(else_branch),
+            (false, None) => Ok(Flow::Normal),
+        }
+    }
+
+    fn exec_while(
+        &mut self,
+        label: &Option<String>,
+        condition: &Expr,
+        body: &Stmt,
+    ) -> Result<Flow, String> {
+        while self.eval_expr(condition)?.is_truthy() {
+            match self.exec(body)? {
+                Flow::Break(target) if Flow::targets(&target, label) => break,
+                Flow::Continue(target) if Flow::targets(&target, label) => (),
+                Flow::Normal => (),
+                flow => return Ok(flow),
+            }
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    fn exec_for(
+        &mut self,
+        label: &Option<String>,
+        init: Option<&Stmt>,
+        condition: Option<&Expr>,
+        update: &[Expr],
+        body: &Stmt,
+    ) -> Result<Flow, String> {
+        if let Some(init) = init {
+            self.exec(init)?;
+        }
+
+        loop {
+            if let Some(condition) = condition
+                && !self.eval_expr(condition)?.is_truthy()
+            {
+                break;
+            }
+
+            match self.exec(body)? {
+                Flow::Break(target) if Flow::targets(&target, label) => break,
+                Flow::Continue(target) if Flow::targets(&target, label) => (),
+                Flow::Normal => (),
+                flow => return Ok(flow),
+            }
+
+            for update in update {
+                self.eval_expr(update)?;
+            }
+        }
+
+        Ok(Flow::Normal)
+    }
+
+    /// Call a declared or registered function with already evaluated arguments
+    pub fn call(&mut self, name: &str, args: Vec<Value>) -> Result<Value, String> {
+        let function = match self.functions.get(name).cloned() {
+            Some(Callable::Script(function)) => function,
+            Some(Callable::Native(function)) => return function(&args),
+            None => return Err(format!("Undefined function '{name}'")),
+        };
+
+        // A rest parameter takes any number of extra arguments
+        let rest = function.params.last().is_some_and(|param| param.rest);
+
+        if !rest && args.len() > function.params.len() {
+            return Err(format!(
+                "Function '{name}' expects at most {} argument(s), got {}",
+                function.params.len(),
+                args.len()
+            ));
+        }
+
+        if self.depth >= self.max_depth {
+            return Err(format!(
+                "Maximum recursion depth of {} exceeded",
+                self.max_depth
+            ));
+        }
+
+        // The callee sees the globals and its parameters, not the caller's locals
+        let locals = self.scopes.split_off(1);
+        self.depth += 1;
+        let result = self.scoped(|env| env.call_function(name, &function, args));
+        self.depth -= 1;
+        self.scopes.extend(locals);
+        result
+    }
+
+    fn call_function(
+        &mut self,
+        name: &str,
+        function: &Function,
+        args: Vec<Value>,
+    ) -> Result<Value, String> {
+        let mut args = args.into_iter();
+
+        for param in &function.params {
+            if param.rest {
+                self.define(&param.name, Value::Array(args.by_ref().collect()), false);
+                continue;
+            }
+
+            let value = match (args.next(), &param.default) {
+                (Some(value), _) => value,
+                (None, Some(default)) => self.eval_expr(default)?,
+                (None, None) => {
+                    return Err(format!("Missing argument '{}' for '{name}'", param.name));
+                }
+            };
+
+            self.define(&param.name, value, false);
+        }
+
+        match self.exec(&function.body)? {
+            Flow::Return(value) => Ok(value),
+            Flow::Normal => Ok(Value::Null),
+            Flow::Break(_) | Flow::Continue(_) => Err(format!(
+                "'break' or 'continue' outside of a loop in '{name}'"
+            )),
+        }
+    }
+
+    /// Evaluate an expression to a value
+    pub fn eval_expr(&mut self, expr: &Expr) -> Result<Value, String> {
+        match expr {
+            Expr::Literal(value) => Ok(value.clone()),
+            Expr::Variable(name) => self
+                .get(name)
+                .cloned()
+                .ok_or_else(|| format!("Undefined variable '{name}'")),
+            Expr::Group(inner) => self.eval_expr(inner),
+            Expr::Sequence(exprs) => exprs
+                .iter()
+                .try_fold(Value::Null, |_, expr| self.eval_expr(expr)),
+            Expr::Block { statements, tail } => {
+                self.scoped(|env| env.eval_block(statements, tail.as_deref()))
+            }
+            Expr::Unary { op, operand } => self.eval_unary(op, operand),
+            Expr::Binary { op, left, right } => self.eval_binary(op, left, right),
+            Expr::Assignment { target, value } => self.eval_assignment(target, None, value),
+            Expr::CompoundAssignment { target, op, value } => {
+                self.eval_assignment(target, Some(op), value)
+            }
+            Expr::Call { callee, args, .. } => self.eval_call(callee, args),
+            Expr::PreIncrement { operand } => self.step(operand, BinaryOp::Add, false),
+            Expr::PreDecrement { operand } => self.step(operand, BinaryOp::Sub, false),
+            Expr::PostIncrement { operand } => self.step(operand, BinaryOp::Add, true),
+            Expr::PostDecrement { operand } => self.step(operand, BinaryOp::Sub, true),
+            Expr::Array { elements } => self.eval_list(elements).map(Value::Array),
+            Expr::Object { entries } => {
+                let mut fields = BTreeMap::new();
+
+                // A repeated key keeps its last value
+                for (key, value) in entries {
+                    fields.insert(key.clone(), self.eval_expr(value)?);
+                }
+
+                Ok(Value::Object(fields))
+            }
+            Expr::Index { object, index } => {
+                let object = self.eval_expr(object)?;
+                object.index(&self.eval_expr(index)?)
+            }
+            Expr::Member {
+                object,
+                property,
+                optional,
+            } => match self.eval_expr(object)? {
+                Value::Null if *optional => Ok(Value::Null),
+                object => object.member(property),
+            },
+            _ => Err(format!("Cannot evaluate {}", describe(expr))),
+        }
+    }
+
+    fn eval_block(&mut self, statements: &[Stmt], tail: Option<&Expr>) -> Result<Value, String> {
+        match self.exec_all(statements)? {
+            Flow::Normal => (),
+            _ => return Err("Cannot leave a block expression early".to_string()),
+        }
+
+        match tail {
+            Some(tail) => self.eval_expr(tail),
+            None => Ok(Value::Null),
+        }
+    }
+
+    fn eval_unary(&mut self, op: &UnaryOp, operand: &Expr) -> Result<Value, String> {
+        let operand = self.eval_expr(operand)?;
+
+        match op {
+            UnaryOp::Not => Ok(Value::Bool(!operand.is_truthy())),
+            UnaryOp::Neg => -operand,
+            UnaryOp::Pos => operand.to_number(),
+            UnaryOp::BitNot => !operand,
+        }
+    }
+
+    fn eval_binary(&mut self, op: &BinaryOp, left: &Expr, right: &Expr) -> Result<Value, String> {
+        let left = self.eval_expr(left)?;
+
+        // `&&` and `||` only evaluate the right side when the left doesn't decide the result
+        match op {
+            BinaryOp::And if !left.is_truthy() => Ok(left),
+            BinaryOp::Or if left.is_truthy() => Ok(left),
+            BinaryOp::And | BinaryOp::Or => self.eval_expr(right),
+            op => binary(op, left, self.eval_expr(right)?, self.wrapping),
+        }
+    }
+
+    fn eval_assignment(
+        &mut self,
+        target: &Expr,
+        op: Option<&BinaryOp>,
+        value: &Expr,
+    ) -> Result<Value, String> {
+        let name = assignee(target)?;
+
+        let value = match op {
+            // Like `&&` and `||`, only evaluate and assign when the target doesn't decide
+            Some(op @ (BinaryOp::And | BinaryOp::Or)) => {
+                let current = self.eval_expr(target)?;
+
+                if current.is_truthy() == (*op == BinaryOp::Or) {
+                    return Ok(current);
+                }
+
+                self.eval_expr(value)?
+            }
+            Some(op) => {
+                let current = self.eval_expr(target)?;
+                binary(op, current, self.eval_expr(value)?, self.wrapping)?
+            }
+            None => self.eval_expr(value)?,
+        };
+
+        self.assign(name, value.clone())?;
+        Ok(value)
+    }
+
+    fn eval_call(&mut self, callee: &Expr, args: &[Expr]) -> Result<Value, String> {
+        let name = match callee {
+            Expr::Variable(name) => name,
+            callee => return Err(format!("Cannot call {}", describe(callee))),
+        };
+
+        let args = self.eval_list(args)?;
+
+        self.call(name, args)
+    }
+
+    /// Evaluate array elements or call arguments, expanding `...array` in place
+    fn eval_list(&mut self, exprs: &[Expr]) -> Result<Vec<Value>, String> {
+        let mut values = Vec::with_capacity(exprs.len());
+
+        for expr in exprs {
+            match expr {
+                Expr::Spread { expr } => match self.eval_expr(expr)? {
+                    Value::Array(spread) => values.extend(spread),
+                    value => return Err(format!("Cannot spread {}", value.type_name())),
+                },
+                expr => values.push(self.eval_expr(expr)?),
+            }
+        }
+
+        Ok(values)
+    }
+
+    /// Increment or decrement a variable, yielding the old value when `postfix`
+    fn step(&mut self, operand: &Expr, op: BinaryOp, postfix: bool) -> Result<Value, String> {
+        let name = assignee(operand)?;
+        let current = self.eval_expr(operand)?;
+        let value = binary(&op, current.clone(), Value::Int(1), self.wrapping)?;
+
+        self.assign(name, value.clone())?;
+
+        match postfix {
+            true => Ok(current),
+            false => Ok(value),
+        }
+    }
+}
+
+/// Name of the variable an assignment writes to
+fn assignee(target: &Expr) -> Result<&str, String> {
+    match target {
+        Expr::Variable(name) => Ok(name),
+        _ => Err(format!("Cannot assign to {}", describe(target))),
+    }
+}
+
+fn binary(op: &BinaryOp, left: Value, right: Value, wrapping: bool) -> Result<Value, String> {
+    match op {
+        BinaryOp::Add if wrapping => left.wrapping_add(right),
+        BinaryOp::Sub if wrapping => left.wrapping_sub(right),
+        BinaryOp::Mul if wrapping => left.wrapping_mul(right),
+        BinaryOp::Add => left + right,
+        BinaryOp::Sub => left - right,
+        BinaryOp::Mul => left * right,
+        BinaryOp::Div => left / right,
+        BinaryOp::FloorDiv => left.floor_div(right),
+        BinaryOp::Mod => left % right,
+        BinaryOp::Pow => left.pow(right),
+        BinaryOp::Eq => Ok(Value::Bool(left == right)),
+        BinaryOp::Neq => Ok(Value::Bool(left != right)),
+        BinaryOp::Lt => left.compare(&right).map(|o| Value::Bool(o.is_lt())),
+        BinaryOp::Le => left.compare(&right).map(|o| Value::Bool(o.is_le())),
+        BinaryOp::Gt => left.compare(&right).map(|o| Value::Bool(o.is_gt())),
+        BinaryOp::Ge => left.compare(&right).map(|o| Value::Bool(o.is_ge())),
+        BinaryOp::BitAnd => left & right,
+        BinaryOp::BitOr => left | right,
+        BinaryOp::BitXor => left ^ right,
+        BinaryOp::Shl => left << right,
+        BinaryOp::Shr => left >> right,
+        // Never reached, `&&` and `||` short-circuit in eval_binary and eval_assignment
+        BinaryOp::And => Ok(Value::Bool(left.is_truthy() && right.is_truthy())),
+        BinaryOp::Or => Ok(Value::Bool(left.is_truthy() || right.is_truthy())),
+    }
+}
+
+fn describe(expr: &Expr) -> &'static str {
+    match expr {
+        Expr::Literal(_) => "literal",
+        Expr::Variable(_) => "variable",
+        Expr::Binary { .. } => "binary expression",
+        Expr::Unary { .. } => "unary expression",
+        Expr::Group(_) => "group",
+        Expr::Sequence(_) => "sequence",
+        Expr::Block { .. } => "block expression",
+        Expr::Call { .. } => "call",
+        Expr::Member { .. } => "member access",
+        Expr::Index { .. } => "index",
+        Expr::Array { .. } => "array literal",
+        Expr::Object { .. } => "object literal",
+        Expr::Spread { .. } => "spread",
+        Expr::Assignment { .. } | Expr::CompoundAssignment { .. } => "assignment",
+        Expr::PreIncrement { .. }
+        | Expr::PostIncrement { .. }
+        | Expr::PreDecrement { .. }
+        | Expr::PostDecrement { .. } => "increment/decrement",
+    }
+}
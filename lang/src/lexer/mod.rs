@@ -1,15 +1,26 @@
 use logos::Logos;
+mod semantic;
+mod template;
 mod utils;
 
+pub use semantic::{SemanticToken, TokenCategory, tokenize};
+pub use template::TemplatePart;
+
+use template::parse_template;
+
+pub(crate) use utils::block_comment_end;
+
 use utils::{
-    parse_block_comment, parse_float, parse_identifier, parse_int, parse_line_comment, parse_string,
+    parse_block_comment, parse_char, parse_doc_block_comment, parse_doc_line_comment, parse_float,
+    parse_identifier, parse_int, parse_line_comment, parse_string,
 };
 
 #[derive(Logos, Debug, PartialEq, Clone)]
 pub enum Token {
     #[regex(r"[0-9]+", parse_int)]
     IntLiteral(i64),
-    #[regex(r"[0-9]+\.[0-9]+", parse_float)]
+    /// `1.5`, `.5` or `5.`, a dot after an identifier is still member access
+    #[regex(r"[0-9]+\.[0-9]*|\.[0-9]+", parse_float)]
     FloatLiteral(f64),
     #[token("true")]
     BoolTrue,
@@ -17,6 +28,10 @@ pub enum Token {
     BoolFalse,
     #[regex(r#""([^"\\]|\\.)*""#, parse_string)]
     StringLiteral(String),
+    #[regex(r"'([^'\\]|\\.)*'", parse_char)]
+    CharLiteral(char),
+    #[token("`", parse_template)]
+    TemplateString(Vec<TemplatePart>),
     #[token("null")]
     NullLiteral,
 
@@ -41,6 +56,12 @@ pub enum Token {
     Import,
     #[token("export")]
     Export,
+    #[token("default")]
+    Default,
+    #[token("from")]
+    From,
+    #[token("as")]
+    As,
     #[token("while")]
     While,
     #[token("for")]
@@ -53,8 +74,13 @@ pub enum Token {
     // ===== Comments =====
     #[regex(r"//[^\r\n]*", parse_line_comment)]
     LineComment(String),
-    #[regex(r"/\*(?:[^*]|\*[^/])*\*/", parse_block_comment)]
+    #[token("/*", parse_block_comment)]
+    #[token("/**/", |_| String::new())]
     BlockComment(String),
+    #[regex(r"///[^\r\n]*", parse_doc_line_comment)]
+    DocLineComment(String),
+    #[token("/**", parse_doc_block_comment)]
+    DocBlockComment(String),
 
     // ===== Operators =====
     // Basic
@@ -68,6 +94,8 @@ pub enum Token {
     Star,
     #[token("/")]
     Slash,
+    #[token("\\")]
+    Backslash,
     #[token("%")]
     Modulo,
     #[token("^")]
@@ -86,6 +114,8 @@ pub enum Token {
     SlashEqual,
     #[token("%=")]
     ModuloEqual,
+    #[token("**=")]
+    DoubleStarEqual,
     #[token("^=")]
     CaretEqual,
 
@@ -116,12 +146,18 @@ pub enum Token {
     And,
     #[token("||")]
     Or,
+    #[token("&&=")]
+    AndEqual,
+    #[token("||=")]
+    OrEqual,
 
     // Bitwise
     #[token("&")]
     BitAnd,
     #[token("|")]
     BitOr,
+    #[token("~")]
+    Tilde,
     #[token("<<")]
     ShiftLeft,
     #[token(">>")]
@@ -156,6 +192,12 @@ pub enum Token {
     Colon,
     #[token(".")]
     Dot,
+    #[token("...")]
+    Ellipsis,
+    #[token("?.")]
+    QuestionDot,
+    #[token("=>")]
+    FatArrow,
 
     // ===== Whitespace =====
     #[regex(r"[ \t\r\n]+", logos::skip)]
@@ -166,17 +208,132 @@ pub enum Token {
     // #[token("?")] Question,
     // #[token("??")] NullCoalesce,
     // #[token("|>")] Pipe,
-    // #[token("...")] Ellipsis,
+}
+
+/// Quotes that delimit string literals. Outside of [`StringQuote::Double`],
+/// single quotes make strings, so char literals cannot be written
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum StringQuote {
+    /// `"hello"` is a string and `'c'` a char
+    Double,
+    /// `'hello'` is a string and double quotes are invalid
+    Single,
+    /// Both `"hello"` and `'hello'` are strings
+    Both,
+}
+
+impl StringQuote {
+    /// Reinterpret a lexed token and its source text for this quote style
+    fn apply(&self, token: Result<Token, ()>, slice: &str) -> Result<Token, ()> {
+        match self {
+            StringQuote::Double => token,
+            _ if slice.starts_with('\'') && slice.len() >= 2 && slice.ends_with('\'') => {
+                let inner = &slice[1..slice.len() - 1];
+                Ok(Token::StringLiteral(inner.replace("\\'", "'")))
+            }
+            StringQuote::Single if slice.starts_with('"') => Err(()),
+            _ => token,
+        }
+    }
+}
+
+/// Next token of `lexer` as the parser sees it, with quotes read per `quote`
+pub(crate) fn next_token(
+    lexer: &mut logos::Lexer<'_, Token>,
+    quote: StringQuote,
+) -> Option<Result<Token, ()>> {
+    let token = match lexer.next()? {
+        // An unclosed `/**` backtracks to a `/` that still spans the rest of the input
+        Ok(Token::Slash) if lexer.slice() != "/" => Err(()),
+        token => token,
+    };
+
+    Some(quote.apply(token, lexer.slice()))
 }
 
 impl Token {
     /// Check if token is a comment
     pub fn is_comment(&self) -> bool {
-        matches!(self, Token::LineComment(_) | Token::BlockComment(_))
+        matches!(
+            self,
+            Token::LineComment(_)
+                | Token::BlockComment(_)
+                | Token::DocLineComment(_)
+                | Token::DocBlockComment(_)
+        )
+    }
+
+    /// Check if token is a `///` or `/** */` documentation comment
+    pub fn is_doc_comment(&self) -> bool {
+        matches!(self, Token::DocLineComment(_) | Token::DocBlockComment(_))
     }
 
     /// Check if token is whitespace or comment (for filtering)
     pub fn is_trivia(&self) -> bool {
-        matches!(self, Token::LineComment(_) | Token::BlockComment(_))
+        self.is_comment()
+    }
+
+    /// The reserved word a keyword token is written as
+    pub fn keyword(&self) -> Option<&'static str> {
+        let keyword = match self {
+            Token::BoolTrue => "true",
+            Token::BoolFalse => "false",
+            Token::NullLiteral => "null",
+            Token::Let => "let",
+            Token::Const => "const",
+            Token::Fn => "fn",
+            Token::Return => "return",
+            Token::If => "if",
+            Token::Else => "else",
+            Token::Import => "import",
+            Token::Export => "export",
+            Token::Default => "default",
+            Token::From => "from",
+            Token::As => "as",
+            Token::While => "while",
+            Token::For => "for",
+            Token::Continue => "continue",
+            Token::Break => "break",
+            _ => return None,
+        };
+
+        Some(keyword)
+    }
+
+    /// Check if token is a reserved word that cannot name anything
+    pub fn is_keyword(&self) -> bool {
+        self.keyword().is_some()
+    }
+
+    /// Stable highlighting kind, matching the VS Code semantic token types
+    pub fn kind(&self) -> &'static str {
+        self.category().kind()
+    }
+
+    /// Highlighting class of the token, booleans and `null` count as keywords
+    pub fn category(&self) -> TokenCategory {
+        match self {
+            Token::IntLiteral(_) | Token::FloatLiteral(_) => TokenCategory::LiteralNumber,
+            Token::StringLiteral(_) | Token::CharLiteral(_) | Token::TemplateString(_) => {
+                TokenCategory::LiteralString
+            }
+            Token::Identifier(_) => TokenCategory::Identifier,
+            token if token.is_comment() => TokenCategory::Comment,
+            token if token.is_keyword() => TokenCategory::Keyword,
+            Token::LeftParen
+            | Token::RightParen
+            | Token::LeftBrace
+            | Token::RightBrace
+            | Token::LeftBracket
+            | Token::RightBracket
+            | Token::Comma
+            | Token::Semicolon
+            | Token::Colon
+            | Token::Dot
+            | Token::Ellipsis
+            | Token::QuestionDot
+            | Token::FatArrow => TokenCategory::Punctuation,
+            _ => TokenCategory::Operator,
+        }
     }
 }
@@ -0,0 +1,63 @@
+use logos::Logos;
+use serde::{Deserialize, Serialize};
+
+use super::{StringQuote, Token, next_token};
+
+/// Highlighting class of a token, see [`Token::category`]
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum TokenCategory {
+    Keyword,
+    Operator,
+    LiteralNumber,
+    LiteralString,
+    Comment,
+    Punctuation,
+    Identifier,
+}
+
+impl TokenCategory {
+    /// Matching VS Code semantic token type
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TokenCategory::Keyword => "keyword",
+            TokenCategory::Operator => "operator",
+            TokenCategory::LiteralNumber => "number",
+            TokenCategory::LiteralString => "string",
+            TokenCategory::Comment => "comment",
+            TokenCategory::Punctuation => "punctuation",
+            TokenCategory::Identifier => "variable",
+        }
+    }
+}
+
+/// A lexed token reduced to what an editor needs for highlighting
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SemanticToken {
+    pub kind: String,
+    pub category: TokenCategory,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Lex the source into semantic tokens, skipping anything the lexer rejects
+pub fn tokenize(source: &str) -> Vec<SemanticToken> {
+    let mut lexer = Token::lexer(source);
+    let mut tokens = Vec::new();
+
+    while let Some(token_result) = next_token(&mut lexer, StringQuote::Double) {
+        if let Ok(token) = token_result {
+            let span = lexer.span();
+            let category = token.category();
+
+            tokens.push(SemanticToken {
+                kind: category.kind().to_string(),
+                category,
+                start: span.start,
+                end: span.end,
+            });
+        }
+    }
+
+    tokens
+}
@@ -0,0 +1,90 @@
+use std::ops::Range;
+
+use super::Token;
+
+/// Piece of a `` `template ${string}` ``
+#[derive(Debug, PartialEq, Clone)]
+pub enum TemplatePart {
+    /// Literal text with escapes resolved
+    Text(String),
+    /// Byte range of an interpolated expression in the source, without `${` and `}`
+    Expr(Range<usize>),
+}
+
+/// Scan a template after its opening backtick, up to and including the closing one
+pub fn parse_template(lex: &mut logos::Lexer<'_, Token>) -> Option<Vec<TemplatePart>> {
+    let offset = lex.span().end;
+    let remainder = lex.remainder();
+    let mut parts = Vec::new();
+    let mut text = String::new();
+    let mut chars = remainder.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '`' => {
+                if !text.is_empty() {
+                    parts.push(TemplatePart::Text(text));
+                }
+
+                lex.bump(i + 1);
+                return Some(parts);
+            }
+            '\\' => text.push(match chars.next()?.1 {
+                'n' => '\n',
+                't' => '\t',
+                'r' => '\r',
+                '0' => '\0',
+                c @ ('\\' | '`' | '$' | '"' | '\'') => c,
+                _ => return None,
+            }),
+            '$' if chars.peek().is_some_and(|(_, c)| *c == '{') => {
+                let start = i + 2;
+                let end = start + interpolation_len(&remainder[start..])?;
+
+                if !text.is_empty() {
+                    parts.push(TemplatePart::Text(std::mem::take(&mut text)));
+                }
+
+                parts.push(TemplatePart::Expr(offset + start..offset + end));
+
+                // Skip past the closing brace
+                while chars.next_if(|(j, _)| *j <= end).is_some() {}
+            }
+            c => text.push(c),
+        }
+    }
+
+    // Unterminated template
+    None
+}
+
+/// Length of an interpolated expression up to its closing brace, skipping
+/// braces inside nested strings and templates
+fn interpolation_len(source: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut chars = source.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(i),
+            '}' => depth -= 1,
+            '"' | '\'' | '`' => {
+                let quote = c;
+
+                loop {
+                    match chars.next()?.1 {
+                        '\\' => {
+                            chars.next()?;
+                        }
+                        c if c == quote => break,
+                        _ => (),
+                    }
+                }
+            }
+            _ => (),
+        }
+    }
+
+    None
+}
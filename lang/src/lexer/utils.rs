@@ -13,6 +13,31 @@ pub fn parse_string(lex: &mut logos::Lexer<'_, Token>) -> Option<String> {
     Some(s[1..s.len() - 1].replace("\\\"", "\""))
 }
 
+pub fn parse_char(lex: &mut logos::Lexer<'_, Token>) -> Option<char> {
+    let s = lex.slice();
+    let mut chars = s[1..s.len() - 1].chars();
+
+    let c = match chars.next()? {
+        '\\' => match chars.next()? {
+            'n' => '\n',
+            't' => '\t',
+            'r' => '\r',
+            '0' => '\0',
+            '\\' => '\\',
+            '\'' => '\'',
+            '"' => '"',
+            _ => return None,
+        },
+        c => c,
+    };
+
+    // Anything left over means more than one character between the quotes
+    match chars.next() {
+        Some(_) => None,
+        None => Some(c),
+    }
+}
+
 pub fn parse_identifier(lex: &mut logos::Lexer<'_, Token>) -> Option<String> {
     Some(lex.slice().to_string())
 }
@@ -24,7 +49,63 @@ pub fn parse_line_comment(lex: &mut logos::Lexer<'_, Token>) -> Option<String> {
 }
 
 pub fn parse_block_comment(lex: &mut logos::Lexer<'_, Token>) -> Option<String> {
+    // Only /* was matched, the body runs to the */ closing it
+    comment_body(lex)
+}
+
+pub fn parse_doc_line_comment(lex: &mut logos::Lexer<'_, Token>) -> Option<String> {
     let s = lex.slice();
-    // Remove the /* */ wrapper
-    Some(s[2..s.len() - 2].to_string())
-}
\ No newline at end of file
+    // Remove the /// prefix
+    Some(s[3..].to_string())
+}
+
+pub fn parse_doc_block_comment(lex: &mut logos::Lexer<'_, Token>) -> Option<String> {
+    // Only /** was matched, the body runs to the */ closing it
+    comment_body(lex)
+}
+
+/// Consume the rest of a block comment and return its body, or consume the rest of
+/// the input when it is never closed
+fn comment_body(lex: &mut logos::Lexer<'_, Token>) -> Option<String> {
+    let remainder = lex.remainder();
+
+    match block_comment_end(remainder) {
+        Some(end) => {
+            let body = remainder[..end - 2].to_string();
+            lex.bump(end);
+            Some(body)
+        }
+        None => {
+            lex.bump(remainder.len());
+            None
+        }
+    }
+}
+
+/// Length of a block comment's body and closing */, given the text after its
+/// opening /*. Nested comments must be closed first
+pub(crate) fn block_comment_end(text: &str) -> Option<usize> {
+    let bytes = text.as_bytes();
+    let mut depth = 1;
+    let mut i = 0;
+
+    while i + 1 < bytes.len() {
+        match (bytes[i], bytes[i + 1]) {
+            (b'/', b'*') => {
+                depth += 1;
+                i += 2;
+            }
+            (b'*', b'/') => {
+                depth -= 1;
+                i += 2;
+
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => i += 1,
+        }
+    }
+
+    None
+}
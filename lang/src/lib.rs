@@ -1,12 +1,18 @@
 mod error;
 mod wasm;
 
+pub mod eval;
 pub mod lexer;
 pub mod parser;
+pub mod vscode;
 
 pub mod ast {
+    pub mod dot;
     pub mod expr;
+    pub mod format;
     pub mod op;
+    pub mod sexpr;
+    pub mod span;
     pub mod stmt;
     pub mod value;
 }
\ No newline at end of file
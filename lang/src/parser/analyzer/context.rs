@@ -7,11 +7,16 @@ pub struct ParseContext {
     pub column_end: usize,
     pub line_content: String,
     pub span_in_line: Range<usize>,
+    /// Line of the span end, after `line_number` for spans crossing lines
+    pub end_line: usize,
+    /// Column of the span end on `end_line`
+    pub end_column: usize,
 }
 
 impl ParseContext {
     pub fn from_span(source: &str, span: &Range<usize>) -> Self {
         let lines: Vec<&str> = source.lines().collect();
+        let (end_line, end_column) = Self::position(&lines, span.end.max(span.start));
         let mut current_pos = 0;
 
         for (line_num, line) in lines.iter().enumerate() {
@@ -28,20 +33,43 @@ impl ParseContext {
                     column_end: col_end + 1,
                     line_content: line.to_string(),
                     span_in_line: col_start..col_end,
+                    end_line,
+                    end_column,
                 };
             }
 
             current_pos = line_end + 1;
         }
 
+        // Past the last line, point just after its content
+        let last = lines.last().unwrap_or(&"");
+
         Self {
-            line_number: lines.len(),
-            column_start: 1,
-            column_end: 1,
-            line_content: lines.last().unwrap_or(&"").to_string(),
-            span_in_line: 0..0,
+            line_number: lines.len().max(1),
+            column_start: last.len() + 1,
+            column_end: last.len() + 1,
+            line_content: last.to_string(),
+            span_in_line: last.len()..last.len(),
+            end_line,
+            end_column,
         }
     }
+
+    /// One-based line and column of a byte offset, clamped to the end of the source
+    fn position(lines: &[&str], offset: usize) -> (usize, usize) {
+        let mut line_start = 0;
+
+        for (line_num, line) in lines.iter().enumerate() {
+            if offset <= line_start + line.len() {
+                return (line_num + 1, offset - line_start + 1);
+            }
+
+            line_start += line.len() + 1;
+        }
+
+        let last = lines.last().unwrap_or(&"");
+        (lines.len().max(1), last.len() + 1)
+    }
 }
 
 impl std::fmt::Display for ParseContext {
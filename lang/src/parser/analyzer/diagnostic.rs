@@ -1,10 +1,10 @@
-use crate::parser::ParseWarning;
+use crate::parser::{LintLevel, ParseWarning};
 
 use super::ParseError;
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
-#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy)]
+#[derive(Serialize_repr, Deserialize_repr, Debug, Clone, Copy, PartialEq, Eq)]
 #[repr(u8)]
 pub enum DiagnosticLevel {
     Error = 0,
@@ -13,13 +13,119 @@ pub enum DiagnosticLevel {
     Hint = 3,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+impl std::fmt::Display for DiagnosticLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiagnosticLevel::Error => write!(f, "error"),
+            DiagnosticLevel::Warn => write!(f, "warning"),
+            DiagnosticLevel::Info => write!(f, "info"),
+            DiagnosticLevel::Hint => write!(f, "hint"),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Diagnostic {
     line: usize,
     length: usize,
     column: usize,
+    end_line: usize,
+    end_column: usize,
     message: String,
     level: DiagnosticLevel,
+    /// Stable identifier such as `E0001`, see [`ParseError::code`] and [`ParseWarning::code`]
+    code: String,
+    /// Number of identical diagnostics collapsed into this one
+    count: usize,
+}
+
+impl Diagnostic {
+    pub fn is_error(&self) -> bool {
+        matches!(self.level, DiagnosticLevel::Error)
+    }
+
+    pub fn level(&self) -> DiagnosticLevel {
+        self.level
+    }
+
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    /// One-based line the diagnostic starts on
+    pub fn line(&self) -> usize {
+        self.line
+    }
+
+    /// One-based column the diagnostic starts at
+    pub fn column(&self) -> usize {
+        self.column
+    }
+
+    /// Length of the reported source in bytes
+    pub fn length(&self) -> usize {
+        self.length
+    }
+
+    /// How many times the diagnostic was reported at the same place
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Collapse diagnostics with the same position, message and level into the
+    /// first one reported, counting the repeats
+    pub(crate) fn merge_duplicates(diagnostics: Vec<Diagnostic>) -> Vec<Diagnostic> {
+        let mut merged: Vec<Diagnostic> = Vec::with_capacity(diagnostics.len());
+
+        for diagnostic in diagnostics {
+            match merged
+                .iter_mut()
+                .find(|kept| kept.is_duplicate_of(&diagnostic))
+            {
+                Some(kept) => kept.count += diagnostic.count,
+                None => merged.push(diagnostic),
+            }
+        }
+
+        merged
+    }
+
+    fn is_duplicate_of(&self, other: &Diagnostic) -> bool {
+        self.start() == other.start() && self.level == other.level && self.message == other.message
+    }
+
+    /// Report at the level a lint is configured with
+    pub(crate) fn with_lint_level(mut self, level: LintLevel) -> Self {
+        self.level = match level {
+            LintLevel::Allow | LintLevel::Info => DiagnosticLevel::Info,
+            LintLevel::Warn => DiagnosticLevel::Warn,
+        };
+        self
+    }
+
+    /// One-based line and column where the diagnostic starts
+    pub fn start(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    /// One-based line and column just past the end of the diagnostic
+    pub fn end(&self) -> (usize, usize) {
+        (self.end_line, self.end_column)
+    }
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}:{}: {}: {}",
+            self.line, self.column, self.level, self.message
+        )
+    }
 }
 
 impl From<ParseError> for Diagnostic {
@@ -27,30 +133,48 @@ impl From<ParseError> for Diagnostic {
         match &value {
             ParseError::BuildError { span, context, .. } => Diagnostic {
                 level: DiagnosticLevel::Error,
+                code: value.code().to_string(),
+                count: 1,
                 message: format!("{value}"),
                 line: context.line_number,
                 column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
                 length: span.end - span.start,
             },
             ParseError::UnexpectedToken { span, context, .. } => Diagnostic {
                 level: DiagnosticLevel::Error,
+                code: value.code().to_string(),
+                count: 1,
                 message: format!("{value}"),
                 line: context.line_number,
                 column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
                 length: span.end - span.start,
             },
-            ParseError::UnexpectedEof { context, .. } => Diagnostic {
-                level: DiagnosticLevel::Error,
-                message: format!("{value}"),
-                line: context.line_number,
-                column: context.column_start,
-                length: 1,
-            },
+            ParseError::UnexpectedEof { context, .. } | ParseError::Unclosed { context, .. } => {
+                Diagnostic {
+                    level: DiagnosticLevel::Error,
+                    code: value.code().to_string(),
+                    count: 1,
+                    message: format!("{value}"),
+                    line: context.line_number,
+                    column: context.column_start,
+                    end_line: context.end_line,
+                    end_column: context.column_start + 1,
+                    length: 1,
+                }
+            }
             ParseError::InvalidSyntax { context, span, .. } => Diagnostic {
                 level: DiagnosticLevel::Error,
+                code: value.code().to_string(),
+                count: 1,
                 message: format!("{value}"),
                 line: context.line_number,
                 column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
                 length: span.end - span.start,
             },
             ParseError::MissingToken {
@@ -59,16 +183,26 @@ impl From<ParseError> for Diagnostic {
                 ..
             } => Diagnostic {
                 level: DiagnosticLevel::Error,
+                code: value.code().to_string(),
+                count: 1,
                 message: format!("{value}"),
                 line: source_context.line_number,
                 column: source_context.column_start,
+                end_line: source_context.end_line,
+                end_column: source_context.end_column,
                 length: span.end - span.start,
             },
-            ParseError::TooMuchRecursion { position, .. } => Diagnostic {
+            ParseError::TooMuchRecursion { context, .. }
+            | ParseError::BudgetExhausted { context, .. }
+            | ParseError::LimitExceeded { context, .. } => Diagnostic {
                 level: DiagnosticLevel::Error,
+                code: value.code().to_string(),
+                count: 1,
                 message: format!("{value}"),
-                line: *position,
-                column: 0,
+                line: context.line_number,
+                column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.column_start + 1,
                 length: 1,
             },
         }
@@ -80,30 +214,145 @@ impl From<ParseWarning> for Diagnostic {
         match &value {
             ParseWarning::UnusedVariable { span, context, .. } => Diagnostic {
                 level: DiagnosticLevel::Warn,
+                code: value.code().to_string(),
+                count: 1,
                 message: format!("{value}"),
                 line: context.line_number,
                 column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
                 length: span.end - span.start,
             },
             ParseWarning::UnusedFunction { span, context, .. } => Diagnostic {
                 level: DiagnosticLevel::Warn,
+                code: value.code().to_string(),
+                count: 1,
                 message: format!("{value}"),
                 line: context.line_number,
                 column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
                 length: span.end - span.start,
             },
             ParseWarning::UnreachableCode { span, context } => Diagnostic {
                 level: DiagnosticLevel::Warn,
+                code: value.code().to_string(),
+                count: 1,
                 message: format!("{value}"),
                 line: context.line_number,
                 column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
                 length: span.end - span.start,
             },
             ParseWarning::NamingConvention { span, context, .. } => Diagnostic {
                 level: DiagnosticLevel::Warn,
+                code: value.code().to_string(),
+                count: 1,
+                message: format!("{value}"),
+                line: context.line_number,
+                column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
+                length: span.end - span.start,
+            },
+            ParseWarning::ChainedComparison { span, context } => Diagnostic {
+                level: DiagnosticLevel::Warn,
+                code: value.code().to_string(),
+                count: 1,
+                message: format!("{value}"),
+                line: context.line_number,
+                column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
+                length: span.end - span.start,
+            },
+            ParseWarning::UndefinedLabel { span, context, .. } => Diagnostic {
+                level: DiagnosticLevel::Warn,
+                code: value.code().to_string(),
+                count: 1,
+                message: format!("{value}"),
+                line: context.line_number,
+                column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
+                length: span.end - span.start,
+            },
+            ParseWarning::ConstantCondition { span, context, .. } => Diagnostic {
+                level: DiagnosticLevel::Warn,
+                code: value.code().to_string(),
+                count: 1,
+                message: format!("{value}"),
+                line: context.line_number,
+                column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
+                length: span.end - span.start,
+            },
+            ParseWarning::SelfAssignment { span, context } => Diagnostic {
+                level: DiagnosticLevel::Warn,
+                code: value.code().to_string(),
+                count: 1,
+                message: format!("{value}"),
+                line: context.line_number,
+                column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
+                length: span.end - span.start,
+            },
+            ParseWarning::Shadowing { span, context, .. } => Diagnostic {
+                level: DiagnosticLevel::Info,
+                code: value.code().to_string(),
+                count: 1,
+                message: format!("{value}"),
+                line: context.line_number,
+                column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
+                length: span.end - span.start,
+            },
+            ParseWarning::DivisionByZero { span, context } => Diagnostic {
+                level: DiagnosticLevel::Error,
+                code: value.code().to_string(),
+                count: 1,
+                message: format!("{value}"),
+                line: context.line_number,
+                column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
+                length: span.end - span.start,
+            },
+            ParseWarning::EmptyBlock { span, context } => Diagnostic {
+                level: DiagnosticLevel::Info,
+                code: value.code().to_string(),
+                count: 1,
+                message: format!("{value}"),
+                line: context.line_number,
+                column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
+                length: span.end - span.start,
+            },
+            ParseWarning::EmptyStatement { span, context } => Diagnostic {
+                level: DiagnosticLevel::Info,
+                code: value.code().to_string(),
+                count: 1,
+                message: format!("{value}"),
+                line: context.line_number,
+                column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
+                length: span.end - span.start,
+            },
+            ParseWarning::XorOperator { span, context } => Diagnostic {
+                level: DiagnosticLevel::Hint,
+                code: value.code().to_string(),
+                count: 1,
                 message: format!("{value}"),
                 line: context.line_number,
                 column: context.column_start,
+                end_line: context.end_line,
+                end_column: context.end_column,
                 length: span.end - span.start,
             },
         }
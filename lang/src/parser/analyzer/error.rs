@@ -27,6 +27,19 @@ pub enum ParseError {
         context: ParseContext,
     },
 
+    /// End of file inside a bracket, paren or brace that was never closed
+    Unclosed {
+        /// Opening delimiter as written, such as `[`
+        delimiter: String,
+        expected: String,
+        /// Location of the opening delimiter
+        opened_at: Range<usize>,
+        /// Line of the opening delimiter
+        opened_line: usize,
+        position: usize,
+        context: ParseContext,
+    },
+
     /// Invalid syntax
     InvalidSyntax {
         message: String,
@@ -42,7 +55,59 @@ pub enum ParseError {
     },
 
     /// Too much recursion (stack overflow prevention)
-    TooMuchRecursion { max_depth: usize, position: usize },
+    TooMuchRecursion {
+        max_depth: usize,
+        position: usize,
+        context: ParseContext,
+    },
+
+    /// More tokens consumed than the config's step budget allows
+    BudgetExhausted {
+        max_steps: usize,
+        position: usize,
+        context: ParseContext,
+    },
+
+    /// More statements or expression nodes than the config allows
+    LimitExceeded {
+        kind: LimitKind,
+        limit: usize,
+        position: usize,
+        context: ParseContext,
+    },
+}
+
+/// What a [`ParseError::LimitExceeded`] counted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LimitKind {
+    Statements,
+    ExpressionNodes,
+}
+
+impl std::fmt::Display for LimitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LimitKind::Statements => write!(f, "statements"),
+            LimitKind::ExpressionNodes => write!(f, "expression nodes"),
+        }
+    }
+}
+
+impl ParseError {
+    /// Stable identifier of the kind of error, for filtering and documentation links
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseError::UnexpectedToken { .. } => "E0001",
+            ParseError::UnexpectedEof { .. } => "E0002",
+            ParseError::Unclosed { .. } => "E0003",
+            ParseError::InvalidSyntax { .. } => "E0004",
+            ParseError::MissingToken { .. } => "E0005",
+            ParseError::BuildError { .. } => "E0006",
+            ParseError::TooMuchRecursion { .. } => "E0007",
+            ParseError::BudgetExhausted { .. } => "E0008",
+            ParseError::LimitExceeded { .. } => "E0009",
+        }
+    }
 }
 
 impl std::fmt::Display for ParseError {
@@ -76,6 +141,15 @@ impl std::fmt::Display for ParseError {
 
                 Ok(())
             }
+            ParseError::Unclosed {
+                delimiter,
+                expected,
+                opened_line,
+                ..
+            } => write!(
+                f,
+                "Unexpected end of file, expected {expected}, unclosed '{delimiter}' opened at line {opened_line}"
+            ),
             ParseError::InvalidSyntax { message, .. } => {
                 write!(f, "Syntax error: {}", message)?;
                 // write!(f, "\n{context}")?;
@@ -88,14 +162,14 @@ impl std::fmt::Display for ParseError {
 
                 Ok(())
             }
-            ParseError::TooMuchRecursion {
-                max_depth,
-                position,
-            } => {
-                write!(
-                    f,
-                    "Maximum recursion depth ({max_depth}) exceeded at position {position}"
-                )
+            ParseError::TooMuchRecursion { max_depth, .. } => {
+                write!(f, "Maximum recursion depth ({max_depth}) exceeded")
+            }
+            ParseError::BudgetExhausted { max_steps, .. } => {
+                write!(f, "Parse budget of {max_steps} steps exhausted")
+            }
+            ParseError::LimitExceeded { kind, limit, .. } => {
+                write!(f, "Too many {kind}, the limit is {limit}")
             }
         }
     }
@@ -1,7 +1,10 @@
-use inflections::Inflect;
 use std::ops::Range;
 
-use crate::ast::stmt::Stmt;
+use crate::{
+    ast::{expr::Expr, op::BinaryOp, span::Span, stmt::Stmt, value::Value},
+    eval,
+    parser::{LintLevel, NamingStyle, ParserConfig},
+};
 
 mod context;
 mod diagnostic;
@@ -9,77 +12,447 @@ mod error;
 mod warning;
 
 pub use context::ParseContext;
-pub use diagnostic::Diagnostic;
-pub use error::ParseError;
+pub use diagnostic::{Diagnostic, DiagnosticLevel};
+pub use error::{LimitKind, ParseError};
 pub use warning::ParseWarning;
 
 pub struct Analyzer<'a> {
     source: &'a str,
     // position: usize,
     diagnostics: Vec<Diagnostic>,
+    /// Labels of the loops enclosing the statement being analyzed
+    labels: Vec<String>,
+    /// Names declared in each enclosing scope, innermost last
+    scopes: Vec<Vec<String>>,
+    shadowing: LintLevel,
+    empty_statement: LintLevel,
+    let_naming: NamingStyle,
+    const_naming: NamingStyle,
+    function_naming: NamingStyle,
+    param_naming: NamingStyle,
 }
 
 impl<'a> Analyzer<'a> {
-    pub fn new(source: &'a str) -> Self {
+    pub fn new(source: &'a str, config: &ParserConfig) -> Self {
         Self {
             source,
             diagnostics: Vec::new(),
+            labels: Vec::new(),
+            scopes: vec![Vec::new()],
+            shadowing: config.shadowing(),
+            empty_statement: config.empty_statement(),
+            let_naming: config.let_naming(),
+            const_naming: config.const_naming(),
+            function_naming: config.function_naming(),
+            param_naming: config.param_naming(),
         }
     }
 
     pub fn analyze(&mut self, statement: &Stmt, span: &Range<usize>) {
         match &statement {
-            Stmt::Let { name, .. } if !name.is_snake_case() => {
+            Stmt::Let {
+                name,
+                value,
+                span: Span(span),
+                ..
+            } => {
+                self.check_name(name, self.let_naming, span);
+
+                self.analyze_expr(value, span);
+                self.declare(name, span);
+            }
+            Stmt::LetMulti { bindings, .. } => {
+                for binding in bindings {
+                    self.check_name(&binding.name, self.let_naming, &binding.span);
+
+                    self.analyze_expr(&binding.value, &binding.span);
+                    self.declare(&binding.name, &binding.span);
+                }
+            }
+            Stmt::Const {
+                name,
+                value,
+                span: Span(span),
+                ..
+            } => {
+                self.check_name(name, self.const_naming, span);
+
+                self.analyze_expr(value, span);
+                self.declare(name, span);
+            }
+            Stmt::Function {
+                name,
+                params,
+                body,
+                span: Span(span),
+                ..
+            } => {
+                self.check_name(name, self.function_naming, span);
+
+                self.declare(name, span);
+                self.scopes.push(Vec::new());
+
+                for param in params {
+                    self.check_name(&param.name, self.param_naming, &param.span);
+
+                    if let Some(default) = &param.default {
+                        self.analyze_expr(default, &param.span);
+                    }
+
+                    self.declare(&param.name, &param.span);
+                }
+
+                // Loops outside the function cannot be targeted from its body
+                let labels = std::mem::take(&mut self.labels);
+                self.analyze(&body, span);
+                self.labels = labels;
+                self.scopes.pop();
+            }
+            Stmt::Block {
+                statements,
+                span: Span(span),
+            } => {
+                self.scopes.push(Vec::new());
+
+                for stmt in statements {
+                    self.analyze(stmt, span);
+                }
+
+                self.scopes.pop();
+            }
+            Stmt::If {
+                condition,
+                then_branch,
+                else_branch,
+                span: Span(span),
+            } => {
+                self.analyze_condition(condition, span);
+                self.analyze_expr(condition, span);
+                self.analyze_body(then_branch, span);
+
+                match else_branch.as_deref() {
+                    Some(stmt @ Stmt::If { .. }) => self.analyze(stmt, span),
+                    Some(stmt) => self.analyze_body(stmt, span),
+                    None => (),
+                }
+            }
+            Stmt::While {
+                label,
+                condition,
+                body,
+                span: Span(span),
+            } => {
+                // `while true` is the idiomatic infinite loop
+                if constant_truthiness(condition) != Some(true) {
+                    self.analyze_condition(condition, span);
+                }
+
+                self.analyze_expr(condition, span);
+                self.analyze_loop_body(label, body, span);
+            }
+            Stmt::For {
+                label,
+                init,
+                condition,
+                update,
+                body,
+                span: Span(span),
+            } => {
+                self.scopes.push(Vec::new());
+
+                if let Some(stmt) = init {
+                    self.analyze(&stmt, span);
+                }
+
+                for expr in condition.iter().chain(update) {
+                    self.analyze_expr(expr, span);
+                }
+
+                self.analyze_loop_body(label, body, span);
+                self.scopes.pop();
+            }
+            Stmt::Break {
+                label: Some(label),
+                span: Span(span),
+            }
+            | Stmt::Continue {
+                label: Some(label),
+                span: Span(span),
+            } if !self.labels.contains(label) => {
                 self.diagnostics.push(
-                    ParseWarning::NamingConvention {
-                        message: format!("expected '{}'", name.to_snake_case()),
+                    ParseWarning::UndefinedLabel {
+                        name: label.clone(),
                         span: span.clone(),
                         context: ParseContext::from_span(self.source, span),
                     }
                     .into(),
                 );
             }
-            Stmt::Const { name, .. } if !name.is_constant_case() => {
+            Stmt::Return {
+                value: Some(value),
+                span: Span(span),
+            }
+            | Stmt::Expression {
+                expr: value,
+                span: Span(span),
+            } => self.analyze_expr(value, span),
+            Stmt::ExportDefault { expr, .. } => self.analyze_expr(expr, span),
+            Stmt::Export { statement } => self.analyze(statement, span),
+            Stmt::Empty { span: Span(span) } if self.empty_statement != LintLevel::Allow => {
                 self.diagnostics.push(
-                    ParseWarning::NamingConvention {
-                        message: format!("expected '{}'", name.to_constant_case()),
+                    Diagnostic::from(ParseWarning::EmptyStatement {
                         span: span.clone(),
                         context: ParseContext::from_span(self.source, span),
-                    }
-                    .into(),
+                    })
+                    .with_lint_level(self.empty_statement),
                 );
             }
-            Stmt::Function { name, body, .. } => {
-                if !name.is_snake_case() {
+            _ => (),
+        };
+    }
+
+    /// Add a name to the innermost scope, reporting it when it hides one from an outer scope
+    fn declare(&mut self, name: &str, span: &Range<usize>) {
+        if self.shadowing == LintLevel::Allow {
+            return;
+        }
+
+        let (scope, outer) = self
+            .scopes
+            .split_last_mut()
+            .expect("the top-level scope is never popped");
+        let shadows = outer
+            .iter()
+            .any(|scope| scope.iter().any(|outer| outer == name));
+
+        scope.push(name.to_string());
+
+        if shadows {
+            self.diagnostics.push(
+                Diagnostic::from(ParseWarning::Shadowing {
+                    name: name.to_string(),
+                    span: span.clone(),
+                    context: ParseContext::from_span(self.source, span),
+                })
+                .with_lint_level(self.shadowing),
+            );
+        }
+    }
+
+    fn analyze_loop_body(&mut self, label: &Option<String>, body: &Stmt, span: &Range<usize>) {
+        match label {
+            Some(label) => {
+                self.labels.push(label.clone());
+                self.analyze_body(body, span);
+                self.labels.pop();
+            }
+            None => self.analyze_body(body, span),
+        }
+    }
+
+    /// Body of a control-flow statement, where an empty block is likely a mistake,
+    /// unlike a standalone `{}`
+    fn analyze_body(&mut self, body: &Stmt, span: &Range<usize>) {
+        if let Stmt::Block {
+            statements,
+            span: Span(block),
+        } = body
+            && statements.is_empty()
+        {
+            self.diagnostics.push(
+                ParseWarning::EmptyBlock {
+                    span: block.clone(),
+                    context: ParseContext::from_span(self.source, block),
+                }
+                .into(),
+            );
+        }
+
+        self.analyze(body, span);
+    }
+
+    fn analyze_condition(&mut self, condition: &Expr, span: &Range<usize>) {
+        if let Some(value) = constant_truthiness(condition) {
+            self.diagnostics.push(
+                ParseWarning::ConstantCondition {
+                    value,
+                    span: span.clone(),
+                    context: ParseContext::from_span(self.source, span),
+                }
+                .into(),
+            );
+        }
+    }
+
+    fn analyze_expr(&mut self, expr: &Expr, span: &Range<usize>) {
+        match expr {
+            Expr::Binary { op, left, right } if !matches!(**left, Expr::Binary { .. }) => {
+                self.analyze_binary(*op, left, right, span);
+                self.analyze_expr(left, span);
+                self.analyze_expr(right, span);
+            }
+            Expr::Binary { .. } => {
+                // The parser builds left-nested chains such as `a + b + c` without
+                // recursing, so walk down their left side iteratively
+                let mut spine = Vec::new();
+                let mut leftmost = expr;
+
+                while let Expr::Binary { op, left, right } = leftmost {
+                    self.analyze_binary(*op, left, right, span);
+                    spine.push(right);
+                    leftmost = left;
+                }
+
+                self.analyze_expr(leftmost, span);
+
+                for right in spine.into_iter().rev() {
+                    self.analyze_expr(right, span);
+                }
+            }
+            Expr::Block { statements, tail } => {
+                self.scopes.push(Vec::new());
+
+                for stmt in statements {
+                    self.analyze(stmt, span);
+                }
+
+                if let Some(tail) = tail {
+                    self.analyze_expr(tail, span);
+                }
+
+                self.scopes.pop();
+            }
+            Expr::Call { callee, args, .. } => {
+                self.analyze_expr(callee, span);
+
+                for arg in args {
+                    self.analyze_expr(arg, span);
+                }
+            }
+            Expr::Array { elements } | Expr::Sequence(elements) => {
+                for element in elements {
+                    self.analyze_expr(element, span);
+                }
+            }
+            Expr::Object { entries } => {
+                for (_, value) in entries {
+                    self.analyze_expr(value, span);
+                }
+            }
+            Expr::Index { object, index } => {
+                self.analyze_expr(object, span);
+                self.analyze_expr(index, span);
+            }
+            Expr::Assignment { target, value } | Expr::CompoundAssignment { target, value, .. } => {
+                // Compound assignments such as `x += x` still change the target
+                if matches!(expr, Expr::Assignment { .. }) && target == value {
                     self.diagnostics.push(
-                        ParseWarning::NamingConvention {
-                            message: format!("expected '{}'", name.to_snake_case()),
+                        ParseWarning::SelfAssignment {
                             span: span.clone(),
-                            context: ParseContext::from_span(self.source, &span),
+                            context: ParseContext::from_span(self.source, span),
                         }
                         .into(),
                     );
                 }
 
-                self.analyze(&body, span);
+                self.analyze_expr(target, span);
+                self.analyze_expr(value, span);
             }
-            Stmt::Block { statements } => {
-                for stmt in statements {
-                    self.analyze(stmt, span);
-                }
+            Expr::Unary { operand, .. }
+            | Expr::PreIncrement { operand }
+            | Expr::PostIncrement { operand }
+            | Expr::PreDecrement { operand }
+            | Expr::PostDecrement { operand } => self.analyze_expr(operand, span),
+            Expr::Group(expr) | Expr::Spread { expr } | Expr::Member { object: expr, .. } => {
+                self.analyze_expr(expr, span)
             }
-            Stmt::For { init, body, .. } => {
-                if let Some(stmt) = init {
-                    self.analyze(&stmt, span);
+            Expr::Literal(_) | Expr::Variable(_) => (),
+        }
+    }
+
+    fn analyze_binary(&mut self, op: BinaryOp, left: &Expr, right: &Expr, span: &Range<usize>) {
+        // Comparisons are left-associative, so a chain nests on the left
+        if op.is_comparison() && matches!(left, Expr::Binary { op, .. } if op.is_comparison()) {
+            self.diagnostics.push(
+                ParseWarning::ChainedComparison {
+                    span: span.clone(),
+                    context: ParseContext::from_span(self.source, span),
                 }
+                .into(),
+            );
+        }
 
-                self.analyze(&body, span);
-            }
-            _ => (),
-        };
+        let divides = matches!(op, BinaryOp::Div | BinaryOp::FloorDiv | BinaryOp::Mod);
+
+        if divides && fold_constant(right).is_some_and(|value| is_zero(&value)) {
+            self.diagnostics.push(
+                ParseWarning::DivisionByZero {
+                    span: span.clone(),
+                    context: ParseContext::from_span(self.source, span),
+                }
+                .into(),
+            );
+        }
+    }
+
+    /// Warn when a declared name does not follow the style configured for its kind
+    fn check_name(&mut self, name: &str, style: NamingStyle, span: &Range<usize>) {
+        if !style.matches(name) {
+            self.diagnostics.push(
+                ParseWarning::NamingConvention {
+                    message: format!("expected '{}'", style.apply(name)),
+                    span: span.clone(),
+                    context: ParseContext::from_span(self.source, span),
+                }
+                .into(),
+            );
+        }
     }
 
     pub fn finalize(self) -> Vec<Diagnostic> {
-        self.diagnostics
+        Diagnostic::merge_duplicates(self.diagnostics)
+    }
+}
+
+/// Truthiness of a constant expression
+fn constant_truthiness(expr: &Expr) -> Option<bool> {
+    fold_constant(expr).map(|value| value.is_truthy())
+}
+
+/// Value of an expression built only from literals and operators, unless evaluating it fails
+fn fold_constant(expr: &Expr) -> Option<Value> {
+    match is_constant(expr) {
+        true => eval::eval_expr(expr).ok(),
+        false => None,
+    }
+}
+
+fn is_constant(expr: &Expr) -> bool {
+    match expr {
+        Expr::Literal(_) => true,
+        Expr::Group(expr) | Expr::Unary { operand: expr, .. } => is_constant(expr),
+        Expr::Binary { .. } => {
+            // Left-nested chains can be longer than the stack is deep
+            let mut leftmost = expr;
+
+            while let Expr::Binary { left, right, .. } = leftmost {
+                if !is_constant(right) {
+                    return false;
+                }
+
+                leftmost = left;
+            }
+
+            is_constant(leftmost)
+        }
+        _ => false,
+    }
+}
+
+fn is_zero(value: &Value) -> bool {
+    match value {
+        Value::Int(i) => *i == 0,
+        Value::Float(f) => *f == 0.0,
+        _ => false,
     }
 }
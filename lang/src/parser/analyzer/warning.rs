@@ -32,6 +32,84 @@ pub enum ParseWarning {
         span: Range<usize>,
         context: ParseContext,
     },
+
+    /// Comparison used as the operand of another comparison, e.g. `a < b < c`
+    ChainedComparison {
+        span: Range<usize>,
+        context: ParseContext,
+    },
+
+    /// `break` or `continue` naming a label no enclosing loop has
+    UndefinedLabel {
+        name: String,
+        span: Range<usize>,
+        context: ParseContext,
+    },
+
+    /// `if`, `else` or loop body without any statements
+    EmptyBlock {
+        span: Range<usize>,
+        context: ParseContext,
+    },
+
+    /// `if` or `while` condition that is always truthy or always falsy
+    ConstantCondition {
+        value: bool,
+        span: Range<usize>,
+        context: ParseContext,
+    },
+
+    /// Assignment of an expression to itself, e.g. `x = x`
+    SelfAssignment {
+        span: Range<usize>,
+        context: ParseContext,
+    },
+
+    /// Declaration hiding a binding of the same name from an enclosing scope
+    Shadowing {
+        name: String,
+        span: Range<usize>,
+        context: ParseContext,
+    },
+
+    /// Division or remainder by a constant zero, reported as an error
+    DivisionByZero {
+        span: Range<usize>,
+        context: ParseContext,
+    },
+
+    /// Lone `;` that does nothing
+    EmptyStatement {
+        span: Range<usize>,
+        context: ParseContext,
+    },
+
+    /// `^` or `^=`, which is bitwise XOR rather than exponentiation
+    XorOperator {
+        span: Range<usize>,
+        context: ParseContext,
+    },
+}
+
+impl ParseWarning {
+    /// Stable identifier of the kind of warning, for filtering and documentation links
+    pub fn code(&self) -> &'static str {
+        match self {
+            ParseWarning::UnusedVariable { .. } => "W0001",
+            ParseWarning::UnusedFunction { .. } => "W0002",
+            ParseWarning::NamingConvention { .. } => "W0003",
+            ParseWarning::UnreachableCode { .. } => "W0004",
+            ParseWarning::ChainedComparison { .. } => "W0005",
+            ParseWarning::UndefinedLabel { .. } => "W0006",
+            ParseWarning::EmptyBlock { .. } => "W0007",
+            ParseWarning::ConstantCondition { .. } => "W0008",
+            ParseWarning::SelfAssignment { .. } => "W0009",
+            ParseWarning::Shadowing { .. } => "W0010",
+            ParseWarning::DivisionByZero { .. } => "W0011",
+            ParseWarning::EmptyStatement { .. } => "W0012",
+            ParseWarning::XorOperator { .. } => "W0013",
+        }
+    }
 }
 
 impl std::fmt::Display for ParseWarning {
@@ -57,6 +135,45 @@ impl std::fmt::Display for ParseWarning {
                 // write!(f, "\n{}", context)?;
                 Ok(())
             }
+            ParseWarning::ChainedComparison { .. } => {
+                write!(
+                    f,
+                    "Comparisons cannot be chained, use parentheses or '&&' to make the intent explicit"
+                )?;
+                Ok(())
+            }
+            ParseWarning::UndefinedLabel { name, .. } => {
+                write!(f, "No enclosing loop is labeled '{}'", name)?;
+                Ok(())
+            }
+            ParseWarning::EmptyBlock { .. } => {
+                write!(f, "Empty block")?;
+                Ok(())
+            }
+            ParseWarning::ConstantCondition { value, .. } => {
+                write!(f, "Condition is always {}", value)?;
+                Ok(())
+            }
+            ParseWarning::SelfAssignment { .. } => {
+                write!(f, "Assigning a value to itself has no effect")?;
+                Ok(())
+            }
+            ParseWarning::Shadowing { name, .. } => {
+                write!(f, "Shadows outer binding '{}'", name)?;
+                Ok(())
+            }
+            ParseWarning::DivisionByZero { .. } => {
+                write!(f, "Division by zero")?;
+                Ok(())
+            }
+            ParseWarning::EmptyStatement { .. } => {
+                write!(f, "Empty statement")?;
+                Ok(())
+            }
+            ParseWarning::XorOperator { .. } => {
+                write!(f, "'^' is bitwise XOR, use '**' for exponentiation")?;
+                Ok(())
+            }
         }
     }
 }
@@ -1,8 +1,15 @@
+use std::ops::Range;
+
 use logos::Logos;
 
-use crate::lexer::Token;
+use crate::{
+    ast::op::BinaryOp,
+    lexer::{StringQuote, Token, block_comment_end, next_token},
+};
 
-use super::{ParseContext, ParseError, Parser, ParserConfig, TokenSpan};
+use super::{
+    LintLevel, NamingStyle, ParseContext, ParseError, Parser, ParserConfig, TokenSpan, Trivia,
+};
 
 pub struct ParserBuilder<'a> {
     source: &'a str,
@@ -27,38 +34,287 @@ impl<'a> ParserBuilder<'a> {
         self
     }
 
+    pub fn fold_negative_literals(mut self, fold: bool) -> Self {
+        self.config.fold_negative_literals = fold;
+        self
+    }
+
+    pub fn require_let_initializer(mut self, require: bool) -> Self {
+        self.config.require_let_initializer = require;
+        self
+    }
+
+    pub fn allow_optional_semicolons(mut self, allow: bool) -> Self {
+        self.config.allow_optional_semicolons = allow;
+        self
+    }
+
+    /// Skip the analyzer when only the AST is needed, `parse` then reports no diagnostics
+    pub fn analyze(mut self, analyze: bool) -> Self {
+        self.config.run_analysis = analyze;
+        self
+    }
+
+    pub fn shadowing(mut self, level: LintLevel) -> Self {
+        self.config.shadowing = level;
+        self
+    }
+
+    /// Report lone `;` statements, which parse as [`Stmt::Empty`](crate::ast::stmt::Stmt::Empty)
+    pub fn empty_statement(mut self, level: LintLevel) -> Self {
+        self.config.empty_statement = level;
+        self
+    }
+
+    /// Point out every `^` and `^=` with a hint that it is bitwise XOR, and that
+    /// `**` raises to a power. Off by default, and with the analyzer
+    pub fn xor_hint(mut self, hint: bool) -> Self {
+        self.config.xor_hint = hint;
+        self
+    }
+
+    /// Quotes that delimit string literals, only double quotes by default. Single
+    /// quoted strings replace char literals
+    pub fn string_quote(mut self, quote: StringQuote) -> Self {
+        self.config.string_quote = quote;
+        self
+    }
+
+    /// Keep lexing past invalid tokens, so [`Parser::parse`] reports all of them as
+    /// error diagnostics rather than `build` failing on the first. Invalid tokens
+    /// inside template interpolations still fail the parse
+    pub fn recover_invalid_tokens(mut self, recover: bool) -> Self {
+        self.config.recover_invalid_tokens = recover;
+        self
+    }
+
+    /// Report every comment along with the index of the token it comes before in
+    /// [`ParseResult::trivia`](super::ParseResult::trivia), for tools that keep comments
+    pub fn record_trivia(mut self, record: bool) -> Self {
+        self.config.record_trivia = record;
+        self
+    }
+
+    /// Fail with [`ParseError::LimitExceeded`] after `max` statements, nested ones included
+    pub fn max_statements(mut self, max: usize) -> Self {
+        self.config.max_statements = Some(max);
+        self
+    }
+
+    /// Fail with [`ParseError::LimitExceeded`] after `max` expression nodes
+    pub fn max_expression_nodes(mut self, max: usize) -> Self {
+        self.config.max_expression_nodes = Some(max);
+        self
+    }
+
+    /// Fail with [`ParseError::BudgetExhausted`] after consuming `steps` tokens, bounding
+    /// the time spent on untrusted input
+    pub fn max_parse_steps(mut self, steps: usize) -> Self {
+        self.config.max_parse_steps = Some(steps);
+        self
+    }
+
+    /// Reject `op` and its compound assignment, e.g. to teach a subset of the language
+    pub fn disable_operator(mut self, op: BinaryOp) -> Self {
+        if !self.config.disabled_operators.contains(&op) {
+            self.config.disabled_operators.push(op);
+        }
+
+        self
+    }
+
+    /// Style the analyzer expects of `let` names, snake case by default
+    pub fn let_naming(mut self, style: NamingStyle) -> Self {
+        self.config.let_naming = style;
+        self
+    }
+
+    /// Style the analyzer expects of `const` names, constant case by default
+    pub fn const_naming(mut self, style: NamingStyle) -> Self {
+        self.config.const_naming = style;
+        self
+    }
+
+    /// Style the analyzer expects of function names, snake case by default
+    pub fn function_naming(mut self, style: NamingStyle) -> Self {
+        self.config.function_naming = style;
+        self
+    }
+
+    /// Style the analyzer expects of parameter names, snake case by default
+    pub fn param_naming(mut self, style: NamingStyle) -> Self {
+        self.config.param_naming = style;
+        self
+    }
+
     pub fn build(self) -> Result<Parser<'a>, ParseError> {
-        let mut lexer = Token::lexer(self.source);
-        let mut tokens = Vec::new();
-
-        while let Some(token_result) = lexer.next() {
-            match token_result {
-                Ok(token) => {
-                    let span = lexer.span();
-                    tokens.push(TokenSpan { token, span });
-                }
-                Err(_) => {
-                    let span = lexer.span();
-                    let invalid_text = &self.source[span.start..span.end.min(self.source.len())];
-
-                    let context = ParseContext::from_span(self.source, &span.clone());
-
-                    return Err(ParseError::BuildError {
-                        message: "Invalid token".to_string(),
-                        invalid_text: invalid_text.to_string(),
-                        span,
-                        context,
-                    });
-                }
+        let range = 0..self.source.len();
+        let quote = self.config.string_quote;
+        let (tokens, comments, lex_errors) = match self.config.recover_invalid_tokens {
+            true => lex_recovering(self.source, range, quote),
+            false => {
+                let (tokens, comments) = lex(self.source, range, quote)?;
+                (tokens, comments, Vec::new())
             }
-        }
+        };
+        let trivia = match self.config.record_trivia {
+            true => trivia(&tokens, &comments),
+            false => Vec::new(),
+        };
 
         Ok(Parser {
             pos: 0,
             depth: 0,
             tokens,
+            comments,
+            source: self.source,
+            config: self.config,
+            streaming: false,
+            offset: 0,
+            stream: None,
+            stream_error: None,
+            statements: 0,
+            expression_nodes: 0,
+            steps: 0,
+            budget_error: None,
+            lex_errors,
+            trivia,
+            hints: Vec::new(),
+        })
+    }
+
+    /// Parser that pulls tokens from the lexer as it goes instead of lexing the
+    /// whole source up front, so `tokens` only holds a small window around the
+    /// current position. Invalid tokens are reported by [`Parser::parse`]
+    pub fn build_streaming(self) -> Parser<'a> {
+        let mut parser = Parser {
+            pos: 0,
+            depth: 0,
+            tokens: Vec::new(),
+            comments: Vec::new(),
             source: self.source,
             config: self.config,
+            streaming: true,
+            offset: 0,
+            stream: Some(Token::lexer(self.source)),
+            stream_error: None,
+            statements: 0,
+            expression_nodes: 0,
+            steps: 0,
+            budget_error: None,
+            lex_errors: Vec::new(),
+            trivia: Vec::new(),
+            hints: Vec::new(),
+        };
+
+        parser.fill();
+        parser
+    }
+}
+
+/// Tokens and comments of `range`, with spans relative to the whole source
+pub(super) fn lex(
+    source: &str,
+    range: Range<usize>,
+    quote: StringQuote,
+) -> Result<(Vec<TokenSpan>, Vec<TokenSpan>), ParseError> {
+    let offset = range.start;
+    let mut lexer = Token::lexer(&source[range]);
+    let mut tokens = Vec::new();
+    let mut comments = Vec::new();
+
+    while let Some(token_result) = next_token(&mut lexer, quote) {
+        let span = lexer.span().start + offset..lexer.span().end + offset;
+
+        match token_result {
+            Ok(token) if token.is_comment() => comments.push(TokenSpan { token, span }),
+            Ok(token) => tokens.push(TokenSpan { token, span }),
+            Err(_) => return Err(invalid_token(source, span, quote)),
+        }
+    }
+
+    Ok((tokens, comments))
+}
+
+/// Like [`lex`], but skips invalid tokens and returns an error for each of them
+pub(super) fn lex_recovering(
+    source: &str,
+    range: Range<usize>,
+    quote: StringQuote,
+) -> (Vec<TokenSpan>, Vec<TokenSpan>, Vec<ParseError>) {
+    let offset = range.start;
+    let mut lexer = Token::lexer(&source[range]);
+    let mut tokens = Vec::new();
+    let mut comments = Vec::new();
+    let mut errors = Vec::new();
+
+    while let Some(token_result) = next_token(&mut lexer, quote) {
+        let span = lexer.span().start + offset..lexer.span().end + offset;
+
+        match token_result {
+            Ok(token) if token.is_comment() => comments.push(TokenSpan { token, span }),
+            Ok(token) => tokens.push(TokenSpan { token, span }),
+            Err(_) => errors.push(invalid_token(source, span, quote)),
+        }
+    }
+
+    (tokens, comments, errors)
+}
+
+/// Each comment along with the index of the first token after it
+fn trivia(tokens: &[TokenSpan], comments: &[TokenSpan]) -> Vec<Trivia> {
+    comments
+        .iter()
+        .map(|comment| Trivia {
+            comment: comment.token.clone(),
+            span: comment.span.clone(),
+            next_token: tokens.partition_point(|token| token.span.start < comment.span.start),
         })
+        .collect()
+}
+
+pub(super) fn invalid_token(source: &str, span: Range<usize>, quote: StringQuote) -> ParseError {
+    let end = span.end.min(source.len());
+    let text = &source[span.start..end];
+
+    let is_string_quote = |c| c == '"' || (c == '\'' && quote != StringQuote::Double);
+    let (message, span) = match text.chars().next() {
+        // The lexer gives up on an unclosed string at the end of input, but the
+        // literal most likely was meant to end on its own line
+        Some(open) if is_string_quote(open) && !is_closed(text, open) => {
+            let line_end = text.find('\n').map_or(end, |i| span.start + i);
+            ("Unterminated string literal", span.start..line_end)
+        }
+        // Likewise for a comment, which runs to the end of input either way
+        Some('/') if text.starts_with("/*") && block_comment_end(&text[2..]).is_none() => {
+            ("Unterminated block comment", span.start..end)
+        }
+        _ => ("Invalid token", span),
+    };
+
+    let context = ParseContext::from_span(source, &span.clone());
+
+    ParseError::BuildError {
+        message: message.to_string(),
+        invalid_text: source[span.clone()].to_string(),
+        span,
+        context,
+    }
+}
+
+/// Whether the string literal opening `text` has a closing `quote`
+fn is_closed(text: &str, quote: char) -> bool {
+    let mut chars = text.chars().skip(1);
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            c if c == quote => return true,
+            _ => {}
+        }
     }
+
+    false
 }
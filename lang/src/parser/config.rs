@@ -1,3 +1,56 @@
+use inflections::Inflect;
+
+use crate::{ast::op::BinaryOp, lexer::StringQuote};
+
+/// How an optional analyzer check is reported
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LintLevel {
+    /// Skip the check
+    #[default]
+    Allow,
+    Info,
+    Warn,
+}
+
+/// Case convention the analyzer expects a declared name to follow
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NamingStyle {
+    /// `snake_case`
+    Snake,
+    /// `camelCase`
+    Camel,
+    /// `PascalCase`
+    Pascal,
+    /// `CONSTANT_CASE`
+    Constant,
+    /// Accept any name
+    None,
+}
+
+impl NamingStyle {
+    /// Whether `name` already follows the style
+    pub fn matches(&self, name: &str) -> bool {
+        match self {
+            NamingStyle::Snake => name.is_snake_case(),
+            NamingStyle::Camel => name.is_camel_case(),
+            NamingStyle::Pascal => name.is_pascal_case(),
+            NamingStyle::Constant => name.is_constant_case(),
+            NamingStyle::None => true,
+        }
+    }
+
+    /// `name` rewritten to follow the style
+    pub fn apply(&self, name: &str) -> String {
+        match self {
+            NamingStyle::Snake => name.to_snake_case(),
+            NamingStyle::Camel => name.to_camel_case(),
+            NamingStyle::Pascal => name.to_pascal_case(),
+            NamingStyle::Constant => name.to_constant_case(),
+            NamingStyle::None => name.to_string(),
+        }
+    }
+}
+
 /// Parser configuration options
 #[derive(Debug, Clone)]
 pub struct ParserConfig {
@@ -5,6 +58,44 @@ pub struct ParserConfig {
     pub(super) allow_trailing_commas: bool,
     /// Maximum recursion depth to prevent stack overflow
     pub(super) max_recursion_depth: usize,
+    /// Parse `-` directly followed by a number literal as a negative literal
+    pub(super) fold_negative_literals: bool,
+    /// Reject `let x;` instead of defaulting the value to null
+    pub(super) require_let_initializer: bool,
+    /// Let a line break, `}` or end of file terminate a statement without `;`
+    pub(super) allow_optional_semicolons: bool,
+    /// Run the analyzer after parsing, without it no warnings are reported
+    pub(super) run_analysis: bool,
+    /// Report declarations that shadow a binding from an enclosing scope
+    pub(super) shadowing: LintLevel,
+    /// Report lone `;` statements
+    pub(super) empty_statement: LintLevel,
+    /// Hint that `^` is bitwise XOR, for users expecting exponentiation
+    pub(super) xor_hint: bool,
+    /// Quotes that delimit string literals
+    pub(super) string_quote: StringQuote,
+    /// Skip invalid tokens and report each as an error diagnostic instead of
+    /// failing on the first one
+    pub(super) recover_invalid_tokens: bool,
+    /// Report every comment and the tokens around it in the parse result
+    pub(super) record_trivia: bool,
+    /// Statements a source may contain, unlimited when `None`
+    pub(super) max_statements: Option<usize>,
+    /// Expression nodes a source may contain, unlimited when `None`
+    pub(super) max_expression_nodes: Option<usize>,
+    /// Tokens the parser may consume, including those of template interpolations.
+    /// Backtracking refunds the tokens it rewinds over, unlimited when `None`
+    pub(super) max_parse_steps: Option<usize>,
+    /// Operators rejected as invalid syntax, along with their compound assignments
+    pub(super) disabled_operators: Vec<BinaryOp>,
+    /// Style expected of `let` names
+    pub(super) let_naming: NamingStyle,
+    /// Style expected of `const` names
+    pub(super) const_naming: NamingStyle,
+    /// Style expected of function names
+    pub(super) function_naming: NamingStyle,
+    /// Style expected of parameter names
+    pub(super) param_naming: NamingStyle,
 }
 
 impl ParserConfig {
@@ -15,6 +106,78 @@ impl ParserConfig {
     pub fn max_recursion_depth(&self) -> usize {
         self.max_recursion_depth
     }
+
+    pub fn fold_negative_literals(&self) -> bool {
+        self.fold_negative_literals
+    }
+
+    pub fn require_let_initializer(&self) -> bool {
+        self.require_let_initializer
+    }
+
+    pub fn allow_optional_semicolons(&self) -> bool {
+        self.allow_optional_semicolons
+    }
+
+    pub fn run_analysis(&self) -> bool {
+        self.run_analysis
+    }
+
+    pub fn shadowing(&self) -> LintLevel {
+        self.shadowing
+    }
+
+    pub fn empty_statement(&self) -> LintLevel {
+        self.empty_statement
+    }
+
+    pub fn xor_hint(&self) -> bool {
+        self.xor_hint
+    }
+
+    pub fn string_quote(&self) -> StringQuote {
+        self.string_quote
+    }
+
+    pub fn recover_invalid_tokens(&self) -> bool {
+        self.recover_invalid_tokens
+    }
+
+    pub fn record_trivia(&self) -> bool {
+        self.record_trivia
+    }
+
+    pub fn max_statements(&self) -> Option<usize> {
+        self.max_statements
+    }
+
+    pub fn max_expression_nodes(&self) -> Option<usize> {
+        self.max_expression_nodes
+    }
+
+    pub fn max_parse_steps(&self) -> Option<usize> {
+        self.max_parse_steps
+    }
+
+    pub fn disabled_operators(&self) -> &[BinaryOp] {
+        &self.disabled_operators
+    }
+
+    pub fn let_naming(&self) -> NamingStyle {
+        self.let_naming
+    }
+
+    pub fn const_naming(&self) -> NamingStyle {
+        self.const_naming
+    }
+
+    pub fn function_naming(&self) -> NamingStyle {
+        self.function_naming
+    }
+
+    pub fn param_naming(&self) -> NamingStyle {
+        self.param_naming
+    }
 }
 
 impl Default for ParserConfig {
@@ -22,6 +185,24 @@ impl Default for ParserConfig {
         Self {
             allow_trailing_commas: true,
             max_recursion_depth: 1000,
+            fold_negative_literals: false,
+            require_let_initializer: false,
+            allow_optional_semicolons: false,
+            run_analysis: true,
+            shadowing: LintLevel::Allow,
+            empty_statement: LintLevel::Allow,
+            xor_hint: false,
+            string_quote: StringQuote::Double,
+            recover_invalid_tokens: false,
+            record_trivia: false,
+            max_statements: None,
+            max_expression_nodes: None,
+            max_parse_steps: None,
+            disabled_operators: Vec::new(),
+            let_naming: NamingStyle::Snake,
+            const_naming: NamingStyle::Constant,
+            function_naming: NamingStyle::Snake,
+            param_naming: NamingStyle::Snake,
         }
     }
 }
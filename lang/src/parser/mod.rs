@@ -1,6 +1,6 @@
 use crate::{
     ast::{expr::Expr, stmt::Stmt},
-    lexer::Token,
+    lexer::{Token, next_token},
     parser::analyzer::Analyzer,
 };
 use std::ops::{Deref, Range};
@@ -9,9 +9,11 @@ mod analyzer;
 mod builder;
 mod config;
 
-pub use analyzer::{Diagnostic, ParseContext, ParseError, ParseWarning};
+pub use analyzer::{
+    Diagnostic, DiagnosticLevel, LimitKind, ParseContext, ParseError, ParseWarning,
+};
 pub use builder::ParserBuilder;
-pub use config::ParserConfig;
+pub use config::{LintLevel, NamingStyle, ParserConfig};
 
 /// Enhanced token with source position information
 #[derive(Debug, Clone)]
@@ -20,6 +22,24 @@ pub struct TokenSpan {
     pub span: Range<usize>,
 }
 
+/// A comment and its place among the tokens the parser sees
+#[derive(Debug, Clone)]
+pub struct Trivia {
+    /// The comment token, holding its text
+    pub comment: Token,
+    pub span: Range<usize>,
+    /// Index of the token the comment comes before, not counting comments. Equals
+    /// the number of tokens for a comment after the last one
+    pub next_token: usize,
+}
+
+impl Trivia {
+    /// Index of the token the comment comes after, if any
+    pub fn previous_token(&self) -> Option<usize> {
+        self.next_token.checked_sub(1)
+    }
+}
+
 impl Deref for TokenSpan {
     type Target = Token;
 
@@ -28,10 +48,19 @@ impl Deref for TokenSpan {
     }
 }
 
+/// Saved parser state to backtrack to after a failed speculative parse
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Checkpoint {
+    pos: usize,
+    depth: usize,
+    steps: usize,
+}
+
 #[derive(Debug)]
 pub struct ParseResult {
     statements: Vec<Stmt>,
     diagnostics: Vec<Diagnostic>,
+    trivia: Vec<Trivia>,
 }
 
 impl ParseResult {
@@ -39,70 +68,384 @@ impl ParseResult {
         &self.statements
     }
 
-    pub fn diagnositcs(&self) -> &[Diagnostic] {
+    pub fn diagnostics(&self) -> &[Diagnostic] {
         &self.diagnostics
     }
+
+    /// Every comment in the source, when built with
+    /// [`record_trivia`](ParserBuilder::record_trivia)
+    pub fn trivia(&self) -> &[Trivia] {
+        &self.trivia
+    }
+
+    pub fn errors(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.with_level(DiagnosticLevel::Error)
+    }
+
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.with_level(DiagnosticLevel::Warn)
+    }
+
+    pub fn infos(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.with_level(DiagnosticLevel::Info)
+    }
+
+    /// Whether any diagnostic is an error, such as a division by zero or, for
+    /// [`Parser::parse_program`], a syntax error
+    pub fn has_errors(&self) -> bool {
+        self.errors().next().is_some()
+    }
+
+    fn with_level(&self, level: DiagnosticLevel) -> impl Iterator<Item = &Diagnostic> {
+        self.diagnostics
+            .iter()
+            .filter(move |diagnostic| diagnostic.level() == level)
+    }
+
+    #[deprecated(note = "misspelled, use `diagnostics` instead")]
+    pub fn diagnositcs(&self) -> &[Diagnostic] {
+        self.diagnostics()
+    }
 }
 
 /// Parser with configuration and safety features
 #[derive(Clone)]
 pub struct Parser<'a> {
+    /// Tokens to parse, without comments
     pub tokens: Vec<TokenSpan>,
+    /// Comments in source order, kept apart so looking tokens up never skips them
+    comments: Vec<TokenSpan>,
     pub config: ParserConfig,
     pub source: &'a str,
     pub pos: usize,
     depth: usize,
+    /// Whether tokens are pulled from the lexer while parsing
+    streaming: bool,
+    /// Number of tokens a streaming parser has dropped from the front of `tokens`
+    offset: usize,
+    /// Lexer a streaming parser pulls the remaining tokens from
+    stream: Option<logos::Lexer<'a, Token>>,
+    /// Invalid token hit while streaming, reported once parsing stops
+    stream_error: Option<ParseError>,
+    /// Statements parsed so far, checked against `max_statements`
+    statements: usize,
+    /// Expression nodes parsed so far, checked against `max_expression_nodes`
+    expression_nodes: usize,
+    /// Tokens consumed so far, checked against `max_parse_steps`
+    steps: usize,
+    /// Set once the step budget runs out, after which the input appears to end
+    budget_error: Option<ParseError>,
+    /// Invalid tokens skipped when recovering, reported as diagnostics by `parse`
+    lex_errors: Vec<ParseError>,
+    /// Comments recorded for the parse result when `record_trivia` is set
+    trivia: Vec<Trivia>,
+    /// Hints about the source found while parsing, reported as diagnostics by `parse`
+    hints: Vec<Diagnostic>,
 }
 
+/// Non-comment tokens a streaming parser keeps buffered from the current position on,
+/// enough for the deepest lookahead, `peek_nth(3)` when telling objects from blocks
+const LOOKAHEAD: usize = 4;
+
 impl<'a> Parser<'a> {
-    fn span(&self) -> Option<&TokenSpan> {
-        let mut pos = self.pos;
+    /// Token at an absolute position, accounting for tokens dropped while streaming
+    fn token(&self, pos: usize) -> Option<&TokenSpan> {
+        self.tokens.get(pos.checked_sub(self.offset)?)
+    }
 
-        while let Some(token_span) = self.tokens.get(pos) {
-            match token_span.is_comment() {
-                true => pos += 1,
-                false => return Some(token_span),
+    /// Buffered tokens from the current position on
+    fn upcoming(&self) -> &[TokenSpan] {
+        if self.budget_error.is_some() {
+            return &[];
+        }
+
+        &self.tokens[(self.pos - self.offset).min(self.tokens.len())..]
+    }
+
+    /// Pull tokens from the lexer until enough lie ahead of the current position
+    fn fill(&mut self) {
+        let Some(lexer) = self.stream.as_mut() else {
+            return;
+        };
+
+        let quote = self.config.string_quote;
+
+        while self.tokens.len() + self.offset < self.pos + LOOKAHEAD {
+            match next_token(lexer, quote) {
+                Some(Ok(token)) => {
+                    let token_span = TokenSpan {
+                        token,
+                        span: lexer.span(),
+                    };
+
+                    if token_span.is_comment() && self.config.record_trivia {
+                        self.trivia.push(Trivia {
+                            comment: token_span.token.clone(),
+                            span: token_span.span.clone(),
+                            next_token: self.offset + self.tokens.len(),
+                        });
+                    }
+
+                    match token_span.is_comment() {
+                        true => self.comments.push(token_span),
+                        false => self.tokens.push(token_span),
+                    }
+                }
+                Some(Err(_)) if self.config.recover_invalid_tokens => {
+                    let error = builder::invalid_token(self.source, lexer.span(), quote);
+                    self.lex_errors.push(error);
+                }
+                Some(Err(_)) => {
+                    self.stream_error =
+                        Some(builder::invalid_token(self.source, lexer.span(), quote));
+                    self.stream = None;
+                    return;
+                }
+                None => {
+                    self.stream = None;
+                    return;
+                }
             }
         }
-        None
     }
 
-    fn parse(&mut self) -> Result<ParseResult, ParseError> {
+    /// Drop the tokens a streaming parser no longer needs, keeping the previous
+    /// one for line break checks
+    fn discard(&mut self) {
+        if !self.streaming {
+            return;
+        }
+
+        let keep = self.pos.saturating_sub(1).max(self.offset);
+        self.tokens.drain(..keep - self.offset);
+        self.offset = keep;
+
+        if let Some(first) = self.tokens.first() {
+            let start = first.span.start;
+            let passed = self
+                .comments
+                .partition_point(|comment| comment.span.end <= start);
+            self.comments.drain(..passed);
+        }
+    }
+
+    /// Lex the rest of a streaming parser's source, so an invalid token anywhere
+    /// is reported like it is when building a buffered parser
+    fn take_stream_error(&mut self) -> Option<ParseError> {
+        if self.config.recover_invalid_tokens {
+            return None;
+        }
+
+        if let Some(mut lexer) = self.stream.take() {
+            let quote = self.config.string_quote;
+
+            while let Some(token_result) = next_token(&mut lexer, quote) {
+                if token_result.is_err() {
+                    return Some(builder::invalid_token(self.source, lexer.span(), quote));
+                }
+            }
+        }
+
+        self.stream_error.take()
+    }
+
+    fn span(&self) -> Option<&TokenSpan> {
+        match self.budget_error {
+            Some(_) => None,
+            None => self.token(self.pos),
+        }
+    }
+
+    /// Error that stopped parsing early, which takes precedence over the error it caused
+    pub(crate) fn take_halt_error(&mut self) -> Option<ParseError> {
+        self.budget_error
+            .take()
+            .or_else(|| self.take_stream_error())
+    }
+
+    /// Parse the whole source as a program, for parsers built with a custom config
+    pub fn parse(&mut self) -> Result<ParseResult, ParseError> {
+        self.parse_statements(false)
+    }
+
+    /// Parse every statement, failing on the first error unless `recover` is set.
+    /// Recovering reports each error as a diagnostic and resumes at the next statement
+    fn parse_statements(&mut self, recover: bool) -> Result<ParseResult, ParseError> {
         let mut statements: Vec<Stmt> = vec![];
-        let mut analyzer = Analyzer::new(self.source);
+        let mut errors: Vec<Diagnostic> = vec![];
+        let mut analyzer = self
+            .config
+            .run_analysis
+            .then(|| Analyzer::new(self.source, &self.config));
 
         while !self.eof() {
             let span = match self.span().map(|x| &x.span) {
                 Some(res) => res.clone(),
                 None => self.pos..self.pos,
             };
+            let checkpoint = self.checkpoint();
+
+            let statement = match self.next_statement() {
+                Ok(statement) => statement,
+                Err(error) if recover => {
+                    let halt = matches!(
+                        error,
+                        ParseError::BudgetExhausted { .. } | ParseError::LimitExceeded { .. }
+                    );
+                    errors.push(error.into());
+
+                    if halt {
+                        break;
+                    }
+
+                    self.restore(checkpoint);
+                    self.synchronize();
+                    continue;
+                }
+                Err(error) => return Err(error),
+            };
 
-            let statement = self.safe_call(|parser| Stmt::parse(parser))?;
+            if let Some(analyzer) = &mut analyzer {
+                analyzer.analyze(&statement, &span);
+            }
 
-            analyzer.analyze(&statement, &span);
             statements.push(statement);
         }
 
-        let diagnostics = analyzer.finalize();
+        if let Some(error) = self.take_halt_error() {
+            match recover {
+                true => errors.push(error.into()),
+                false => return Err(error),
+            }
+        }
+
+        let mut diagnostics: Vec<Diagnostic> =
+            self.lex_errors.drain(..).map(Diagnostic::from).collect();
+        diagnostics.extend(errors);
+        diagnostics.append(&mut self.hints);
+        diagnostics.extend(analyzer.map(Analyzer::finalize).unwrap_or_default());
 
         Ok(ParseResult {
             diagnostics,
             statements,
+            trivia: std::mem::take(&mut self.trivia),
         })
     }
 
+    /// Record a hint once, tokens parsed again after backtracking do not repeat it.
+    /// Dropped when analysis is disabled
+    pub(crate) fn hint(&mut self, warning: ParseWarning) {
+        let hint = Diagnostic::from(warning);
+
+        if self.config.run_analysis && !self.hints.contains(&hint) {
+            self.hints.push(hint);
+        }
+    }
+
+    /// Parse the statement at the current position, then drop the tokens before it
+    /// when streaming
+    pub(crate) fn next_statement(&mut self) -> Result<Stmt, ParseError> {
+        let statement = self
+            .safe_call(|parser| Stmt::parse(parser))
+            .map_err(|error| self.take_halt_error().unwrap_or(error))?;
+        self.discard();
+
+        Ok(statement)
+    }
+
+    /// Skip the statement starting at the current position: up to and including its
+    /// `;` or closing `}`, or up to the next keyword that starts a statement.
+    /// Statements only nest in braces, so tokens inside them never end it
+    fn synchronize(&mut self) {
+        let mut depth = 0usize;
+        let mut first = true;
+
+        while let Some(token) = self.peek() {
+            let starts_statement = Stmt::is_statement_start(token) && token != &Token::LeftBrace;
+
+            if depth == 0 && !first && starts_statement {
+                return;
+            }
+
+            let token = token.clone();
+            self.advance();
+            first = false;
+
+            match token {
+                Token::LeftBrace => depth += 1,
+                Token::RightBrace if depth <= 1 => {
+                    self.consume(&Token::Semicolon);
+                    return;
+                }
+                Token::RightBrace => depth -= 1,
+                Token::Semicolon if depth == 0 => return,
+                _ => {}
+            }
+        }
+    }
+
     pub(crate) fn eof(&self) -> bool {
-        self.span().is_none() // Use current() which already skips comments
+        self.span().is_none()
     }
 
     pub(crate) fn eof_position(&self) -> usize {
-        self.tokens.last().map(|pt| pt.span.end).unwrap_or(0)
+        let last = |tokens: &[TokenSpan]| tokens.last().map(|pt| pt.span.end).unwrap_or(0);
+
+        last(&self.tokens).max(last(&self.comments))
     }
 
     pub(crate) fn peek(&self) -> Option<&Token> {
         self.span().map(|ts| &ts.token)
     }
 
+    /// Parser over another part of the same source, for template interpolations
+    pub(crate) fn sub_parser(&self, range: Range<usize>) -> Result<Parser<'a>, ParseError> {
+        let (tokens, comments) = builder::lex(self.source, range, self.config.string_quote)?;
+
+        Ok(Parser {
+            tokens,
+            comments,
+            config: self.config.clone(),
+            source: self.source,
+            pos: 0,
+            depth: self.depth,
+            streaming: false,
+            offset: 0,
+            stream: None,
+            stream_error: None,
+            statements: self.statements,
+            expression_nodes: self.expression_nodes,
+            steps: self.steps,
+            budget_error: None,
+            lex_errors: Vec::new(),
+            trivia: Vec::new(),
+            hints: Vec::new(),
+        })
+    }
+
+    /// Take over what a sub-parser counted against the limits, the budget error it
+    /// ran into and its hints, so an interpolation costs the same as inline code
+    pub(crate) fn merge_sub_parser(&mut self, inner: Parser<'a>) {
+        self.statements = inner.statements;
+        self.expression_nodes = inner.expression_nodes;
+        self.steps = inner.steps;
+        self.budget_error = self.budget_error.take().or(inner.budget_error);
+
+        for hint in inner.hints {
+            if !self.hints.contains(&hint) {
+                self.hints.push(hint);
+            }
+        }
+    }
+
+    /// The `n`th upcoming token, `n` must stay below `LOOKAHEAD` for streaming
+    /// parsers to see it
+    pub(crate) fn peek_nth(&self, n: usize) -> Option<&Token> {
+        self.upcoming()
+            .get(n)
+            .map(|token_span| &token_span.token)
+    }
+
     pub(crate) fn error(&self, message: &str, expected: Option<&str>) -> ParseError {
         match (self.span(), expected) {
             (Some(token_span), Some(exp)) => ParseError::UnexpectedToken {
@@ -136,28 +479,70 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Consume the next token, or return `None` at the end of input or once the
+    /// step budget is spent
     pub(crate) fn advance(&mut self) -> Option<&TokenSpan> {
-        let start_pos = self.pos;
+        let pos = self.pos;
+        self.span()?;
+        self.spend_step()?;
 
-        loop {
-            match self.pos >= self.tokens.len() {
-                true => return None,
-                false => {}
-            }
-
-            let span = &self.tokens[self.pos];
-            self.pos += 1;
+        self.pos += 1;
+        self.fill();
+        self.token(pos)
+    }
 
-            match span.is_comment() {
-                false => return Some(span),
-                true => {}
-            }
+    /// Take a step from the budget, making the rest of the input appear missing once
+    /// it runs out
+    fn spend_step(&mut self) -> Option<()> {
+        let Some(max_steps) = self.config.max_parse_steps() else {
+            return Some(());
+        };
 
-            match self.pos > start_pos + self.tokens.len() {
-                true => return None,
-                false => {}
-            }
+        if self.steps < max_steps {
+            self.steps += 1;
+            return Some(());
         }
+
+        let position = self.position();
+        self.budget_error = Some(ParseError::BudgetExhausted {
+            max_steps,
+            context: ParseContext::from_span(self.source, &(position..position)),
+            position,
+        });
+
+        None
+    }
+
+    /// Text of the comments between the previous token and the next one, skipping
+    /// comments that trail on the previous token's line
+    pub(crate) fn leading_comments(&self) -> Vec<String> {
+        let previous_end = self
+            .pos
+            .checked_sub(1)
+            .and_then(|i| self.token(i))
+            .map(|token_span| token_span.span.end);
+
+        let start = previous_end.unwrap_or(0);
+        let end = self.span().map_or(self.source.len(), |next| next.span.start);
+        let first = self
+            .comments
+            .partition_point(|comment| comment.span.start < start);
+
+        self.comments[first..]
+            .iter()
+            .take_while(|comment| comment.span.start < end)
+            .filter(|token_span| match previous_end {
+                Some(end) => self.source[end..token_span.span.start].contains('\n'),
+                None => true,
+            })
+            .filter_map(|token_span| match &token_span.token {
+                Token::LineComment(text)
+                | Token::BlockComment(text)
+                | Token::DocLineComment(text)
+                | Token::DocBlockComment(text) => Some(text.clone()),
+                _ => None,
+            })
+            .collect()
     }
 
     pub(crate) fn consume(&mut self, token: &Token) -> bool {
@@ -195,6 +580,70 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Where the next token starts, or the end of input
+    pub(crate) fn position(&self) -> usize {
+        match self.span() {
+            Some(ts) => ts.span.start,
+            None => self.eof_position(),
+        }
+    }
+
+    /// Range from `start` to the end of the last consumed token
+    pub(crate) fn span_from(&self, start: usize) -> Range<usize> {
+        let end = match self.pos.checked_sub(1).and_then(|i| self.token(i)) {
+            Some(ts) => ts.span.end.max(start),
+            None => start,
+        };
+
+        start..end
+    }
+
+    /// Whether a line break separates the last consumed token from the next one
+    pub(crate) fn line_break_before(&self) -> bool {
+        match (self.pos.checked_sub(1).and_then(|i| self.token(i)), self.span()) {
+            (Some(previous), Some(next)) => {
+                self.source[previous.span.end..next.span.start].contains('\n')
+            }
+            _ => false,
+        }
+    }
+
+    /// Whether the statement ends here without a `;`, at a line break, `}` or end of file
+    pub(crate) fn implicit_terminator(&self) -> bool {
+        self.config.allow_optional_semicolons()
+            && (self.eof() || self.peek() == Some(&Token::RightBrace) || self.line_break_before())
+    }
+
+    /// Expect the `;` ending a statement, optional at clear boundaries when enabled
+    pub(crate) fn expect_terminator(&mut self) -> Result<(), ParseError> {
+        if self.consume(&Token::Semicolon) || self.implicit_terminator() {
+            return Ok(());
+        }
+
+        // Another statement or the end of the block already following means this
+        // one only lacks its `;`
+        let ended = self.peek().is_some_and(Stmt::is_statement_start)
+            || self.peek() == Some(&Token::RightBrace)
+            || self.line_break_before();
+
+        match ended {
+            true => {
+                let end = self
+                    .pos
+                    .checked_sub(1)
+                    .and_then(|i| self.token(i))
+                    .map_or(0, |token_span| token_span.span.end);
+
+                Err(ParseError::MissingToken {
+                    expected: "semicolon".to_string(),
+                    span: end..end,
+                    context: ParseContext::from_span(self.source, &(end..end)),
+                })
+            }
+            false => self.expect(Token::Semicolon),
+        }
+    }
+
     pub(crate) fn safe_call<T, F>(&mut self, f: F) -> Result<T, ParseError>
     where
         F: FnOnce(&mut Self) -> Result<T, ParseError>,
@@ -202,13 +651,15 @@ impl<'a> Parser<'a> {
         self.depth += 1;
 
         let result = match self.depth > self.config.max_recursion_depth {
-            true => Err(ParseError::TooMuchRecursion {
-                max_depth: self.config.max_recursion_depth,
-                position: match self.span() {
-                    Some(ts) => ts.span.start,
-                    None => self.eof_position(),
-                },
-            }),
+            true => {
+                let position = self.position();
+
+                Err(ParseError::TooMuchRecursion {
+                    max_depth: self.config.max_recursion_depth,
+                    context: ParseContext::from_span(self.source, &(position..position)),
+                    position,
+                })
+            }
             false => f(self),
         };
 
@@ -217,6 +668,103 @@ impl<'a> Parser<'a> {
         result
     }
 
+    /// Consume the opening delimiter `open` and run `f`, which parses up to and
+    /// including the closing one. Input ending before then is reported as unclosed
+    /// at the opener, the innermost one when several are left open.
+    pub(crate) fn delimited<T, F>(&mut self, open: Token, f: F) -> Result<T, ParseError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, ParseError>,
+    {
+        let start = self.position();
+        self.expect(open)?;
+        let opened_at = self.span_from(start);
+
+        f(self).map_err(|err| match err {
+            ParseError::UnexpectedEof {
+                expected,
+                position,
+                context,
+            } => ParseError::Unclosed {
+                delimiter: self.source[opened_at.clone()].to_string(),
+                expected,
+                opened_line: ParseContext::from_span(self.source, &opened_at).line_number,
+                opened_at,
+                position,
+                context,
+            },
+            err => err,
+        })
+    }
+
+    /// Count a parsed statement, failing once there are more than `max_statements`
+    pub(crate) fn count_statement(&mut self) -> Result<(), ParseError> {
+        self.statements += 1;
+        self.check_limit(
+            LimitKind::Statements,
+            self.statements,
+            self.config.max_statements(),
+        )
+    }
+
+    /// Count a parsed expression node, failing once there are more than `max_expression_nodes`
+    pub(crate) fn count_expression_node(&mut self) -> Result<(), ParseError> {
+        self.expression_nodes += 1;
+        self.check_limit(
+            LimitKind::ExpressionNodes,
+            self.expression_nodes,
+            self.config.max_expression_nodes(),
+        )
+    }
+
+    fn check_limit(
+        &self,
+        kind: LimitKind,
+        count: usize,
+        limit: Option<usize>,
+    ) -> Result<(), ParseError> {
+        match limit {
+            Some(limit) if count > limit => {
+                let position = self.position();
+
+                Err(ParseError::LimitExceeded {
+                    kind,
+                    limit,
+                    context: ParseContext::from_span(self.source, &(position..position)),
+                    position,
+                })
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Tokens lexed so far, without comments. A streaming parser only keeps the
+    /// ones it still needs
+    pub fn tokens(&self) -> &[TokenSpan] {
+        &self.tokens
+    }
+
+    /// Comments lexed so far, in source order
+    pub fn comments(&self) -> &[TokenSpan] {
+        &self.comments
+    }
+
+    /// Save the current position, nesting depth and spent steps
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint {
+            pos: self.pos,
+            depth: self.depth,
+            steps: self.steps,
+        }
+    }
+
+    /// Rewind to a checkpoint taken on this parser, refunding the steps spent since,
+    /// so tokens consumed again after backtracking are only charged once
+    pub fn restore(&mut self, checkpoint: Checkpoint) {
+        self.pos = checkpoint.pos;
+        self.depth = checkpoint.depth;
+        self.steps = checkpoint.steps;
+    }
+
     pub fn builder(source: &'a str) -> ParserBuilder<'a> {
         ParserBuilder::new(source)
     }
@@ -227,6 +775,35 @@ impl<'a> Parser<'a> {
         parser.parse()
     }
 
+    /// Parse a whole program without stopping at the first error. Every lexer and
+    /// parse error is reported as a diagnostic, next to the statements that did parse
+    pub fn parse_program(source: &'a str) -> ParseResult {
+        Self::builder(source)
+            .recover_invalid_tokens(true)
+            .build()
+            .and_then(|mut parser| parser.parse_statements(true))
+            .unwrap_or_else(|error| ParseResult {
+                statements: Vec::new(),
+                diagnostics: vec![error.into()],
+                trivia: Vec::new(),
+            })
+    }
+
+    /// Whether the source only fails because it ends early, like after an unclosed
+    /// brace or a trailing binary operator. A REPL can read more input rather than
+    /// report the error, which is still reported once input is complete
+    pub fn is_incomplete(source: &'a str) -> bool {
+        let result = Self::builder(source)
+            .analyze(false)
+            .build()
+            .and_then(|mut parser| parser.parse());
+
+        matches!(
+            result,
+            Err(ParseError::UnexpectedEof { .. } | ParseError::Unclosed { .. })
+        )
+    }
+
     pub fn parse_expr(source: &'a str) -> Result<Expr, ParseError> {
         let mut parser = Self::builder(source).build()?;
         let expr = parser.safe_call(|p| crate::ast::expr::Expr::parse(p))?;
@@ -237,6 +814,15 @@ impl<'a> Parser<'a> {
         }
     }
 
+    /// Parse the expression at the start of the source, leaving whatever follows it.
+    /// Also returns the byte offset parsing stopped at, where any leftover input starts
+    pub fn parse_expr_partial(source: &'a str) -> Result<(Expr, usize), ParseError> {
+        let mut parser = Self::builder(source).build()?;
+        let expr = parser.safe_call(|p| crate::ast::expr::Expr::parse(p))?;
+
+        Ok((expr, parser.position()))
+    }
+
     pub fn parse_stmt(source: &'a str) -> Result<Stmt, ParseError> {
         let mut parser = Self::builder(source).build()?;
         parser.safe_call(|p| Stmt::parse(p))
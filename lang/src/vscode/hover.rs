@@ -0,0 +1,148 @@
+use logos::Logos;
+use serde::{Deserialize, Serialize};
+use std::ops::Range;
+
+use crate::{ast::stmt::Stmt, lexer::Token, parser::Parser};
+
+/// Description of the name under the cursor
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Hover {
+    /// e.g. `variable 'x'` or `function call 'add'`
+    pub description: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+/// Describe the identifier covering the byte `offset`, if the source parses and there is one
+///
+/// Expressions carry no spans, so the identifier is located in the token stream and
+/// resolved against the declarations of the parsed program.
+pub fn hover_at(source: &str, offset: usize) -> Option<Hover> {
+    let result = Parser::parse_src(source).ok()?;
+    let tokens: Vec<(Token, Range<usize>)> = Token::lexer(source)
+        .spanned()
+        .filter_map(|(token, span)| Some((token.ok()?, span)))
+        .filter(|(token, _)| !token.is_trivia())
+        .collect();
+
+    let index = tokens
+        .iter()
+        .position(|(_, span)| span.start <= offset && offset < span.end)?;
+    let (Token::Identifier(name), span) = &tokens[index] else {
+        return None;
+    };
+
+    let previous = index.checked_sub(1).map(|i| &tokens[i].0);
+    let next = tokens.get(index + 1).map(|(token, _)| token);
+
+    let kind = match (previous, next) {
+        (Some(Token::Fn), _) => "function",
+        (Some(Token::Let), _) => "variable",
+        (Some(Token::Const), _) => "constant",
+        (Some(Token::Dot), _) => "member",
+        (_, Some(Token::LeftParen)) => "function call",
+        _ => {
+            let mut bindings = Vec::new();
+            collect_bindings(result.statements(), &mut bindings);
+
+            // The closest declaration before the use, falling back to later ones for
+            // functions called ahead of their definition
+            bindings
+                .iter()
+                .rev()
+                .filter(|binding| binding.name == *name)
+                .find(|binding| binding.start <= offset)
+                .or_else(|| bindings.iter().find(|binding| binding.name == *name))
+                .map_or("variable", |binding| binding.kind)
+        }
+    };
+
+    Some(Hover {
+        description: format!("{} '{}'", kind, name),
+        start: span.start,
+        end: span.end,
+    })
+}
+
+struct Binding<'a> {
+    name: &'a str,
+    kind: &'static str,
+    start: usize,
+}
+
+fn collect_bindings<'a>(statements: &'a [Stmt], bindings: &mut Vec<Binding<'a>>) {
+    for statement in statements {
+        collect_binding(statement, bindings);
+    }
+}
+
+fn collect_binding<'a>(statement: &'a Stmt, bindings: &mut Vec<Binding<'a>>) {
+    match statement {
+        Stmt::Let { name, span, .. } => bindings.push(Binding {
+            name,
+            kind: "variable",
+            start: span.start,
+        }),
+        Stmt::LetMulti {
+            bindings: declared, ..
+        } => {
+            for binding in declared {
+                bindings.push(Binding {
+                    name: &binding.name,
+                    kind: "variable",
+                    start: binding.span.start,
+                });
+            }
+        }
+        Stmt::Const { name, span, .. } => bindings.push(Binding {
+            name,
+            kind: "constant",
+            start: span.start,
+        }),
+        Stmt::Function {
+            name,
+            params,
+            body,
+            span,
+            ..
+        } => {
+            bindings.push(Binding {
+                name,
+                kind: "function",
+                start: span.start,
+            });
+
+            for param in params {
+                bindings.push(Binding {
+                    name: &param.name,
+                    kind: "parameter",
+                    start: param.span.start,
+                });
+            }
+
+            collect_binding(body, bindings);
+        }
+        Stmt::Block { statements, .. } => collect_bindings(statements, bindings),
+        Stmt::If {
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            collect_binding(then_branch, bindings);
+
+            if let Some(stmt) = else_branch {
+                collect_binding(stmt, bindings);
+            }
+        }
+        Stmt::While { body, .. } => collect_binding(body, bindings),
+        Stmt::For { init, body, .. } => {
+            if let Some(stmt) = init {
+                collect_binding(stmt, bindings);
+            }
+
+            collect_binding(body, bindings);
+        }
+        Stmt::Export { statement } => collect_binding(statement, bindings),
+        _ => (),
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast::format::format_program_with_comments,
+    lexer::{SemanticToken, tokenize},
+    parser::{Diagnostic, Parser},
+};
+
+mod hover;
+mod symbols;
+
+pub use hover::{Hover, hover_at};
+pub use symbols::{DocumentSymbol, document_symbols};
+
+/// Response of the document formatting endpoint
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FormatResult {
+    /// Canonical source, or the original source when it does not parse or has a
+    /// comment the formatter cannot place
+    pub formatted: String,
+    pub errors: Vec<Diagnostic>,
+}
+
+/// Format a whole document, leaving it untouched when it does not parse or when
+/// formatting would lose one of its comments
+pub fn format_code(source: &str) -> FormatResult {
+    let result = match Parser::builder(source).record_trivia(true).build() {
+        Ok(mut parser) => parser.parse(),
+        Err(error) => Err(error),
+    };
+
+    match result {
+        Ok(result) => FormatResult {
+            formatted: format_program_with_comments(result.statements(), source, result.trivia())
+                .unwrap_or_else(|| source.to_string()),
+            errors: Vec::new(),
+        },
+        Err(error) => FormatResult {
+            formatted: source.to_string(),
+            errors: vec![Diagnostic::from(error)],
+        },
+    }
+}
+
+/// Semantic tokens of a document for highlighting, skipping anything the lexer rejects
+pub fn tokenize_code(source: &str) -> Vec<SemanticToken> {
+    tokenize(source)
+}
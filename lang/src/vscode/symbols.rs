@@ -0,0 +1,114 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast::{expr::Expr, stmt::Stmt},
+    parser::Parser,
+};
+
+/// Declaration shown in the editor's outline
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct DocumentSymbol {
+    pub name: String,
+    /// `function`, `variable` or `constant`
+    pub kind: String,
+    pub exported: bool,
+    /// Whole declaration, or just the name for one binding of a multi-name `let`
+    pub start: usize,
+    pub end: usize,
+    /// Declarations nested in a function body
+    pub children: Vec<DocumentSymbol>,
+}
+
+/// Declarations of the program in source order, empty when it does not parse
+pub fn document_symbols(source: &str) -> Vec<DocumentSymbol> {
+    let mut symbols = Vec::new();
+
+    if let Ok(result) = Parser::parse_src(source) {
+        collect_symbols(result.statements(), false, &mut symbols);
+    }
+
+    symbols
+}
+
+fn collect_symbols(statements: &[Stmt], exported: bool, symbols: &mut Vec<DocumentSymbol>) {
+    for statement in statements {
+        collect_symbol(statement, exported, symbols);
+    }
+}
+
+fn collect_symbol(statement: &Stmt, exported: bool, symbols: &mut Vec<DocumentSymbol>) {
+    let (kind, name, span, children) = match statement {
+        Stmt::Let {
+            name, value, span, ..
+        } => ("variable", name, span, expr_symbols(value)),
+        Stmt::LetMulti { bindings, .. } => {
+            for binding in bindings {
+                symbols.push(DocumentSymbol {
+                    name: binding.name.clone(),
+                    kind: "variable".to_string(),
+                    exported,
+                    start: binding.span.start,
+                    end: binding.span.end,
+                    children: expr_symbols(&binding.value),
+                });
+            }
+
+            return;
+        }
+        Stmt::Const {
+            name, value, span, ..
+        } => ("constant", name, span, expr_symbols(value)),
+        Stmt::Function {
+            name, body, span, ..
+        } => {
+            let mut children = Vec::new();
+            collect_symbol(body, false, &mut children);
+
+            ("function", name, span, children)
+        }
+        Stmt::Block { statements, .. } => return collect_symbols(statements, false, symbols),
+        Stmt::If {
+            then_branch,
+            else_branch,
+            ..
+        } => {
+            collect_symbol(then_branch, false, symbols);
+
+            if let Some(stmt) = else_branch {
+                collect_symbol(stmt, false, symbols);
+            }
+
+            return;
+        }
+        Stmt::While { body, .. } => return collect_symbol(body, false, symbols),
+        Stmt::For { init, body, .. } => {
+            if let Some(stmt) = init {
+                collect_symbol(stmt, false, symbols);
+            }
+
+            return collect_symbol(body, false, symbols);
+        }
+        Stmt::Export { statement } => return collect_symbol(statement, true, symbols),
+        _ => return,
+    };
+
+    symbols.push(DocumentSymbol {
+        name: name.clone(),
+        kind: kind.to_string(),
+        exported,
+        start: span.start,
+        end: span.end,
+        children,
+    });
+}
+
+/// Declarations inside block expressions of an initializer
+fn expr_symbols(expr: &Expr) -> Vec<DocumentSymbol> {
+    let mut symbols = Vec::new();
+
+    if let Expr::Block { statements, .. } = expr {
+        collect_symbols(statements, false, &mut symbols);
+    }
+
+    symbols
+}
@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 #[cfg(all(target_arch = "wasm32", feature = "wasm"))]
 use crate::parser::{Diagnostic, ParseError, ParseResult, Parser};
 
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+use crate::vscode;
+
 #[cfg(feature = "wee_alloc")]
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
@@ -24,7 +27,7 @@ impl From<Result<ParseResult, ParseError>> for WasmResult {
         match value {
             Ok(result) => WasmResult {
                 success: true,
-                diagnostics: result.diagnositcs().to_vec(),
+                diagnostics: result.diagnostics().to_vec(),
             },
             Err(error) => WasmResult {
                 success: false,
@@ -54,4 +57,31 @@ pub fn parse_code(source: &str) -> JsValue {
     let wasm_result = WasmResult::from(parse_result);
 
     serde_wasm_bindgen::to_value(&wasm_result).unwrap()
-}
\ No newline at end of file
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+#[wasm_bindgen]
+pub fn tokenize_code(source: &str) -> JsValue {
+    serde_wasm_bindgen::to_value(&vscode::tokenize_code(source)).unwrap()
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+#[wasm_bindgen]
+pub fn format_code(source: &str) -> JsValue {
+    serde_wasm_bindgen::to_value(&vscode::format_code(source)).unwrap()
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+#[wasm_bindgen]
+pub fn hover_at(source: &str, offset: usize) -> JsValue {
+    match vscode::hover_at(source, offset) {
+        Some(hover) => serde_wasm_bindgen::to_value(&hover).unwrap(),
+        None => JsValue::NULL,
+    }
+}
+
+#[cfg(all(target_arch = "wasm32", feature = "wasm"))]
+#[wasm_bindgen]
+pub fn document_symbols(source: &str) -> JsValue {
+    serde_wasm_bindgen::to_value(&vscode::document_symbols(source)).unwrap()
+}
@@ -0,0 +1,85 @@
+// Replacing the global allocator conflicts with the one the `wee_alloc` feature installs
+#![cfg(not(feature = "wee_alloc"))]
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+};
+
+use qbit_lang::{lexer::Token, parser::Parser};
+
+/// Counts allocations made on the current thread, so parallel tests do not interfere
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+fn allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
+
+fn large_source() -> String {
+    (0..2000)
+        .map(|i| {
+            format!("// helper {i}\nfn helper_{i}(a, b) {{ let sum = a + b * {i}; return sum; }}\n")
+        })
+        .collect()
+}
+
+#[test]
+fn token_allocations() {
+    let source = large_source();
+    let (parser, count) = allocations(|| Parser::builder(&source).build().unwrap());
+
+    // Only tokens carrying text allocate, plus the growth of the token and comment buffers
+    let names = parser
+        .tokens()
+        .iter()
+        .filter(|token_span| {
+            matches!(
+                token_span.token,
+                Token::Identifier(_) | Token::StringLiteral(_)
+            )
+        })
+        .count();
+    let comments = parser
+        .comments()
+        .iter()
+        .filter(|token_span| matches!(token_span.token, Token::LineComment(_)))
+        .count();
+    let owned = names + comments;
+
+    assert_eq!(owned, 2000 * 8);
+    assert!(
+        count <= owned + 32,
+        "{count} allocations for {owned} owned tokens"
+    );
+
+    // Looking tokens up while parsing does not copy them, only the names, comments and
+    // nodes kept in the tree allocate
+    let mut parser = parser;
+    let (result, count) = allocations(|| parser.parse().unwrap());
+
+    assert_eq!(result.statements().len(), 2000);
+    assert!(
+        count <= 22 * 2000 + 8,
+        "{count} allocations for 2000 functions"
+    );
+}
@@ -0,0 +1,69 @@
+use qbit_lang::{ast::dot::ast_to_dot, parser::Parser};
+
+fn dot(source: &str) -> String {
+    let program = Parser::parse_src(source)
+        .unwrap_or_else(|e| panic!("Failed to parse program '{}': {}", source, e));
+
+    ast_to_dot(program.statements())
+}
+
+#[test]
+fn dot_precedence() {
+    let dot = dot("2 + 3 * 4;");
+
+    assert!(dot.starts_with("digraph ast {\n"));
+    assert!(dot.ends_with("}\n"));
+
+    for label in [
+        "Program",
+        "Expression",
+        "Binary +",
+        "Literal 2",
+        "Binary *",
+        "Literal 3",
+        "Literal 4",
+    ] {
+        assert!(
+            dot.contains(&format!("[label=\"{label}\"];")),
+            "Missing node '{label}' in:\n{dot}"
+        );
+    }
+
+    // `*` hangs off the right of `+`, so it binds tighter
+    assert!(dot.contains("n0 -> n1;"));
+    assert!(dot.contains("n1 -> n2;"));
+    assert!(dot.contains("n2 -> n3 [label=\"left\"];"));
+    assert!(dot.contains("n2 -> n4 [label=\"right\"];"));
+    assert!(dot.contains("n4 -> n5 [label=\"left\"];"));
+    assert!(dot.contains("n4 -> n6 [label=\"right\"];"));
+    assert_eq!(dot.matches(" -> ").count(), 6);
+}
+
+#[test]
+fn dot_statements() {
+    let dot = dot(r#"
+            fn greet(name, ...rest) {
+                if name == "" { return "\"anon\""; } else { return name; }
+            }
+            outer: while true { break outer; }
+        "#);
+
+    for label in [
+        "Function greet(name, ...rest)",
+        "If",
+        "Binary ==",
+        "Literal \\\"\\\"",
+        "Literal \\\"\\\\\\\"anon\\\\\\\"\\\"",
+        "While outer",
+        "Break outer",
+    ] {
+        assert!(
+            dot.contains(&format!("[label=\"{label}\"];")),
+            "Missing node '{label}' in:\n{dot}"
+        );
+    }
+
+    for edge in ["body", "condition", "then", "else"] {
+        assert!(dot.contains(&format!("[label=\"{edge}\"];")));
+    }
+}
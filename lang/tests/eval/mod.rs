@@ -0,0 +1,733 @@
+use std::{cell::RefCell, rc::Rc};
+
+use qbit_lang::{
+    ast::value::Value,
+    eval::{Environment, eval_expr},
+    parser::Parser,
+};
+
+fn eval(source: &str) -> Result<Value, String> {
+    let expr = Parser::parse_expr(source)
+        .unwrap_or_else(|e| panic!("Failed to parse expression '{}': {}", source, e));
+
+    eval_expr(&expr)
+}
+
+fn assert_eval(source: &str, expected: Value) {
+    match eval(source) {
+        Ok(actual) => assert_eq!(actual, expected, "Wrong result for '{}'", source),
+        Err(e) => panic!("Failed to evaluate '{}': {}", source, e),
+    }
+}
+
+fn assert_eval_err(source: &str, expected: &str) {
+    match eval(source) {
+        Ok(value) => panic!("Expected '{}' to fail, got {:?}", source, value),
+        Err(e) => assert!(
+            e.contains(expected),
+            "Expected error to contain '{}', got: {}",
+            expected,
+            e
+        ),
+    }
+}
+
+fn run(source: &str) -> Result<Environment, String> {
+    let program = Parser::parse_src(source)
+        .unwrap_or_else(|e| panic!("Failed to parse program '{}': {}", source, e));
+    let mut env = Environment::new();
+
+    env.exec_all(program.statements())?;
+    Ok(env)
+}
+
+fn assert_run(source: &str) -> Environment {
+    run(source).unwrap_or_else(|e| panic!("Failed to run '{}': {}", source, e))
+}
+
+fn assert_run_err(source: &str, expected: &str) {
+    match run(source) {
+        Ok(_) => panic!("Expected '{}' to fail", source),
+        Err(e) => assert!(
+            e.contains(expected),
+            "Expected error to contain '{}', got: {}",
+            expected,
+            e
+        ),
+    }
+}
+
+#[test]
+fn arithmetic_eval() {
+    assert_eval("40 + 2", Value::Int(42));
+    assert_eval("(1 + 2) * 3", Value::Int(9));
+    assert_eval("10 - 5 - 2", Value::Int(3));
+    assert_eval("7 / 2", Value::Float(3.5));
+    assert_eval("8 / 2", Value::Int(4));
+    assert_eval("7 % 3", Value::Int(1));
+    assert_eval("7 \\ 2", Value::Int(3));
+    assert_eval("-7 \\ 2", Value::Int(-4));
+    assert_eval("7 \\ -2", Value::Int(-4));
+    assert_eval("-7 \\ -2", Value::Int(3));
+    assert_eval("-8 \\ 2", Value::Int(-4));
+    assert_eval("7.5 \\ 2", Value::Float(3.0));
+    assert_eval("-7 \\ 2.0", Value::Float(-4.0));
+    assert_eval("2 ** 3 ** 2", Value::Int(512));
+    assert_eval("-2 ** 2", Value::Int(-4));
+    assert_eval("2 ** -1", Value::Float(0.5));
+    assert_eval("1.5 + 1", Value::Float(2.5));
+    assert_eval("6 & 3 | 8", Value::Int(10));
+    assert_eval("6 ^ 3", Value::Int(5));
+    assert_eval("6 ^ 3 & 5 | 8", Value::Int(15));
+    assert_eval("1 << 4 >> 2", Value::Int(4));
+    assert_eval("~5", Value::Int(-6));
+    assert_eval("~~5", Value::Int(5));
+    assert_eval("+5", Value::Int(5));
+    assert_eval("+\"3\"", Value::Int(3));
+    assert_eval("+\" 2.5 \"", Value::Float(2.5));
+    assert_eval("1 + +\"2\"", Value::Int(3));
+    assert_eval("\"foo\" + \"bar\"", Value::Str("foobar".to_string()));
+    assert_eval("`foo${\"b\" + \"a\"}r`", Value::Str("foobar".to_string()));
+
+    assert_eval_err("1 / 0", "Division by zero");
+    assert_eval_err("1 % 0", "Division by zero");
+    assert_eval_err("1 \\ 0", "Division by zero");
+    assert_eval_err("-true", "Cannot negate bool");
+    assert_eval_err("\"a\" - 1", "Cannot subtract int from string");
+    assert_eval_err("2 ** 64", "Integer overflow");
+    assert_eval_err("1 << 64", "out of range");
+    assert_eval_err("~1.5", "Cannot apply '~' to float");
+    assert_eval_err("1.5 ^ 1", "Cannot apply '^' to float and int");
+    assert_eval_err("5 ^ \"a\"", "Cannot apply '^' to int and string");
+    assert_run_err("let s = \"a\"; s ^= 1;", "Cannot apply '^' to string and int");
+    assert_eval_err("+\"abc\"", "Cannot convert string 'abc' to a number");
+    assert_eval_err("+true", "Cannot apply '+' to bool");
+}
+
+#[test]
+fn comparison_eval() {
+    assert_eval("1 < 2", Value::Bool(true));
+    assert_eval("2 <= 1", Value::Bool(false));
+    assert_eval("1.5 > 1", Value::Bool(true));
+    assert_eval("'a' < 'b'", Value::Bool(true));
+    assert_eval("\"abc\" == \"abc\"", Value::Bool(true));
+    assert_eval("1 != 2", Value::Bool(true));
+    assert_eval("!0", Value::Bool(true));
+}
+
+#[test]
+fn compare_types_eval() {
+    use std::cmp::Ordering;
+
+    let values = [
+        Value::Int(1),
+        Value::Float(1.5),
+        Value::Bool(true),
+        Value::Str("a".to_string()),
+        Value::Char('a'),
+        Value::Null,
+    ];
+
+    for left in &values {
+        for right in &values {
+            let numbers = |value: &Value| matches!(value, Value::Int(_) | Value::Float(_));
+            let orderable = (numbers(left) && numbers(right))
+                || std::mem::discriminant(left) == std::mem::discriminant(right)
+                    && *left != Value::Null;
+
+            match left.compare(right) {
+                Ok(_) if orderable => (),
+                Err(e) if !orderable => assert_eq!(
+                    e,
+                    format!(
+                        "Cannot compare {} and {}",
+                        left.type_name(),
+                        right.type_name()
+                    )
+                ),
+                result => panic!(
+                    "Unexpected result comparing {:?} and {:?}: {:?}",
+                    left, right, result
+                ),
+            }
+        }
+    }
+
+    assert_eq!(
+        Value::Int(1).compare(&Value::Float(1.5)),
+        Ok(Ordering::Less)
+    );
+    assert_eq!(
+        Value::Float(2.0).compare(&Value::Int(2)),
+        Ok(Ordering::Equal)
+    );
+    assert_eq!(
+        Value::Bool(true).compare(&Value::Bool(false)),
+        Ok(Ordering::Greater)
+    );
+    assert_eq!(
+        Value::Float(f64::NAN).compare(&Value::Int(1)),
+        Err("Cannot compare NaN".to_string())
+    );
+
+    // The ordering operators fail instead of quietly yielding false
+    assert_eval_err("null < 1", "Cannot compare null and int");
+    assert_eval_err("true >= 1", "Cannot compare bool and int");
+    assert_eval_err("\"1\" > 0", "Cannot compare string and int");
+    assert_eval_err("'a' <= \"a\"", "Cannot compare char and string");
+
+    // Equality never fails, mixed types are simply unequal
+    assert_eval("null == 1", Value::Bool(false));
+    assert_eval("true != 1", Value::Bool(true));
+}
+
+#[test]
+fn float_special_values_eval() {
+    let env = assert_run(
+        "let inf = 10.0 ** 400; let nan = inf - inf; let same = nan == nan; let differ = nan != nan; let zeros = 0.0 == -0.0;",
+    );
+    let nan = env.get("nan").unwrap();
+
+    // IEEE 754 equality
+    assert!(nan.is_nan());
+    assert_eq!(env.get("same"), Some(&Value::Bool(false)));
+    assert_eq!(env.get("differ"), Some(&Value::Bool(true)));
+    assert_eq!(env.get("zeros"), Some(&Value::Bool(true)));
+
+    // Identity treats every NaN alike and tells the zeros apart
+    assert!(nan.is_identical(&Value::Float(-f64::NAN)));
+    assert!(!Value::Float(0.0).is_identical(&Value::Float(-0.0)));
+    assert!(Value::Float(1.5).is_identical(&Value::Float(1.5)));
+    assert!(!Value::Float(1.0).is_identical(&Value::Int(1)));
+
+    // Readable display
+    assert_eq!(nan.to_string(), "NaN");
+    assert_eq!(format!("{}", Value::Float(-f64::NAN)), "NaN");
+    assert_eq!(format!("{}", env.get("inf").unwrap()), "inf");
+    assert_eq!(Value::Float(f64::NEG_INFINITY).to_string(), "-inf");
+    assert_eq!(format!("{}", Value::Float(-0.0)), "-0");
+
+    // NaN is falsy, the infinities are truthy
+    assert!(!nan.is_truthy());
+    assert!(env.get("inf").unwrap().is_truthy());
+}
+
+#[test]
+fn integer_overflow_eval() {
+    // Checked by default
+    assert_eval_err("9223372036854775807 + 1", "Integer overflow");
+    assert_eval_err("-9223372036854775807 - 2", "Integer overflow");
+    assert_eval_err("4611686018427387904 * 2", "Integer overflow");
+    assert_run_err("let i = 9223372036854775807; i++;", "Integer overflow");
+    assert_run_err("let i = 9223372036854775807; i += 1;", "Integer overflow");
+    assert_eval("9223372036854775806 + 1", Value::Int(i64::MAX));
+
+    // `i64::MIN / -1` overflows, its remainder does not
+    let env = assert_run("let min = -9223372036854775807 - 1; let r = min % -1;");
+    assert_eq!(env.get("r"), Some(&Value::Int(0)));
+    assert_run_err(
+        "let min = -9223372036854775807 - 1; let q = min / -1;",
+        "Integer overflow",
+    );
+
+    // Wrapping when enabled
+    let program = Parser::parse_src(
+        "let a = 9223372036854775807 + 1; let b = a - 1; let c = a * 2; let d = b; d++;",
+    )
+    .unwrap();
+    let mut env = Environment::new().wrapping_arithmetic(true);
+    env.exec_all(program.statements()).unwrap();
+    assert_eq!(env.get("a"), Some(&Value::Int(i64::MIN)));
+    assert_eq!(env.get("b"), Some(&Value::Int(i64::MAX)));
+    assert_eq!(env.get("c"), Some(&Value::Int(0)));
+    assert_eq!(env.get("d"), Some(&Value::Int(i64::MIN)));
+
+    assert_eq!(
+        Value::Int(i64::MAX).wrapping_add(Value::Int(1)),
+        Ok(Value::Int(i64::MIN))
+    );
+    assert_eq!(
+        Value::Float(1.5).wrapping_mul(Value::Int(2)),
+        Ok(Value::Float(3.0))
+    );
+}
+
+#[test]
+fn to_int_conversion() {
+    assert_eq!(Value::Float(3.0).to_int(), Some(3));
+    assert_eq!(Value::Float(-0.0).to_int(), Some(0));
+    assert_eq!(Value::Float(3.7).to_int(), None);
+    assert_eq!(Value::Float(-3.7).to_int(), None);
+    assert_eq!(Value::Float(1e30).to_int(), None);
+    assert_eq!(Value::Float(f64::NAN).to_int(), None);
+    assert_eq!(Value::Float(f64::INFINITY).to_int(), None);
+
+    // The edges of the i64 range
+    assert_eq!(
+        Value::Float(-9.223372036854775808e18).to_int(),
+        Some(i64::MIN)
+    );
+    assert_eq!(Value::Float(9.223372036854775808e18).to_int(), None);
+
+    assert_eq!(Value::Str("42".to_string()).to_int(), Some(42));
+    assert_eq!(Value::Str("4.2".to_string()).to_int(), None);
+
+    // Chars convert to their code point either way
+    assert_eq!(Value::Char('a').to_int(), Some(97));
+    assert_eq!(Value::Char('a').to_float(), Some(97.0));
+    assert_eq!(Value::Char('é').to_float(), Some(233.0));
+}
+
+#[test]
+fn string_access_eval() {
+    assert_eval("\"abc\"[0]", Value::Char('a'));
+    assert_eval("\"abc\"[2]", Value::Char('c'));
+    assert_eval("\"héllo\"[1]", Value::Char('é'));
+    assert_eval("\"abc\".length", Value::Int(3));
+    assert_eval("\"\".length", Value::Int(0));
+    assert_eval("\"héllo\".length", Value::Int(5));
+
+    let env = assert_run("let s = \"hello\"; let i = 1; let c = s[i + 1]; let n = s.length;");
+    assert_eq!(env.get("c"), Some(&Value::Char('l')));
+    assert_eq!(env.get("n"), Some(&Value::Int(5)));
+
+    // Chars concatenate with strings and each other
+    assert_eval("\"ab\"[0] + \"c\"", Value::Str("ac".to_string()));
+    assert_eval("\"c\" + \"ab\"[1]", Value::Str("cb".to_string()));
+    assert_eval("\"ab\"[1] + \"ab\"[0]", Value::Str("ba".to_string()));
+    assert_eval_err("\"ab\"[0] + 1", "Cannot add char and int");
+
+    assert_eval_err("\"abc\"[3]", "Index 3 is out of bounds for length 3");
+    assert_eval_err("\"abc\"[-1]", "Index -1 is out of bounds for length 3");
+    assert_eval_err("\"abc\"[1.0]", "Cannot index string with float");
+    assert_eval_err("5[0]", "Cannot index int");
+    assert_eval_err("\"abc\".size", "string has no member 'size'");
+    assert_eval_err("true.length", "bool has no member 'length'");
+}
+
+#[test]
+fn array_eval() {
+    let numbers = Value::Array(vec![Value::Int(1), Value::Int(2), Value::Int(3)]);
+    assert_eval("[1, 2, 3]", numbers.clone());
+    assert_eval("[1,2,3][1]", Value::Int(2));
+    assert_eval("[].length", Value::Int(0));
+    assert_eval("[1, [2, 3]][1][0]", Value::Int(2));
+    assert_eval("[1, ...[2, 3], 4].length", Value::Int(4));
+    assert_eval("[[1, 2], [3]] == [[1, 2], [3]]", Value::Bool(true));
+    assert_eval("!![]", Value::Bool(false));
+    assert_eval("!![0]", Value::Bool(true));
+    assert_eq!(numbers.to_string(), "[1, 2, 3]");
+    assert_eq!(Value::Array(vec![]).to_string(), "[]");
+
+    let env = assert_run("let xs = [1, 2, 3]; let i = 2; let last = xs[i]; let n = xs.length;");
+    assert_eq!(env.get("last"), Some(&Value::Int(3)));
+    assert_eq!(env.get("n"), Some(&Value::Int(3)));
+
+    assert_eval_err("[1, 2, 3][3]", "Index 3 is out of bounds for length 3");
+    assert_eval_err("[1, 2, 3][-1]", "Index -1 is out of bounds for length 3");
+    assert_eval_err("[][0]", "Index 0 is out of bounds for length 0");
+    assert_eval_err("[1][true]", "Cannot index array with bool");
+    assert_eval_err("[...1]", "Cannot spread int");
+    assert_eval_err("[1] + [2]", "Cannot add array and array");
+}
+
+#[test]
+fn object_eval() {
+    assert_eval("{a: 1}.a", Value::Int(1));
+    assert_eval("{a: 1}[\"a\"]", Value::Int(1));
+    assert_eval("{a: 1, a: 2}.a", Value::Int(2));
+    assert_eval("{\"b c\": [1, 2]}[\"b c\"][1]", Value::Int(2));
+    assert_eval("{outer: {inner: true}}.outer.inner", Value::Bool(true));
+    assert_eval("{length: 7}.length", Value::Int(7));
+    assert_eval("{a: 1} == {a: 1}", Value::Bool(true));
+    assert_eval("!!{a: 0}", Value::Bool(true));
+
+    let object = eval("{b: \"x\", a: [1, 2]}").unwrap();
+    assert_eq!(object.type_name(), "object");
+    assert_eq!(object.to_string(), "{a: [1, 2], b: x}");
+
+    let env = assert_run("let obj = {a: 1, b: 2}; let key = \"b\"; let x = obj[key] + obj.a;");
+    assert_eq!(env.get("x"), Some(&Value::Int(3)));
+
+    // Missing keys are errors rather than null
+    assert_eval_err("{a: 1}.b", "Object has no key 'b'");
+    assert_eval_err("{a: 1}[\"b\"]", "Object has no key 'b'");
+    assert_eval_err("{a: 1}?.b", "Object has no key 'b'");
+    assert_eval_err("{a: 1}[0]", "Cannot index object with int");
+    assert_run_err(
+        "let obj = {a: 1}; let n = obj.length;",
+        "Object has no key 'length'",
+    );
+}
+
+#[test]
+fn short_circuit_eval() {
+    // The right side is never evaluated when the left side decides the result
+    assert_eval("false && 1 / 0", Value::Bool(false));
+    assert_eval("true || 1 / 0", Value::Bool(true));
+    assert_eval_err("true && 1 / 0", "Division by zero");
+    assert_eval_err("false || 1 / 0", "Division by zero");
+
+    // The deciding operand is the result
+    assert_eval("0 || \"default\"", Value::Str("default".to_string()));
+    assert_eval("1 && 2", Value::Int(2));
+    assert_eval("\"\" && 2", Value::Str(String::new()));
+}
+
+#[test]
+fn logical_assignment_eval() {
+    let env = assert_run(
+        "let a = 0; a ||= 5; let b = 1; b &&= 7; let c = 2; c ||= 9; let d = 0; d &&= 9;",
+    );
+    assert_eq!(env.get("a"), Some(&Value::Int(5)));
+    assert_eq!(env.get("b"), Some(&Value::Int(7)));
+    assert_eq!(env.get("c"), Some(&Value::Int(2)));
+    assert_eq!(env.get("d"), Some(&Value::Int(0)));
+
+    // The value is only evaluated, and the target only written, when needed
+    let env = assert_run(
+        "let n = 0; let x = 1; x ||= n++; let y = 0; y &&= n++; let z = 0; let r = z ||= n++;",
+    );
+    assert_eq!(env.get("n"), Some(&Value::Int(1)));
+    assert_eq!(env.get("x"), Some(&Value::Int(1)));
+    assert_eq!(env.get("y"), Some(&Value::Int(0)));
+    assert_eq!(env.get("z"), Some(&Value::Int(0)));
+    assert_eq!(env.get("r"), Some(&Value::Int(0)));
+
+    // Constants that would not be written are left alone
+    assert_run("const K = 1; K ||= 2;");
+    assert_run_err("const K = 0; K ||= 2;", "constant");
+}
+
+#[test]
+fn variable_eval() {
+    let env = assert_run("let x = 1; x = x + 2;");
+    assert_eq!(env.get("x"), Some(&Value::Int(3)));
+
+    let env = assert_run("let i = 0; i += 5; i *= 2; let j = i++; let k = --i;");
+    assert_eq!(env.get("i"), Some(&Value::Int(10)));
+    assert_eq!(env.get("j"), Some(&Value::Int(10)));
+    assert_eq!(env.get("k"), Some(&Value::Int(10)));
+
+    // Assignment is an expression yielding the assigned value
+    let env = assert_run("let a; let b; a = b = 4;");
+    assert_eq!(env.get("a"), Some(&Value::Int(4)));
+    assert_eq!(env.get("b"), Some(&Value::Int(4)));
+
+    let env = assert_run("let flags = 6; flags ^= 3; let n = 2; n **= 3;");
+    assert_eq!(env.get("flags"), Some(&Value::Int(5)));
+    assert_eq!(env.get("n"), Some(&Value::Int(8)));
+
+    // A sequence evaluates left to right and yields the last value
+    assert_eval("(1, 2, 3)", Value::Int(3));
+    let env = assert_run("let a = 0; let b = 0; a = (b = 2, b * 5); b++, a++;");
+    assert_eq!(env.get("a"), Some(&Value::Int(11)));
+    assert_eq!(env.get("b"), Some(&Value::Int(3)));
+
+    let env = assert_run("const LIMIT = 2 ** 4; let half = LIMIT / 2;");
+    assert_eq!(env.get("half"), Some(&Value::Int(8)));
+
+    assert_run_err("let x = y;", "Undefined variable 'y'");
+    assert_run_err("y = 1;", "Undefined variable 'y'");
+    assert_run_err("const MAX = 1; MAX = 2;", "Cannot assign to constant 'MAX'");
+    assert_run_err("const MAX = 1; MAX++;", "Cannot assign to constant 'MAX'");
+    assert_run_err("let x = 1; x.y = 2;", "Cannot assign to member access");
+    assert_eval_err("x", "Undefined variable 'x'");
+}
+
+#[test]
+fn scope_eval() {
+    // Inner declarations are dropped with their block, assignments reach outwards
+    let env = assert_run("let x = 1; let y = 1; { let x = 2; y = x; }");
+    assert_eq!(env.get("x"), Some(&Value::Int(1)));
+    assert_eq!(env.get("y"), Some(&Value::Int(2)));
+
+    let env = assert_run("{ let inner = 1; }");
+    assert_eq!(env.get("inner"), None);
+
+    // Shadowing a constant in an inner scope is allowed
+    let env = assert_run("const N = 1; let m = 0; { let N = 5; N = 6; m = N; }");
+    assert_eq!(env.get("m"), Some(&Value::Int(6)));
+
+    // Block expressions yield their tail in their own scope
+    let env = assert_run("let x = { let a = 1; a + 2 }; let y = {};");
+    assert_eq!(env.get("x"), Some(&Value::Int(3)));
+    assert_eq!(env.get("y"), Some(&Value::Null));
+    assert_eq!(env.get("a"), None);
+}
+
+#[test]
+fn control_flow_eval() {
+    let env = assert_run("let x = 0; if x < 1 { x = 1; } else { x = 2; }");
+    assert_eq!(env.get("x"), Some(&Value::Int(1)));
+
+    let env = assert_run("let x = 5; if x < 1 { x = 1; } else if x < 3 { x = 2; } else { x = 3; }");
+    assert_eq!(env.get("x"), Some(&Value::Int(3)));
+
+    let env = assert_run("let sum = 0; let i = 0; while i < 5 { i++; sum += i; }");
+    assert_eq!(env.get("sum"), Some(&Value::Int(15)));
+
+    // `continue` still runs the update, `break` leaves the loop
+    let env = assert_run(
+        "let sum = 0; for (let i = 0; i < 10; i++) { if i % 2 == 0 { continue; } if i > 7 { break; } sum += i; }",
+    );
+    assert_eq!(env.get("sum"), Some(&Value::Int(16)));
+    assert_eq!(env.get("i"), None);
+
+    // Updates run left to right
+    let env = assert_run("let i = 0; let j = 10; for (; i < j; i++, j--) {}");
+    assert_eq!(env.get("i"), Some(&Value::Int(5)));
+    assert_eq!(env.get("j"), Some(&Value::Int(5)));
+
+    let env = assert_run("let n = 0; while true { n++; if n == 3 { break; } }");
+    assert_eq!(env.get("n"), Some(&Value::Int(3)));
+
+    // Labels let nested loops continue or break an outer one
+    let env = assert_run(
+        r#"
+            let pairs = 0;
+            outer: for (let i = 0; i < 4; i++) {
+                for (let j = 0; j < 4; j++) {
+                    if j > i { continue outer; }
+                    if i == 3 { break outer; }
+                    pairs++;
+                }
+            }
+        "#,
+    );
+    assert_eq!(env.get("pairs"), Some(&Value::Int(6)));
+
+    assert_run_err(
+        "fn f() { while true { break missing; } } f();",
+        "'break' or 'continue' outside of a loop in 'f'",
+    );
+}
+
+#[test]
+fn function_eval() {
+    let env = assert_run(
+        r#"
+            fn fibonacci(n) {
+                if n <= 1 {
+                    return n;
+                } else {
+                    return fibonacci(n - 1) + fibonacci(n - 2);
+                }
+            }
+
+            let result = fibonacci(10);
+        "#,
+    );
+    assert_eq!(env.get("result"), Some(&Value::Int(55)));
+
+    // Return unwinds out of nested loops to the enclosing call
+    let env = assert_run(
+        r#"
+            fn find(limit) {
+                for (let i = 0; ; i++) {
+                    while true {
+                        if i * i > limit { return i; }
+                        break;
+                    }
+                }
+            }
+
+            let found = find(50);
+        "#,
+    );
+    assert_eq!(env.get("found"), Some(&Value::Int(8)));
+
+    // Defaults, implicit null return and isolation from the caller's locals
+    let env = assert_run(
+        r#"
+            const BASE = 100;
+            fn add(a, b = BASE) { return a + b; }
+            fn nothing() {}
+
+            let x = add(1);
+            let y = add(1, 2);
+            let z = nothing();
+        "#,
+    );
+    assert_eq!(env.get("x"), Some(&Value::Int(101)));
+    assert_eq!(env.get("y"), Some(&Value::Int(3)));
+    assert_eq!(env.get("z"), Some(&Value::Null));
+
+    // A rest parameter collects the remaining arguments into an array
+    let env = assert_run(
+        r#"
+            fn all(...r) { return r; }
+            fn tail(first, ...r) { return r; }
+
+            let none = all();
+            let one = all(1);
+            let several = all(1, "a", [2]);
+            let after = tail(1, 2, 3);
+            let empty = tail(1);
+        "#,
+    );
+    let ints = |values: &[i64]| Value::Array(values.iter().map(|&i| Value::Int(i)).collect());
+    assert_eq!(env.get("none"), Some(&ints(&[])));
+    assert_eq!(env.get("one"), Some(&ints(&[1])));
+    assert_eq!(
+        env.get("several"),
+        Some(&Value::Array(vec![
+            Value::Int(1),
+            Value::Str("a".to_string()),
+            ints(&[2])
+        ]))
+    );
+    assert_eq!(env.get("after"), Some(&ints(&[2, 3])));
+    assert_eq!(env.get("empty"), Some(&ints(&[])));
+    assert_run_err("fn f(a, ...r) {} f();", "Missing argument 'a' for 'f'");
+
+    // Spread arguments fill the parameters in order, the rest collects what is left
+    let env = assert_run(
+        r#"
+            fn tail(first, ...r) { return r; }
+            fn add(a, b) { return a + b; }
+
+            let xs = [1, 2, 3];
+            let spread = tail(...xs);
+            let mixed = tail(0, ...xs, 4);
+            let sum = add(...[1, 2]);
+        "#,
+    );
+    assert_eq!(env.get("spread"), Some(&ints(&[2, 3])));
+    assert_eq!(env.get("mixed"), Some(&ints(&[1, 2, 3, 4])));
+    assert_eq!(env.get("sum"), Some(&Value::Int(3)));
+    assert_run_err("fn f(...r) {} f(...1);", "Cannot spread int");
+
+    assert_run_err(
+        "fn f() { return local; } { let local = 1; f(); }",
+        "Undefined variable 'local'",
+    );
+    assert_run_err("fn f(a) {} f();", "Missing argument 'a' for 'f'");
+    assert_run_err(
+        "fn f(a) {} f(1, 2);",
+        "expects at most 1 argument(s), got 2",
+    );
+    assert_run_err("fn f() { break; } f();", "outside of a loop");
+    assert_run_err("missing(1);", "Undefined function 'missing'");
+}
+
+/// Error of running `source` in an environment with the given call depth limit
+fn recursion_error(source: &str, depth: usize) -> String {
+    let program = Parser::parse_src(source).unwrap();
+    let mut env = Environment::new().max_recursion_depth(depth);
+
+    env.exec_all(program.statements()).unwrap_err()
+}
+
+#[test]
+fn recursion_limit_eval() {
+    // Only function calls count, however deeply their bodies nest
+    assert_eq!(
+        recursion_error("fn f() { return f(); } f();", 50),
+        "Maximum recursion depth of 50 exceeded"
+    );
+    assert_eq!(
+        recursion_error(
+            "fn g(n) { if (n >= 0) { { return 1 + [g(n + 1)][0]; } } } g(0);",
+            50
+        ),
+        "Maximum recursion depth of 50 exceeded"
+    );
+
+    let source = "fn f(n) { if n == 0 { return 0; } return 1 + f(n - 1); }";
+    let env = assert_run(&format!("{source} let r = f(40);"));
+    assert_eq!(env.get("r"), Some(&Value::Int(40)));
+}
+
+#[test]
+fn deep_recursion_eval() {
+    // Unoptimized builds take over 10 KB of native stack per script call
+    std::thread::Builder::new()
+        .stack_size(16 * 1024 * 1024)
+        .spawn(|| {
+            let source = "fn f(n) { if n == 0 { return 0; } return 1 + f(n - 1); }";
+
+            let env = assert_run(&format!("{source} let r = f(500);"));
+            assert_eq!(env.get("r"), Some(&Value::Int(500)));
+
+            // The default limit is the parser's
+            assert_run_err(
+                &format!("{source} f(1000);"),
+                "Maximum recursion depth of 1000 exceeded",
+            );
+        })
+        .unwrap()
+        .join()
+        .unwrap();
+}
+
+#[test]
+fn failed_eval_depth() {
+    // A call that fails still leaves the depth it entered
+    let mut env = Environment::new().max_recursion_depth(5);
+    let failing = Parser::parse_src(
+        "fn bad() { return [nope]; } fn outer() { return bad(); } let a = outer(); bad();",
+    )
+    .unwrap();
+
+    for stmt in failing.statements().iter().take(2) {
+        env.exec(stmt).unwrap();
+    }
+
+    for _ in 0..20 {
+        for stmt in &failing.statements()[2..] {
+            assert!(env.exec(stmt).is_err());
+        }
+    }
+
+    let program = Parser::parse_src("fn ok() { return 1; } let b = ok();").unwrap();
+    env.exec_all(program.statements()).unwrap();
+    assert_eq!(env.get("b"), Some(&Value::Int(1)));
+}
+
+#[test]
+fn native_eval() {
+    let output = Rc::new(RefCell::new(Vec::new()));
+    let calls = Rc::new(RefCell::new(Vec::new()));
+    let mut env = Environment::with_output(output.clone());
+
+    let seen = calls.clone();
+    env.register("max", move |args| {
+        seen.borrow_mut().push(args.to_vec());
+
+        match args {
+            [Value::Int(a), Value::Int(b)] => Ok(Value::Int(*a.max(b))),
+            _ => Err("max expects two ints".to_string()),
+        }
+    });
+
+    let program = Parser::parse_src(
+        r#"
+            let big = max(40 + 2, 7);
+            print("answer:", big, 1.5, true);
+            print();
+        "#,
+    )
+    .unwrap();
+    env.exec_all(program.statements()).unwrap();
+
+    // Natives receive evaluated arguments
+    assert_eq!(*calls.borrow(), [vec![Value::Int(42), Value::Int(7)]]);
+    assert_eq!(env.get("big"), Some(&Value::Int(42)));
+    assert_eq!(
+        String::from_utf8(output.borrow().clone()).unwrap(),
+        "answer: 42 1.5 true\n\n"
+    );
+
+    // Native errors and unknown functions surface as evaluation errors
+    assert_eq!(
+        env.eval_expr(&Parser::parse_expr("max(1)").unwrap()),
+        Err("max expects two ints".to_string())
+    );
+    assert_run_err("print(missing(1));", "Undefined function 'missing'");
+}
+
+#[test]
+fn unsupported_eval() {
+    assert_eval_err("(1)(2)", "Cannot call group");
+}
@@ -0,0 +1,241 @@
+use qbit_lang::{
+    ast::{
+        expr::Expr,
+        format::{format_expr, format_stmt},
+        op::{BinaryOp, UnaryOp},
+        value::Value,
+    },
+    parser::Parser,
+};
+
+fn round_trip_expr(source: &str) -> String {
+    let expr = Parser::parse_expr(source)
+        .unwrap_or_else(|e| panic!("Failed to parse expression '{}': {}", source, e));
+    let formatted = format_expr(&expr);
+    let reparsed = Parser::parse_expr(&formatted)
+        .unwrap_or_else(|e| panic!("Failed to reparse '{}': {}", formatted, e));
+
+    assert_eq!(expr, reparsed, "Round trip changed the AST of '{}'", source);
+    formatted
+}
+
+fn round_trip_stmt(source: &str) -> String {
+    let stmt = Parser::parse_stmt(source)
+        .unwrap_or_else(|e| panic!("Failed to parse statement '{}': {}", source, e));
+    let formatted = format_stmt(&stmt);
+    let reparsed = Parser::parse_stmt(&formatted)
+        .unwrap_or_else(|e| panic!("Failed to reparse '{}': {}", formatted, e));
+
+    assert_eq!(stmt, reparsed, "Round trip changed the AST of '{}'", source);
+    formatted
+}
+
+#[test]
+fn format_expr_spacing() {
+    assert_eq!(round_trip_expr("a+b*c"), "a + b * c");
+    assert_eq!(round_trip_expr("(a+b)*c"), "(a + b) * c");
+    assert_eq!(round_trip_expr("2**3**2"), "2 ** 3 ** 2");
+    assert_eq!(round_trip_expr("2^3"), "2 ^ 3");
+    assert_eq!(round_trip_expr("a|b^c&d"), "a | b ^ c & d");
+    assert_eq!(round_trip_expr("(a|b)^c"), "(a | b) ^ c");
+    assert_eq!(round_trip_expr("10-5-2"), "10 - 5 - 2");
+    assert_eq!(round_trip_expr(r"(a+b)\c%2"), r"(a + b) \ c % 2");
+    assert_eq!(round_trip_expr("!flag&&-x"), "!flag && -x");
+    assert_eq!(round_trip_expr("- -x"), "- -x");
+    assert_eq!(round_trip_expr("!!x"), "!!x");
+    assert_eq!(round_trip_expr("a+ +b"), "a + +b");
+    assert_eq!(round_trip_expr("+ +x"), "+ +x");
+    assert_eq!(round_trip_expr("~~x&~y"), "~~x & ~y");
+    assert_eq!(round_trip_expr("-2**2"), "-2 ** 2");
+    assert_eq!(round_trip_expr("2**-2"), "2 ** -2");
+    assert_eq!(round_trip_expr("f(x,y.prop[0])"), "f(x, y.prop[0])");
+    assert_eq!(
+        round_trip_expr("[1,2.0,\"a\\\"b\"]"),
+        "[1, 2.0, \"a\\\"b\"]"
+    );
+    assert_eq!(round_trip_expr("i+=step*2"), "i += step * 2");
+    assert_eq!(round_trip_expr("x^=2"), "x ^= 2");
+    assert_eq!(round_trip_expr("x**=2"), "x **= 2");
+    assert_eq!(round_trip_expr("a=b=c"), "a = b = c");
+    assert_eq!(round_trip_expr("(a,b=1,c)"), "(a, b = 1, c)");
+    assert_eq!(round_trip_expr("i++ + ++j"), "i++ + ++j");
+    assert_eq!(round_trip_expr(r"['a','\n','\'']"), r"['a', '\n', '\'']");
+    assert_eq!(round_trip_expr("a?.b.c(1)?.(2)"), "a?.b.c(1)?.(2)");
+    assert_eq!(round_trip_expr("f(...[1,...xs])"), "f(...[1, ...xs])");
+    assert_eq!(
+        round_trip_expr("{a:1,\"b c\":{let_:[]}}.a"),
+        "{a: 1, \"b c\": {let_: []}}.a"
+    );
+    assert_eq!(round_trip_expr("{\"let\":1}"), "{\"let\": 1}");
+}
+
+#[test]
+fn format_expr_parens() {
+    // Trees built by hand only get the parentheses precedence requires
+    let add = Expr::Binary {
+        op: BinaryOp::Add,
+        left: Box::new(Expr::Variable("a".to_string())),
+        right: Box::new(Expr::Variable("b".to_string())),
+    };
+
+    let expr = Expr::Binary {
+        op: BinaryOp::Mul,
+        left: Box::new(add.clone()),
+        right: Box::new(Expr::Variable("c".to_string())),
+    };
+    assert_eq!(format_expr(&expr), "(a + b) * c");
+
+    let expr = Expr::Binary {
+        op: BinaryOp::Sub,
+        left: Box::new(Expr::Literal(Value::Int(1))),
+        right: Box::new(add.clone()),
+    };
+    assert_eq!(format_expr(&expr), "1 - (a + b)");
+
+    let expr = Expr::Binary {
+        op: BinaryOp::Add,
+        left: Box::new(add),
+        right: Box::new(Expr::Literal(Value::Int(1))),
+    };
+    assert_eq!(format_expr(&expr), "a + b + 1");
+
+    let expr = Expr::Binary {
+        op: BinaryOp::Pow,
+        left: Box::new(Expr::Unary {
+            op: UnaryOp::Neg,
+            operand: Box::new(Expr::Literal(Value::Int(2))),
+        }),
+        right: Box::new(Expr::Literal(Value::Int(2))),
+    };
+    assert_eq!(format_expr(&expr), "(-2) ** 2");
+
+    // Folded negative literals are parenthesized like negations
+    let expr = Expr::Binary {
+        op: BinaryOp::Pow,
+        left: Box::new(Expr::Literal(Value::Int(-2))),
+        right: Box::new(Expr::Literal(Value::Int(-2))),
+    };
+    assert_eq!(format_expr(&expr), "(-2) ** -2");
+}
+
+#[test]
+fn format_stmt_indent() {
+    let formatted = round_trip_stmt(
+        "fn fibonacci(n){if n<=1{return n;}else{return fibonacci(n-1)+fibonacci(n-2);}}",
+    );
+
+    assert_eq!(
+        formatted,
+        "fn fibonacci(n) {\n    if n <= 1 {\n        return n;\n    } else {\n        return fibonacci(n - 1) + fibonacci(n - 2);\n    }\n}"
+    );
+
+    let formatted = round_trip_stmt("for(let i=0;i<10;i++){print(i);}");
+    assert_eq!(
+        formatted,
+        "for (let i = 0; i < 10; i++) {\n    print(i);\n}"
+    );
+
+    assert_eq!(round_trip_stmt("for(;;){}"), "for (;;) {}");
+    assert_eq!(
+        round_trip_stmt("for(;i<j;i++,j--){}"),
+        "for (; i < j; i++, j--) {}"
+    );
+    assert_eq!(
+        round_trip_stmt("outer:while a{for(;;){continue outer;}break;}"),
+        "outer: while a {\n    for (;;) {\n        continue outer;\n    }\n    break;\n}"
+    );
+    assert_eq!(round_trip_stmt("fn f(a,b=10){}"), "fn f(a, b = 10) {}");
+    assert_eq!(round_trip_stmt("fn f(a,...b){}"), "fn f(a, ...b) {}");
+    assert_eq!(round_trip_stmt("let x;"), "let x;");
+    assert_eq!(round_trip_stmt("let x:int=1;"), "let x: int = 1;");
+    assert_eq!(round_trip_stmt("fn f():int{}"), "fn f(): int {}");
+    assert_eq!(
+        round_trip_stmt("fn f(a:int,b:int=2){}"),
+        "fn f(a: int, b: int = 2) {}"
+    );
+    assert_eq!(
+        round_trip_stmt("fn f(){let x={let a=1;a+2};}"),
+        "fn f() {\n    let x = {\n        let a = 1;\n        a + 2\n    };\n}"
+    );
+    assert_eq!(round_trip_stmt("let x={};"), "let x = {};");
+    assert_eq!(round_trip_stmt("import \"math\";"), "import math;");
+    assert_eq!(
+        round_trip_stmt("import{add,sub}from\"math\";"),
+        "import { add, sub } from math;"
+    );
+    assert_eq!(
+        round_trip_stmt("import{add as plus}from\"./lib.qb\";"),
+        "import { add as plus } from \"./lib.qb\";"
+    );
+    assert_eq!(round_trip_stmt("import utils as u;"), "import utils as u;");
+    assert_eq!(
+        round_trip_stmt("import \"./lib.qb\";"),
+        "import \"./lib.qb\";"
+    );
+}
+
+#[test]
+fn format_leading_comments() {
+    assert_eq!(
+        round_trip_stmt("// Entry point\nfn main(){\n// counter\nlet i=0;}"),
+        "// Entry point\nfn main() {\n    // counter\n    let i = 0;\n}"
+    );
+    assert_eq!(
+        round_trip_stmt("/* multi\n line */\nconst X=1;"),
+        "/* multi\n line */\nconst X = 1;"
+    );
+}
+
+#[test]
+fn format_round_trip() {
+    let program = Parser::parse_src(
+        r#"
+            fn main() {
+                let count = 10;
+                const MAX = 3;
+                while attempts < MAX { if tryOperation() { return true; } attempts++; }
+                if a { x = 1; } else if b { x = 2; } else { x = 3; }
+                for (; i < count; i += 1) { continue; }
+                { break; }
+                { ; }
+            }
+
+            export fn utility(a, b) { return (a + b) * -a ** 2; }
+        "#,
+    )
+    .unwrap();
+
+    for stmt in program.statements() {
+        let formatted = format_stmt(stmt);
+        let reparsed = Parser::parse_stmt(&formatted)
+            .unwrap_or_else(|e| panic!("Failed to reparse '{}': {}", formatted, e));
+
+        assert_eq!(stmt, &reparsed);
+        assert_eq!(format_stmt(&reparsed), formatted);
+    }
+}
+
+#[cfg(feature = "pretty-debug")]
+#[test]
+fn source_string() {
+    let source_string = |source: &str| Parser::parse_expr(source).unwrap().to_source_string();
+
+    // Every operator operand is grouped, unlike the canonical output
+    assert_eq!(source_string("1 + 2 * 3"), "1 + (2 * 3)");
+    assert_eq!(source_string("a - b - c"), "(a - b) - c");
+    assert_eq!(source_string("-2 ** 2"), "-(2 ** 2)");
+    assert_eq!(source_string("!a && b || c"), "((!a) && b) || c");
+    assert_eq!(source_string("x = y += 1"), "x = y += 1");
+    assert_eq!(source_string("f(a + b)[i].len"), "f(a + b)[i].len");
+    assert_eq!(
+        source_string("{ let x = 1; x * 2 }"),
+        "{ let x = 1; x * 2 }"
+    );
+
+    let stmt =
+        Parser::parse_stmt("// note\nfn f(a) { if a { return 1; } return 2 + 3 * 4; }").unwrap();
+    assert_eq!(
+        stmt.to_string(),
+        "fn f(a) { if a { return 1; } return 2 + (3 * 4); }"
+    );
+}
@@ -0,0 +1,228 @@
+use logos::Logos;
+use qbit_lang::{
+    lexer::{SemanticToken, TemplatePart, Token, TokenCategory, tokenize},
+    parser::{Diagnostic, Parser},
+};
+
+#[test]
+fn token_kind() {
+    assert_eq!(Token::Let.kind(), "keyword");
+    assert_eq!(Token::BoolTrue.kind(), "keyword");
+    assert_eq!(Token::From.kind(), "keyword");
+    assert_eq!(Token::IntLiteral(1).kind(), "number");
+    assert_eq!(Token::FloatLiteral(1.5).kind(), "number");
+    assert_eq!(Token::StringLiteral("a".to_string()).kind(), "string");
+    assert_eq!(Token::Identifier("a".to_string()).kind(), "variable");
+    assert_eq!(Token::LineComment("a".to_string()).kind(), "comment");
+    assert_eq!(Token::BlockComment("a".to_string()).kind(), "comment");
+    assert_eq!(Token::DocLineComment("a".to_string()).kind(), "comment");
+    assert_eq!(Token::PlusEqual.kind(), "operator");
+    assert_eq!(Token::Semicolon.kind(), "punctuation");
+}
+
+#[test]
+fn token_keyword() {
+    // Every keyword lexes back from the word it reports
+    for source in [
+        "true", "false", "null", "let", "const", "fn", "return", "if", "else", "import", "export",
+        "default", "from", "as", "while", "for", "continue", "break",
+    ] {
+        let token = Token::lexer(source).next().unwrap().unwrap();
+        assert_eq!(token.keyword(), Some(source));
+        assert!(token.is_keyword());
+    }
+
+    assert_eq!(Token::Identifier("for_each".to_string()).keyword(), None);
+    assert!(!Token::Semicolon.is_keyword());
+    assert!(!Token::StringLiteral("let".to_string()).is_keyword());
+}
+
+#[test]
+fn token_category() {
+    let cases = [
+        ("while", TokenCategory::Keyword),
+        ("null", TokenCategory::Keyword),
+        ("**=", TokenCategory::Operator),
+        ("&&", TokenCategory::Operator),
+        ("42", TokenCategory::LiteralNumber),
+        ("4.2", TokenCategory::LiteralNumber),
+        ("\"hi\"", TokenCategory::LiteralString),
+        ("'c'", TokenCategory::LiteralString),
+        ("`t ${x}`", TokenCategory::LiteralString),
+        ("/** docs */", TokenCategory::Comment),
+        ("// note", TokenCategory::Comment),
+        ("?.", TokenCategory::Punctuation),
+        ("{", TokenCategory::Punctuation),
+        ("count", TokenCategory::Identifier),
+    ];
+
+    for (source, category) in cases {
+        let token = Token::lexer(source).next().unwrap().unwrap();
+        assert_eq!(token.category(), category, "{source}");
+        assert_eq!(token.kind(), category.kind());
+    }
+}
+
+#[test]
+fn tokenize_src() {
+    let tokens = tokenize("let x = \"hi\"; // done");
+
+    let expected = [
+        ("keyword", TokenCategory::Keyword, 0, 3),
+        ("variable", TokenCategory::Identifier, 4, 5),
+        ("operator", TokenCategory::Operator, 6, 7),
+        ("string", TokenCategory::LiteralString, 8, 12),
+        ("punctuation", TokenCategory::Punctuation, 12, 13),
+        ("comment", TokenCategory::Comment, 14, 21),
+    ];
+
+    assert_eq!(tokens.len(), expected.len());
+
+    for (token, (kind, category, start, end)) in tokens.iter().zip(expected) {
+        assert_eq!(
+            token,
+            &SemanticToken {
+                kind: kind.to_string(),
+                category,
+                start,
+                end,
+            }
+        );
+    }
+
+    // Invalid characters are skipped rather than aborting
+    let tokens = tokenize("1 @ 2");
+    assert_eq!(tokens.len(), 2);
+    assert_eq!(tokens[1].start, 4);
+
+    // So is an unclosed comment, rather than lexing as a `/`
+    assert_eq!(tokenize("1 /** docs").len(), 1);
+}
+
+#[test]
+fn xor_tokens() {
+    let tokens: Vec<_> = Token::lexer("a ^ b ^= c ** d **= e")
+        .filter_map(Result::ok)
+        .filter(|token| !matches!(token, Token::Identifier(_)))
+        .collect();
+
+    assert_eq!(
+        tokens,
+        [
+            Token::Caret,
+            Token::CaretEqual,
+            Token::DoubleStar,
+            Token::DoubleStarEqual
+        ]
+    );
+    assert_eq!(Token::CaretEqual.kind(), "operator");
+}
+
+#[test]
+fn doc_comments() {
+    let lex = |source: &str| {
+        Token::lexer(source)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap_or_else(|e| panic!("Failed to lex '{}': {:?}", source, e))
+    };
+
+    assert_eq!(lex("// plain"), [Token::LineComment(" plain".to_string())]);
+    assert_eq!(lex("/// doc"), [Token::DocLineComment(" doc".to_string())]);
+    assert_eq!(
+        lex("/* plain */"),
+        [Token::BlockComment(" plain ".to_string())]
+    );
+    assert_eq!(
+        lex("/** doc */"),
+        [Token::DocBlockComment(" doc ".to_string())]
+    );
+    assert_eq!(lex("/**/"), [Token::BlockComment(String::new())]);
+
+    // A line comment stops at the newline rather than swallowing the next one
+    assert_eq!(
+        lex("// a\n/// b"),
+        [
+            Token::LineComment(" a".to_string()),
+            Token::DocLineComment(" b".to_string())
+        ]
+    );
+
+    assert!(Token::DocBlockComment(String::new()).is_comment());
+    assert!(Token::DocLineComment(String::new()).is_trivia());
+    assert!(Token::DocLineComment(String::new()).is_doc_comment());
+    assert!(!Token::LineComment(String::new()).is_doc_comment());
+}
+
+#[test]
+fn nested_comments() {
+    let lex = |source: &str| Token::lexer(source).collect::<Result<Vec<_>, _>>();
+
+    // One level, the outer comment keeps going past the inner */
+    assert_eq!(
+        lex("/* outer /* inner */ still comment */ x"),
+        Ok(vec![
+            Token::BlockComment(" outer /* inner */ still comment ".to_string()),
+            Token::Identifier("x".to_string()),
+        ])
+    );
+
+    // Two levels, in a doc comment too
+    assert_eq!(
+        lex("/** a /* b /* c */ b */ a */"),
+        Ok(vec![Token::DocBlockComment(
+            " a /* b /* c */ b */ a ".to_string()
+        )])
+    );
+    assert_eq!(
+        lex("/* a **/"),
+        Ok(vec![Token::BlockComment(" a *".to_string())])
+    );
+
+    // Closing only the inner comment leaves the outer one open
+    assert!(lex("/* outer /* inner */ x").is_err());
+    let error = Parser::builder("x;\n/* outer /* inner */ x")
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(
+        Diagnostic::from(error).to_string(),
+        "2:1: error: Lexer error: Unterminated block comment ('/* outer /* inner */ x')"
+    );
+}
+
+#[test]
+fn template_strings() {
+    let lex = |source: &str| Token::lexer(source).collect::<Result<Vec<_>, _>>();
+
+    assert_eq!(
+        lex("`a\\n\\`b`").unwrap(),
+        [Token::TemplateString(vec![TemplatePart::Text(
+            "a\n`b".to_string()
+        )])]
+    );
+
+    // Interpolations are kept as source ranges, braces inside them are balanced
+    let source = "`x = ${ {a: 1}.a } and ${\"}\"}`";
+    assert_eq!(
+        lex(source).unwrap(),
+        [Token::TemplateString(vec![
+            TemplatePart::Text("x = ".to_string()),
+            TemplatePart::Expr(7..17),
+            TemplatePart::Text(" and ".to_string()),
+            TemplatePart::Expr(25..28),
+        ])]
+    );
+    assert_eq!(&source[7..17], " {a: 1}.a ");
+    assert_eq!(&source[25..28], "\"}\"");
+
+    // Multi-line templates keep their newlines
+    assert_eq!(
+        lex("`one\ntwo`").unwrap(),
+        [Token::TemplateString(vec![TemplatePart::Text(
+            "one\ntwo".to_string()
+        )])]
+    );
+
+    assert!(lex("`open").is_err());
+    assert!(lex("`${open`").is_err());
+}
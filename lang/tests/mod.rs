@@ -1 +1,6 @@
-mod parser;
\ No newline at end of file
+mod dot;
+mod eval;
+mod format;
+mod lexer;
+mod parser;
+mod vscode;
@@ -0,0 +1,401 @@
+use qbit_lang::parser::{DiagnosticLevel, LintLevel, NamingStyle, Parser};
+
+use super::TestHelper;
+
+fn warnings(source: &str) -> Vec<String> {
+    TestHelper::src(source)
+        .unwrap_or_else(|e| panic!("Failed to parse '{}': {}", source, e))
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect()
+}
+
+#[test]
+fn diagnostics_accessor() {
+    let result = TestHelper::src("let ok = 1 < 2 < 3;").unwrap();
+    assert_eq!(result.diagnostics().len(), 1);
+
+    // The misspelled accessor still works for existing callers
+    #[allow(deprecated)]
+    let old = result.diagnositcs();
+    assert_eq!(old.len(), result.diagnostics().len());
+}
+
+#[test]
+fn chained_comparison() {
+    let diagnostics = warnings("let ok = 1 < 2 < 3;");
+    assert_eq!(diagnostics.len(), 1);
+    assert!(
+        diagnostics[0].contains("Comparisons cannot be chained"),
+        "got: {}",
+        diagnostics[0]
+    );
+
+    // Nested inside function bodies and conditions
+    let diagnostics = warnings("fn check(a, b, c) { if a == b == c { return; } }");
+    assert_eq!(diagnostics.len(), 1);
+    assert!(diagnostics[0].starts_with("1:21: warning:"));
+
+    // Explicit parentheses or `&&` make the intent clear
+    assert!(warnings("let ok = (1 < 2) == flag;").is_empty());
+    assert!(warnings("let ok = 1 < 2 && 2 < 3;").is_empty());
+
+    // Mixing relational and equality operators is still a chain
+    assert_eq!(warnings("let ok = a < b == c;").len(), 1);
+}
+
+#[test]
+fn undefined_label() {
+    assert!(warnings("outer: while true { while true { break outer; } }").is_empty());
+
+    let diagnostics = warnings("outer: while true { break inner; }");
+    assert_eq!(diagnostics.len(), 1);
+    assert!(
+        diagnostics[0].contains("No enclosing loop is labeled 'inner'"),
+        "got: {}",
+        diagnostics[0]
+    );
+
+    // Labels are scoped to the loop they name
+    assert_eq!(
+        warnings("outer: while true { break; } while true { continue outer; }").len(),
+        1
+    );
+
+    // and do not reach into function bodies
+    assert_eq!(
+        warnings("outer: while true { fn f() { while true { break outer; } } }").len(),
+        1
+    );
+}
+
+#[test]
+fn empty_block() {
+    let diagnostics = warnings("if ready {}");
+    assert_eq!(diagnostics, ["1:10: info: Empty block"]);
+
+    // Every kind of control-flow body is checked
+    assert_eq!(warnings("while running {}").len(), 1);
+    assert_eq!(warnings("for (let i = 0; i < 3; i++) {}").len(), 1);
+    assert_eq!(warnings("if a { f(); } else {}").len(), 1);
+    assert_eq!(warnings("if a { f(); } else if b {}").len(), 1);
+
+    // A standalone block or function body is deliberate
+    assert!(warnings("{}").is_empty());
+    assert!(warnings("fn noop() {}").is_empty());
+    assert!(warnings("if a { {} }").is_empty());
+}
+
+#[test]
+fn constant_condition() {
+    assert_eq!(
+        warnings("if false { f(); }"),
+        ["1:1: warning: Condition is always false"]
+    );
+    assert_eq!(
+        warnings("while 0 { f(); }"),
+        ["1:1: warning: Condition is always false"]
+    );
+    assert_eq!(
+        warnings("if !(\"yes\") { f(); }"),
+        ["1:1: warning: Condition is always false"]
+    );
+    assert_eq!(
+        warnings("if 1 { f(); }"),
+        ["1:1: warning: Condition is always true"]
+    );
+
+    // An always-true `while` is an infinite loop on purpose
+    assert!(warnings("while true { f(); }").is_empty());
+    assert!(warnings("while (1) { f(); }").is_empty());
+
+    // Conditions that depend on variables are left alone
+    assert!(warnings("if ready { f(); }").is_empty());
+    assert!(warnings("while !done { f(); }").is_empty());
+}
+
+#[test]
+fn self_assignment() {
+    assert_eq!(
+        warnings("x = x;"),
+        ["1:1: warning: Assigning a value to itself has no effect"]
+    );
+    assert_eq!(warnings("a.b = a.b;").len(), 1);
+    assert_eq!(warnings("items[i] = items[i];").len(), 1);
+
+    assert!(warnings("x = x + 1;").is_empty());
+    assert!(warnings("x += x;").is_empty());
+    assert!(warnings("a.b = a.c;").is_empty());
+    assert!(warnings("items[i] = items[j];").is_empty());
+}
+
+#[test]
+fn division_by_zero() {
+    assert_eq!(
+        warnings("let half = x / 0;"),
+        ["1:1: error: Division by zero"]
+    );
+    assert_eq!(warnings("let rest = 10 % (2 - 2);").len(), 1);
+    assert_eq!(warnings("let floor = x \\ 0.0;").len(), 1);
+
+    // A parameter default is reported at the parameter
+    assert_eq!(
+        warnings("fn f(a, b = 1 / 0) {}"),
+        ["1:9: error: Division by zero"]
+    );
+
+    // Only a divisor known before running is checked
+    assert!(warnings("let half = x / y;").is_empty());
+    assert!(warnings("let half = 10 / (2 - 1);").is_empty());
+    assert!(warnings("let zero = 0 / x;").is_empty());
+}
+
+fn shadowing_warnings(source: &str, level: LintLevel) -> Vec<String> {
+    Parser::builder(source)
+        .shadowing(level)
+        .build()
+        .and_then(|mut parser| parser.parse())
+        .unwrap_or_else(|e| panic!("Failed to parse '{}': {}", source, e))
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect()
+}
+
+#[test]
+fn shadowing() {
+    let source = "let x = 1;\nfn f() {\n    let x = 2;\n    return x;\n}";
+
+    // Off unless enabled
+    assert!(warnings(source).is_empty());
+    assert!(shadowing_warnings(source, LintLevel::Allow).is_empty());
+
+    assert_eq!(
+        shadowing_warnings(source, LintLevel::Info),
+        ["3:5: info: Shadows outer binding 'x'"]
+    );
+    assert_eq!(
+        shadowing_warnings(source, LintLevel::Warn),
+        ["3:5: warning: Shadows outer binding 'x'"]
+    );
+
+    // A shadowing parameter is reported at the parameter
+    let source = "let x = 1;\nfn f(a, x) {}";
+    assert_eq!(
+        shadowing_warnings(source, LintLevel::Info),
+        ["2:9: info: Shadows outer binding 'x'"]
+    );
+
+    // Parameters and nested blocks shadow too
+    let source = "let x = 1; fn f(x) { { let y = x; { let y = 2; } } }";
+    assert_eq!(shadowing_warnings(source, LintLevel::Info).len(), 2);
+
+    // Sibling scopes and redeclarations in the same scope do not
+    let source = "{ let y = 1; } { let y = 2; } let z = 1; let z = 2;";
+    assert!(shadowing_warnings(source, LintLevel::Info).is_empty());
+}
+
+fn empty_statement_warnings(source: &str, level: LintLevel) -> Vec<String> {
+    Parser::builder(source)
+        .empty_statement(level)
+        .build()
+        .and_then(|mut parser| parser.parse())
+        .unwrap_or_else(|e| panic!("Failed to parse '{}': {}", source, e))
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect()
+}
+
+#[test]
+fn empty_statement() {
+    let source = "let x = 1;;\n;";
+
+    // Off unless enabled
+    assert!(warnings(source).is_empty());
+    assert!(empty_statement_warnings(source, LintLevel::Allow).is_empty());
+
+    assert_eq!(
+        empty_statement_warnings(source, LintLevel::Warn),
+        [
+            "1:11: warning: Empty statement",
+            "2:1: warning: Empty statement"
+        ]
+    );
+}
+
+fn xor_hints(source: &str) -> Vec<String> {
+    Parser::builder(source)
+        .xor_hint(true)
+        .build()
+        .and_then(|mut parser| parser.parse())
+        .unwrap_or_else(|e| panic!("Failed to parse '{}': {}", source, e))
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect()
+}
+
+#[test]
+fn xor_hint() {
+    // Points at the operator
+    assert_eq!(
+        xor_hints("let x = 5 ^ 3;"),
+        ["1:11: hint: '^' is bitwise XOR, use '**' for exponentiation"]
+    );
+    assert_eq!(
+        xor_hints("x ^= 2;"),
+        ["1:3: hint: '^' is bitwise XOR, use '**' for exponentiation"]
+    );
+    assert!(xor_hints("let x = 5 ** 3;").is_empty());
+
+    // Off unless enabled
+    assert!(warnings("let x = 5 ^ 3;").is_empty());
+
+    let result = Parser::builder("a ^ b ^ c;")
+        .xor_hint(true)
+        .build()
+        .and_then(|mut parser| parser.parse())
+        .unwrap();
+    assert_eq!(result.diagnostics().len(), 2);
+    assert_eq!(result.diagnostics()[0].level(), DiagnosticLevel::Hint);
+    assert_eq!(result.diagnostics()[0].length(), 1);
+    assert_eq!(result.diagnostics()[0].code(), "W0013");
+}
+
+#[test]
+fn parameter_naming() {
+    // Points at the parameter, not the function
+    assert_eq!(
+        warnings("fn f(badName) {}"),
+        ["1:6: warning: expected 'bad_name'"]
+    );
+    assert_eq!(
+        warnings("fn f(\n    a,\n    ...moreArgs\n) {}"),
+        ["3:8: warning: expected 'more_args'"]
+    );
+
+    assert!(warnings("fn f(good_name, count = 1) {}").is_empty());
+}
+
+#[test]
+fn nested_declaration_location() {
+    let source = "fn outer() {\n    let ok = 1;\n    if ok {\n        let BadName = 2;\n    }\n}";
+
+    // Reported on the nested declaration's line, not the function's
+    assert_eq!(warnings(source), ["4:9: warning: expected 'bad_name'"]);
+
+    let source = "let x = {\n    const lower = 1;\n    lower\n};";
+    assert_eq!(warnings(source), ["2:5: warning: expected 'LOWER'"]);
+}
+
+#[test]
+fn nested_diagnostic_location() {
+    // Statements inside a function body are reported where they are
+    let source = "fn f(a) {\n    if a {}\n    while 0 { g(); }\n    a = a;\n    return a / 0;\n}";
+    assert_eq!(
+        warnings(source),
+        [
+            "2:10: info: Empty block",
+            "3:5: warning: Condition is always false",
+            "4:5: warning: Assigning a value to itself has no effect",
+            "5:5: error: Division by zero",
+        ]
+    );
+
+    let source = "fn f(a, b) {\n    {\n        let ok = a < b < 1;\n        a == b == 1;\n    }\n}";
+    let diagnostics = warnings(source);
+    assert_eq!(diagnostics.len(), 2);
+    assert!(
+        diagnostics[0].starts_with("3:9: warning:"),
+        "{diagnostics:?}"
+    );
+    assert!(
+        diagnostics[1].starts_with("4:9: warning:"),
+        "{diagnostics:?}"
+    );
+
+    let source = "fn f() {\n    while true {\n        for (;;) { continue outer; }\n    }\n}";
+    let diagnostics = warnings(source);
+    assert_eq!(diagnostics.len(), 1);
+    assert!(
+        diagnostics[0].starts_with("3:20: warning:"),
+        "{diagnostics:?}"
+    );
+
+    let source = "fn f() {\n    g();\n    ;\n}";
+    assert_eq!(
+        empty_statement_warnings(source, LintLevel::Warn),
+        ["3:5: warning: Empty statement"]
+    );
+}
+
+fn let_naming_warnings(source: &str, style: NamingStyle) -> Vec<String> {
+    Parser::builder(source)
+        .let_naming(style)
+        .build()
+        .and_then(|mut parser| parser.parse())
+        .unwrap_or_else(|e| panic!("Failed to parse '{}': {}", source, e))
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect()
+}
+
+#[test]
+fn naming_style() {
+    assert!(let_naming_warnings("let item_count = 1;", NamingStyle::Snake).is_empty());
+    assert_eq!(
+        let_naming_warnings("let itemCount = 1;", NamingStyle::Snake),
+        ["1:1: warning: expected 'item_count'"]
+    );
+
+    assert!(let_naming_warnings("let itemCount = 1;", NamingStyle::Camel).is_empty());
+    assert_eq!(
+        let_naming_warnings("let item_count = 1;", NamingStyle::Camel),
+        ["1:1: warning: expected 'itemCount'"]
+    );
+    assert_eq!(
+        let_naming_warnings("let a = 1, item_count = 2;", NamingStyle::Camel),
+        ["1:12: warning: expected 'itemCount'"]
+    );
+
+    // Other declaration kinds keep their own style
+    assert_eq!(
+        let_naming_warnings("let itemCount = 1; fn doThing() {}", NamingStyle::Camel),
+        ["1:20: warning: expected 'do_thing'"]
+    );
+
+    assert!(let_naming_warnings("let Item_count = 1;", NamingStyle::None).is_empty());
+}
+
+#[test]
+fn analysis_disabled() {
+    let source = "let badName = 1; const lower = 2; fn f(x) { return 1; x; }";
+    assert!(!warnings(source).is_empty());
+
+    let result = Parser::builder(source)
+        .analyze(false)
+        .build()
+        .and_then(|mut parser| parser.parse())
+        .unwrap_or_else(|e| panic!("Failed to parse '{}': {}", source, e));
+
+    assert!(result.diagnostics().is_empty());
+    assert_eq!(result.statements().len(), 3);
+}
+
+#[test]
+fn long_binary_chain() {
+    // The parser builds left-nested chains without recursing, so the analyzer must not
+    // recurse down them either
+    let terms = vec!["a"; 20_000].join(" + ");
+    let result = TestHelper::src(&format!("let x = {terms};")).unwrap();
+    assert!(result.diagnostics().is_empty());
+
+    let terms = vec!["1"; 20_000].join(" + ");
+    assert_eq!(
+        warnings(&format!("let x = {terms} + 1 / 0;")),
+        ["1:1: error: Division by zero"]
+    );
+}
@@ -0,0 +1,39 @@
+use qbit_lang::{
+    ast::{expr::Expr, stmt::Stmt},
+    parser::{Parse, Parser},
+};
+
+use super::{assert_expr, assert_stmt};
+
+#[test]
+fn checkpoint_restore() {
+    let mut parser = Parser::builder("let y = (a); y;").build().unwrap();
+    let checkpoint = parser.checkpoint();
+
+    // Speculatively try an expression, which fails on the `let`
+    assert!(Expr::parse(&mut parser).is_err());
+    assert_ne!(parser.checkpoint(), checkpoint);
+
+    // Rewinding lets the statement parser start from the same token
+    parser.restore(checkpoint);
+    assert_eq!(parser.checkpoint(), checkpoint);
+
+    let stmt = Stmt::parse(&mut parser).unwrap();
+    assert_expr::group(assert_stmt::let_stmt(&stmt, "y"));
+
+    // Rewinding after a successful parse replays it
+    let checkpoint = parser.checkpoint();
+    let first = Stmt::parse(&mut parser).unwrap();
+    assert_expr::variable(assert_stmt::expression_stmt(&first), "y");
+
+    parser.restore(checkpoint);
+    assert_eq!(Stmt::parse(&mut parser).unwrap(), first);
+
+    // A failure several tokens into a nested parse rewinds just as cleanly
+    let mut parser = Parser::builder("((a, b + ))").build().unwrap();
+    let checkpoint = parser.checkpoint();
+    let error = Expr::parse(&mut parser).unwrap_err().to_string();
+
+    parser.restore(checkpoint);
+    assert_eq!(Expr::parse(&mut parser).unwrap_err().to_string(), error);
+}
@@ -0,0 +1,337 @@
+use qbit_lang::{
+    ast::stmt::Stmt,
+    lexer::StringQuote,
+    parser::{Diagnostic, DiagnosticLevel, ParseContext, ParseError, Parser},
+};
+
+use super::TestHelper;
+
+fn error_diagnostic(source: &str) -> String {
+    let error = TestHelper::src(source).unwrap_err();
+    Diagnostic::from(error).to_string()
+}
+
+#[test]
+fn eof_diagnostic() {
+    // Points just past the last token, on its line
+    assert_eq!(
+        error_diagnostic("let x = 1;\nlet y =\n  1 +"),
+        "3:6: error: Unexpected end of file, expected expression"
+    );
+
+    assert_eq!(
+        error_diagnostic("fn f() {\n    return 1;"),
+        "2:14: error: Unexpected end of file, expected RightBrace, unclosed '{' opened at line 1"
+    );
+
+    // Empty input has no lines, but the location is still one-based
+    let error = TestHelper::expr("").unwrap_err();
+    assert!(
+        Diagnostic::from(error)
+            .to_string()
+            .starts_with("1:1: error:"),
+        "Unexpected location for empty input"
+    );
+}
+
+#[test]
+fn unclosed_diagnostic() {
+    assert_eq!(
+        error_diagnostic("let xs = [1, 2, 3"),
+        "1:18: error: Unexpected end of file, expected ',' or ']', unclosed '[' opened at line 1"
+    );
+
+    // Reported at the end of input, naming where the opener was
+    for (source, delimiter, line) in [
+        ("let x = (1 +\n  2", "(", 1),
+        ("let x = 1;\nf(a,\n  b", "(", 2),
+        ("let xs = [];\n\nlet y = xs[0", "[", 3),
+        ("let o = {a: 1,\nb: 2", "{", 1),
+        ("if x {\n  let y = 1;", "{", 1),
+        ("let x = {\n  f()", "{", 1),
+        ("fn f(a,\n", "(", 1),
+    ] {
+        let message = error_diagnostic(source);
+        let expected = format!("unclosed '{delimiter}' opened at line {line}");
+
+        assert!(
+            message.ends_with(&expected),
+            "Expected '{expected}' for {source:?}, got: {message}"
+        );
+    }
+
+    // The innermost delimiter left open is the one reported
+    let error = TestHelper::src("let x = [1, g(2,\n [3, 4], f(5").unwrap_err();
+    match error {
+        ParseError::Unclosed {
+            delimiter,
+            opened_at,
+            opened_line,
+            ..
+        } => {
+            assert_eq!(delimiter, "(");
+            assert_eq!(opened_at, 27..28);
+            assert_eq!(opened_line, 2);
+        }
+        other => panic!("Expected Unclosed, got {other:?}"),
+    }
+
+    // A closed bracket followed by a missing token elsewhere is not unclosed
+    let error = TestHelper::src("let x = [1, 2]").unwrap_err();
+    assert!(matches!(error, ParseError::UnexpectedEof { .. }));
+}
+
+#[test]
+fn trailing_comments_eof() {
+    // A program ending in comments stops after its last statement
+    let program = TestHelper::src("let x = 1;\n// done\n/* really\n   done */").unwrap();
+    assert_eq!(program.statements().len(), 1);
+
+    let program = TestHelper::src("// nothing\n// but comments").unwrap();
+    assert!(program.statements().is_empty());
+
+    // Running out of tokens mid-statement points past the trailing comments
+    assert_eq!(
+        error_diagnostic("let y =\n// trailing\n/* more */"),
+        "3:11: error: Unexpected end of file, expected expression"
+    );
+    assert_eq!(
+        error_diagnostic("let y = 1 // no semicolon"),
+        "1:26: error: Unexpected end of file, expected Semicolon"
+    );
+
+    // A missing semicolon mid-file points just past the statement lacking it
+    assert_eq!(
+        error_diagnostic("let y = 1 // no semicolon\nlet z = 2;"),
+        "1:10: error: Missing semicolon"
+    );
+}
+
+#[test]
+fn multi_line_diagnostic() {
+    let source = "let x = 1;\n/* open\n   comment */ let y = 2;";
+    let span = 11..32;
+
+    let context = ParseContext::from_span(source, &span);
+    assert_eq!((context.line_number, context.column_start), (2, 1));
+    assert_eq!((context.end_line, context.end_column), (3, 14));
+
+    let diagnostic = Diagnostic::from(ParseError::InvalidSyntax {
+        message: "unexpected comment".to_string(),
+        span,
+        context,
+    });
+
+    assert_eq!(diagnostic.start(), (2, 1));
+    assert_eq!(diagnostic.end(), (3, 14));
+
+    // Single-line spans end on the same line
+    let error = TestHelper::src("let x = 1;\nlet 2 = y;").unwrap_err();
+    let diagnostic = Diagnostic::from(error);
+    assert_eq!(diagnostic.start(), (2, 5));
+    assert_eq!(diagnostic.end(), (2, 6));
+}
+
+#[test]
+fn invalid_token_recovery() {
+    let source = "let a = 1; @\nlet b = 2;\n@ let c = 3 #;";
+
+    // Without recovery the first invalid token fails the build
+    assert_eq!(
+        error_diagnostic(source),
+        "1:12: error: Lexer error: Invalid token ('@')"
+    );
+
+    let diagnostics = |mut parser: Parser| {
+        let result = parser
+            .parse()
+            .unwrap_or_else(|e| panic!("Failed to parse '{}': {}", source, e));
+        assert_eq!(result.statements().len(), 3);
+
+        result
+            .diagnostics()
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect::<Vec<_>>()
+    };
+    let expected = [
+        "1:12: error: Lexer error: Invalid token ('@')",
+        "3:1: error: Lexer error: Invalid token ('@')",
+        "3:13: error: Lexer error: Invalid token ('#')",
+    ];
+
+    let builder = || Parser::builder(source).recover_invalid_tokens(true);
+    assert_eq!(diagnostics(builder().build().unwrap()), expected);
+    assert_eq!(diagnostics(builder().build_streaming()), expected);
+}
+
+#[test]
+fn unterminated_string_diagnostic() {
+    let lex_error = |result: Result<Parser, ParseError>| match result.map(|_| ()).unwrap_err() {
+        ParseError::BuildError { message, span, .. } => (message, span),
+        error => panic!("Expected lexer error, got {:?}", error),
+    };
+    let error = |source| lex_error(Parser::builder(source).build());
+    let unterminated = |span| ("Unterminated string literal".to_string(), span);
+
+    // Mid-file the span stops at the end of the line, not the end of input
+    let source = "let a = 1;\nlet s = \"abc;\nlet b = 2;";
+    assert_eq!(error(source), unterminated(19..24));
+    assert_eq!(
+        error_diagnostic(source),
+        "2:9: error: Lexer error: Unterminated string literal ('\"abc;')"
+    );
+
+    // At the end of input, including after an escaped quote
+    assert_eq!(error("let s = \"abc"), unterminated(8..12));
+    assert_eq!(error(r#"x = "a\""#), unterminated(4..8));
+
+    // Single quotes only start strings when configured to
+    assert_eq!(error("x = 'ab").0, "Invalid token");
+    let result = Parser::builder("x = 'ab\ny;")
+        .string_quote(StringQuote::Both)
+        .build();
+    assert_eq!(lex_error(result), unterminated(4..7));
+}
+
+#[test]
+fn unterminated_comment_diagnostic() {
+    let source = "let a = 1;\n/* never closed\nlet b = 2;";
+
+    match TestHelper::src(source).unwrap_err() {
+        ParseError::BuildError { message, span, .. } => {
+            assert_eq!(message, "Unterminated block comment");
+            assert_eq!(span, 11..source.len());
+        }
+        error => panic!("Expected lexer error, got {:?}", error),
+    }
+
+    assert_eq!(
+        error_diagnostic("/** docs"),
+        "1:1: error: Lexer error: Unterminated block comment ('/** docs')"
+    );
+}
+
+#[test]
+fn parse_program_recovery() {
+    let source = "let a = 1;\nlet = 2;\nfn f() { let x = @; }\nconst B = (1 + ;\nlet c = a;";
+    let result = Parser::parse_program(source);
+
+    let diagnostics: Vec<String> = result
+        .diagnostics()
+        .iter()
+        .map(|diagnostic| diagnostic.to_string())
+        .collect();
+    assert_eq!(
+        diagnostics,
+        [
+            "3:18: error: Lexer error: Invalid token ('@')",
+            "2:5: error: Expected identifier, found Equal",
+            "3:19: error: Expected expression, found Semicolon",
+            "4:16: error: Expected expression, found Semicolon",
+        ]
+    );
+
+    // The statements around the broken ones survive
+    let names: Vec<&str> = result
+        .statements()
+        .iter()
+        .map(|stmt| match stmt {
+            Stmt::Let { name, .. } => name.as_str(),
+            stmt => panic!("Expected let, got {:?}", stmt),
+        })
+        .collect();
+    assert_eq!(names, ["a", "c"]);
+
+    // Valid input parses as usual
+    let result = Parser::parse_program("let a = 1;\nlet b = a;");
+    assert_eq!(result.statements().len(), 2);
+    assert!(result.diagnostics().is_empty());
+}
+
+#[test]
+fn diagnostic_levels() {
+    let result = TestHelper::src("let a = 1 / 0;\nlet badName = a;\nif badName {}").unwrap();
+    let messages = |diagnostics: Vec<&Diagnostic>| {
+        diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.to_string())
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(
+        messages(result.errors().collect()),
+        ["1:1: error: Division by zero"]
+    );
+    assert_eq!(
+        messages(result.warnings().collect()),
+        ["2:1: warning: expected 'bad_name'"]
+    );
+    assert_eq!(
+        messages(result.infos().collect()),
+        ["3:12: info: Empty block"]
+    );
+    assert!(result.has_errors());
+
+    let levels: Vec<_> = result.diagnostics().iter().map(Diagnostic::level).collect();
+    assert_eq!(
+        levels,
+        [
+            DiagnosticLevel::Error,
+            DiagnosticLevel::Warn,
+            DiagnosticLevel::Info
+        ]
+    );
+
+    let result = TestHelper::src("let a = 1;\nif a {}").unwrap();
+    assert!(!result.has_errors());
+    assert_eq!(result.infos().count(), 1);
+}
+
+#[test]
+fn diagnostic_fields() {
+    let result = TestHelper::src("fn f(\n    badName\n) {}").unwrap();
+    let diagnostic = &result.diagnostics()[0];
+
+    assert_eq!(diagnostic.line(), 2);
+    assert_eq!(diagnostic.column(), 5);
+    assert_eq!(diagnostic.length(), "badName".len());
+    assert_eq!(diagnostic.message(), "expected 'bad_name'");
+    assert_eq!(diagnostic.level(), DiagnosticLevel::Warn);
+    assert_eq!(diagnostic.start(), (2, 5));
+    assert_eq!(diagnostic.end(), (2, 12));
+}
+
+#[test]
+fn diagnostic_codes() {
+    let error = TestHelper::src("let x = 1 2;").unwrap_err();
+    assert!(matches!(error, ParseError::UnexpectedToken { .. }));
+    assert_eq!(error.code(), "E0001");
+
+    // The code travels with the diagnostic, apart from the message
+    let diagnostic = Diagnostic::from(error);
+    assert_eq!(diagnostic.code(), "E0001");
+    assert!(!diagnostic.message().contains("E0001"));
+    assert!(!diagnostic.to_string().contains("E0001"));
+
+    let result = TestHelper::src("let badName = 1;").unwrap();
+    let warning = result.warnings().next().unwrap();
+    assert_eq!(warning.code(), "W0003");
+    assert_eq!(warning.message(), "expected 'bad_name'");
+}
+
+#[test]
+fn duplicate_diagnostics() {
+    // Both divisions are reported at the statement start
+    let result = TestHelper::src("let x = 1 / 0 + 2 / 0;").unwrap();
+    let diagnostics = result.diagnostics();
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].to_string(), "1:1: error: Division by zero");
+    assert_eq!(diagnostics[0].count(), 2);
+
+    // The same message elsewhere is kept apart
+    let result = TestHelper::src("let x = 1 / 0;\nlet y = 2 / 0;").unwrap();
+    assert_eq!(result.diagnostics().len(), 2);
+    assert!(result.diagnostics().iter().all(|d| d.count() == 1));
+}
@@ -1,6 +1,14 @@
-use super::{TestHelper, assert_expr};
+use super::{TestHelper, assert_expr, assert_stmt};
 use cases::{ARITHMETIC_OPS, BITWISE_OPS, COMPARISON_OPS, ERROR_CASES, PRECEDENCE_CASES};
-use qbit_lang::ast::op::{BinaryOp, UnaryOp};
+use qbit_lang::{
+    ast::{
+        expr::Expr,
+        op::{Associativity, BinaryOp, Precedence, UnaryOp},
+        value::Value,
+    },
+    lexer::StringQuote,
+    parser::{Diagnostic, LimitKind, ParseError, Parser},
+};
 
 mod cases {
     use super::*;
@@ -53,19 +61,19 @@ mod cases {
             right: 3,
         },
         BinaryOpCase {
-            source: "5 % 3",
-            op: BinaryOp::Mod,
+            source: "5 \\ 3",
+            op: BinaryOp::FloorDiv,
             left: 5,
             right: 3,
         },
         BinaryOpCase {
-            source: "5 ** 3",
-            op: BinaryOp::Pow,
+            source: "5 % 3",
+            op: BinaryOp::Mod,
             left: 5,
             right: 3,
         },
         BinaryOpCase {
-            source: "5 ^ 3",
+            source: "5 ** 3",
             op: BinaryOp::Pow,
             left: 5,
             right: 3,
@@ -124,6 +132,12 @@ mod cases {
             left: 5,
             right: 3,
         },
+        BinaryOpCase {
+            source: "5 ^ 3",
+            op: BinaryOp::BitXor,
+            left: 5,
+            right: 3,
+        },
         BinaryOpCase {
             source: "5 << 3",
             op: BinaryOp::Shl,
@@ -139,6 +153,10 @@ mod cases {
     ];
 
     pub const PRECEDENCE_CASES: &[PrecedenceCase] = &[
+        PrecedenceCase {
+            source: "2 + 7 \\ 2",
+            expected: BinaryOp::Add,
+        },
         PrecedenceCase {
             source: "2 + 3 * 4",
             expected: BinaryOp::Add,
@@ -163,6 +181,14 @@ mod cases {
             source: "1 | 2 & 3",
             expected: BinaryOp::BitOr,
         },
+        PrecedenceCase {
+            source: "1 | 2 ^ 3",
+            expected: BinaryOp::BitOr,
+        },
+        PrecedenceCase {
+            source: "1 ^ 2 & 3",
+            expected: BinaryOp::BitXor,
+        },
     ];
 
     pub const ERROR_CASES: &[ErrorCase] = &[
@@ -186,6 +212,14 @@ mod cases {
             source: "func(1, 2,",
             expected: "Unexpected end of file",
         },
+        ErrorCase {
+            source: "'ab'",
+            expected: "Invalid token",
+        },
+        ErrorCase {
+            source: r"'\q'",
+            expected: "Invalid token",
+        },
     ];
 }
 
@@ -210,12 +244,29 @@ fn literal_expr() {
     let expr = TestHelper::assert_expr("false");
     assert_expr::literal_bool(&expr, false);
 
+    // Null literal
+    let expr = TestHelper::assert_expr("null");
+    assert!(matches!(expr, Expr::Literal(Value::Null)));
+
     // String literals
     let expr = TestHelper::assert_expr(r#""Hello, World!""#);
     assert_expr::literal_string(&expr, "Hello, World!");
 
     let expr = TestHelper::assert_expr(r#""with \"quotes\"""#);
     assert_expr::literal_string(&expr, r#"with "quotes""#);
+
+    // Char literals
+    let expr = TestHelper::assert_expr("'a'");
+    assert_expr::literal_char(&expr, 'a');
+
+    let expr = TestHelper::assert_expr(r"'\n'");
+    assert_expr::literal_char(&expr, '\n');
+
+    let expr = TestHelper::assert_expr(r"'\''");
+    assert_expr::literal_char(&expr, '\'');
+
+    let expr = TestHelper::assert_expr(r#"'"'"#);
+    assert_expr::literal_char(&expr, '"');
 }
 
 #[test]
@@ -292,6 +343,93 @@ fn unary_op_expr() {
     let operand1 = assert_expr::unary_op(&expr, UnaryOp::Not);
     let operand2 = assert_expr::unary_op(operand1, UnaryOp::Not);
     assert_expr::literal_bool(operand2, true);
+
+    // Unary plus, positional with respect to addition
+    let expr = TestHelper::assert_expr("+5");
+    let operand = assert_expr::unary_op(&expr, UnaryOp::Pos);
+    assert_expr::literal_int(operand, 5);
+
+    let expr = TestHelper::assert_expr("+\"3\"");
+    let operand = assert_expr::unary_op(&expr, UnaryOp::Pos);
+    assert_expr::literal_string(operand, "3");
+
+    let expr = TestHelper::assert_expr("a + +b");
+    let (left, right) = assert_expr::binary_op(&expr, BinaryOp::Add);
+    assert_expr::variable(left, "a");
+    assert_expr::variable(assert_expr::unary_op(right, UnaryOp::Pos), "b");
+
+    // Bitwise NOT
+    let expr = TestHelper::assert_expr("~5");
+    let operand = assert_expr::unary_op(&expr, UnaryOp::BitNot);
+    assert_expr::literal_int(operand, 5);
+
+    let expr = TestHelper::assert_expr("~~5");
+    let operand1 = assert_expr::unary_op(&expr, UnaryOp::BitNot);
+    let operand2 = assert_expr::unary_op(operand1, UnaryOp::BitNot);
+    assert_expr::literal_int(operand2, 5);
+
+    // Binds tighter than binary operators
+    let expr = TestHelper::assert_expr("~a & b");
+    let (left, _) = assert_expr::binary_op(&expr, BinaryOp::BitAnd);
+    assert_expr::unary_op(left, UnaryOp::BitNot);
+}
+
+#[test]
+fn xor_expr() {
+    // `^` sits between `|` and `&`: a | b ^ c & d = a | (b ^ (c & d))
+    let expr = TestHelper::assert_expr("a | b ^ c & d");
+    let (_, right) = assert_expr::binary_op(&expr, BinaryOp::BitOr);
+    let (left, right) = assert_expr::binary_op(right, BinaryOp::BitXor);
+    assert_expr::variable(left, "b");
+    assert_expr::binary_op(right, BinaryOp::BitAnd);
+
+    // Left-associative, unlike `**`
+    let expr = TestHelper::assert_expr("a ^ b ^ c");
+    let (left, right) = assert_expr::binary_op(&expr, BinaryOp::BitXor);
+    assert_expr::binary_op(left, BinaryOp::BitXor);
+    assert_expr::variable(right, "c");
+
+    // `^=` is XOR assignment, `**=` raises to a power
+    for (source, expected) in [("x ^= 1", BinaryOp::BitXor), ("x **= 2", BinaryOp::Pow)] {
+        match TestHelper::assert_expr(source) {
+            Expr::CompoundAssignment { op, .. } => assert_eq!(op, expected),
+            expr => panic!(
+                "Expected compound assignment for '{}', got {:?}",
+                source, expr
+            ),
+        }
+    }
+}
+
+#[test]
+fn logical_assignment_expr() {
+    for (source, expected) in [("x &&= y", BinaryOp::And), ("x ||= y", BinaryOp::Or)] {
+        match TestHelper::assert_expr(source) {
+            Expr::CompoundAssignment { target, op, value } => {
+                assert_eq!(op, expected);
+                assert_expr::variable(&target, "x");
+                assert_expr::variable(&value, "y");
+            }
+            expr => panic!(
+                "Expected compound assignment for '{}', got {:?}",
+                source, expr
+            ),
+        }
+    }
+
+    // Right-associative like the other assignments, and the value may contain `&&`/`||`
+    let expr = TestHelper::assert_expr("a ||= b &&= c || d");
+    match expr {
+        Expr::CompoundAssignment { value, .. } => match *value {
+            Expr::CompoundAssignment { value, .. } => {
+                assert_expr::binary_op(&value, BinaryOp::Or);
+            }
+            expr => panic!("Expected nested compound assignment, got {:?}", expr),
+        },
+        expr => panic!("Expected compound assignment, got {:?}", expr),
+    }
+
+    assert!(TestHelper::expr("1 &&= x").is_err());
 }
 
 #[test]
@@ -315,6 +453,62 @@ fn right_assoc_expr() {
     assert_expr::literal_int(inner_right, 2);
 }
 
+#[test]
+fn unary_pow_expr() {
+    // Exponentiation binds tighter than a leading unary operator: -2 ** 2 = -(2 ** 2)
+    let expr = TestHelper::assert_expr("-2 ** 2");
+    let operand = assert_expr::unary_op(&expr, UnaryOp::Neg);
+    let (base, exponent) = assert_expr::binary_op(operand, BinaryOp::Pow);
+    assert_expr::literal_int(base, 2);
+    assert_expr::literal_int(exponent, 2);
+
+    // A unary operator in the exponent applies to the exponent only: 2 ** -2 = 2 ** (-2)
+    let expr = TestHelper::assert_expr("2 ** -2");
+    let (base, exponent) = assert_expr::binary_op(&expr, BinaryOp::Pow);
+    assert_expr::literal_int(base, 2);
+    let operand = assert_expr::unary_op(exponent, UnaryOp::Neg);
+    assert_expr::literal_int(operand, 2);
+
+    // Still right-associative under negation: -2 ** 3 ** 2 = -(2 ** (3 ** 2))
+    let expr = TestHelper::assert_expr("-2 ** 3 ** 2");
+    let operand = assert_expr::unary_op(&expr, UnaryOp::Neg);
+    let (_, right) = assert_expr::binary_op(operand, BinaryOp::Pow);
+    assert_expr::binary_op(right, BinaryOp::Pow);
+
+    // Other binary operators still bind looser than unary: -a * b = (-a) * b
+    let expr = TestHelper::assert_expr("-a * b");
+    let (left, _) = assert_expr::binary_op(&expr, BinaryOp::Mul);
+    assert_expr::unary_op(left, UnaryOp::Neg);
+
+    // Parentheses restore a negative base
+    let expr = TestHelper::assert_expr("(-2) ** 2");
+    let (base, _) = assert_expr::binary_op(&expr, BinaryOp::Pow);
+    assert_expr::unary_op(assert_expr::group(base), UnaryOp::Neg);
+}
+
+#[test]
+fn sexpr_expr() {
+    let sexpr = |source: &str| TestHelper::assert_expr(source).to_sexpr();
+
+    // Precedence and associativity
+    assert_eq!(sexpr("2 + 3 * 4"), "(+ 2 (* 3 4))");
+    assert_eq!(sexpr("(2 + 3) * 4"), "(* (+ 2 3) 4)");
+    assert_eq!(sexpr("10 - 5 - 2"), "(- (- 10 5) 2)");
+    assert_eq!(sexpr("2 ** 3 ** 2"), "(** 2 (** 3 2))");
+    assert_eq!(sexpr("-x ** 2"), "(- (** x 2))");
+    assert_eq!(sexpr("!a && b || c == d"), "(|| (&& (! a) b) (== c d))");
+    assert_eq!(sexpr("a | b ^ c & d"), "(| a (^ b (& c d)))");
+    assert_eq!(sexpr("x = y += 1 + 2"), "(= x (+= y (+ 1 2)))");
+
+    // Postfix chains bind tighter than any operator
+    assert_eq!(sexpr("-f(a, b)[0].len"), "(- (. ([] (call f a b) 0) len))");
+    assert_eq!(sexpr("a?.b?.(c)"), "(?.call (?. a b) c)");
+    assert_eq!(sexpr("++i + j--"), "(+ (pre++ i) (post-- j))");
+    assert_eq!(sexpr("f(...[1, \"s\"])"), "(call f (... (array 1 \"s\")))");
+    assert_eq!(sexpr("{a: 1.0}.a"), "(. (object (\"a\" 1.0)) a)");
+    assert_eq!(sexpr("{ f(); 1 }"), "(block (call f) 1)");
+}
+
 #[test]
 fn left_assoc_expr() {
     // Subtraction should be left-associative: 10 - 5 - 2 = (10 - 5) - 2
@@ -399,6 +593,30 @@ fn arr_lit_expr() {
     assert_expr::literal_int(&second_nested[1], 4);
 }
 
+#[test]
+fn object_lit_expr() {
+    let expr = TestHelper::assert_expr("{a: 1, \"b c\": [2], a: x}");
+    let values = assert_expr::object(&expr, &["a", "b c", "a"]);
+    assert_expr::literal_int(values[0], 1);
+    assert_expr::array(values[1], 1);
+    assert_expr::variable(values[2], "x");
+
+    // Nested objects and member access on the literal
+    let expr = TestHelper::assert_expr("{outer: {inner: 1}}.outer");
+    let object = assert_expr::member(&expr, "outer");
+    let values = assert_expr::object(object, &["outer"]);
+    assert_expr::object(values[0], &["inner"]);
+
+    // `{}` is still an empty block and a label still needs its loop
+    let expr = TestHelper::assert_expr("{}");
+    assert_expr::block(&expr, 0);
+    let expr = TestHelper::assert_expr("{ outer: while true { break outer; } }");
+    assert_expr::block(&expr, 1);
+
+    TestHelper::assert_expr_err("{a: 1 b: 2}", "Expected ',' or '}'");
+    TestHelper::assert_expr_err("{a: 1, 2: 3}", "Expected object key");
+}
+
 #[test]
 fn arr_index_expr() {
     // Simple indexing
@@ -448,6 +666,144 @@ fn member_expr() {
     assert_expr::literal_int(index, 0);
 }
 
+#[test]
+fn spread_expr() {
+    // Spread inside an array literal
+    let expr = TestHelper::assert_expr("[1, ...rest, 4]");
+    let elements = assert_expr::array(&expr, 3);
+    assert_expr::literal_int(&elements[0], 1);
+    assert_expr::variable(assert_expr::spread(&elements[1]), "rest");
+    assert_expr::literal_int(&elements[2], 4);
+
+    // Spread of an arbitrary expression in call arguments
+    let expr = TestHelper::assert_expr("f(a, ...get(b))");
+    let (_, args) = assert_expr::call(&expr, "f", 2);
+    assert_expr::variable(&args[0], "a");
+    assert_expr::call(assert_expr::spread(&args[1]), "get", 1);
+
+    // Anywhere else spread is rejected
+    TestHelper::assert_expr_err(
+        "...a",
+        "spread is only allowed in array literals and call arguments",
+    );
+    TestHelper::assert_expr_err(
+        "1 + ...a",
+        "spread is only allowed in array literals and call arguments",
+    );
+    TestHelper::assert_stmt_err(
+        "let x = ...a;",
+        "spread is only allowed in array literals and call arguments",
+    );
+}
+
+#[test]
+fn method_call_expr() {
+    // Calls chain onto member and index accesses
+    let expr = TestHelper::assert_expr("obj.method(1)");
+    let callee = match &expr {
+        Expr::Call {
+            callee,
+            args,
+            optional: false,
+        } => {
+            assert_expr::literal_int(&args[0], 1);
+            callee
+        }
+        _ => panic!("Expected Call, got {:?}", expr),
+    };
+    let object = assert_expr::member(callee, "method");
+    assert_expr::variable(object, "obj");
+
+    let expr = TestHelper::assert_expr("handlers[0]()");
+    match &expr {
+        Expr::Call { callee, .. } => {
+            let (object, _) = assert_expr::index(callee);
+            assert_expr::variable(object, "handlers");
+        }
+        _ => panic!("Expected Call, got {:?}", expr),
+    }
+}
+
+#[test]
+fn optional_chain_expr() {
+    let expr = TestHelper::assert_expr("obj?.prop");
+    let object = assert_expr::optional_member(&expr, "prop");
+    assert_expr::variable(object, "obj");
+
+    // Only the `?.` link of a chain is optional
+    let expr = TestHelper::assert_expr("a?.b.c?.d");
+    let c = assert_expr::optional_member(&expr, "d");
+    let b = assert_expr::member(c, "c");
+    let a = assert_expr::optional_member(b, "b");
+    assert_expr::variable(a, "a");
+
+    // Optional method call: the member is optional, the call itself is not
+    let expr = TestHelper::assert_expr("obj?.method()");
+    match &expr {
+        Expr::Call {
+            callee,
+            optional: false,
+            ..
+        } => {
+            let object = assert_expr::optional_member(callee, "method");
+            assert_expr::variable(object, "obj");
+        }
+        _ => panic!("Expected Call, got {:?}", expr),
+    }
+
+    // Optional call on the callee itself
+    let expr = TestHelper::assert_expr("callback?.(1, 2)");
+    match &expr {
+        Expr::Call {
+            callee,
+            args,
+            optional: true,
+        } => {
+            assert_expr::variable(callee, "callback");
+            assert_eq!(args.len(), 2);
+        }
+        _ => panic!("Expected optional Call, got {:?}", expr),
+    }
+
+    TestHelper::assert_expr_err("obj?.", "Unexpected end of file, expected property name");
+    TestHelper::assert_expr_err("obj?.[0]", "Expected identifier");
+}
+
+#[test]
+fn block_expr() {
+    let expr = TestHelper::assert_expr("{ let a = 1; a + 2 }");
+    let (statements, tail) = assert_expr::block(&expr, 1);
+    let value = assert_stmt::let_stmt(&statements[0], "a");
+    assert_expr::literal_int(value, 1);
+    let (a, two) = assert_expr::binary_op(tail.as_ref().unwrap(), BinaryOp::Add);
+    assert_expr::variable(a, "a");
+    assert_expr::literal_int(two, 2);
+
+    // A trailing semicolon leaves the block without a tail
+    let expr = TestHelper::assert_expr("{ f(); g(); }");
+    let (statements, tail) = assert_expr::block(&expr, 2);
+    assert_stmt::expression_stmt(&statements[1]);
+    assert!(tail.is_none());
+
+    let expr = TestHelper::assert_expr("{}");
+    let (_, tail) = assert_expr::block(&expr, 0);
+    assert!(tail.is_none());
+
+    // Blocks are primaries, so they can be operands
+    let expr = TestHelper::assert_expr("{ 1 } * 2");
+    let (block, _) = assert_expr::binary_op(&expr, BinaryOp::Mul);
+    assert_expr::block(block, 0);
+
+    // In statement position a brace still starts a statement block
+    let stmt = TestHelper::stmt("let x = { let a = 1; a + 2 };").unwrap();
+    let value = assert_stmt::let_stmt(&stmt, "x");
+    assert_expr::block(value, 1);
+    assert_stmt::block_stmt(&TestHelper::stmt("{ a; }").unwrap(), 1);
+
+    TestHelper::assert_expr_err("{ a b }", "Expected RightBrace");
+    TestHelper::assert_expr_err("{ let a = 1;", "expected RightBrace");
+}
+
 #[test]
 fn complex_expr() {
     // Test a complex expression with multiple operators and precedence
@@ -512,6 +868,43 @@ fn comment_expr() {
     assert_expr::literal_int(right, 3);
 }
 
+#[test]
+fn folded_negative_literal_expr() {
+    // Unfolded by default
+    let expr = TestHelper::assert_expr("-123");
+    let operand = assert_expr::unary_op(&expr, UnaryOp::Neg);
+    assert_expr::literal_int(operand, 123);
+
+    // Folded, a minus directly on a number literal becomes part of it
+    assert_expr::literal_int(&TestHelper::folded_expr("-123"), -123);
+    assert_expr::literal_float(&TestHelper::folded_expr("-1.5"), -1.5);
+
+    let expr = TestHelper::folded_expr("10 - -5");
+    let (_, right) = assert_expr::binary_op(&expr, BinaryOp::Sub);
+    assert_expr::literal_int(right, -5);
+
+    // Only the innermost minus touches the literal
+    let expr = TestHelper::folded_expr("- -5");
+    let operand = assert_expr::unary_op(&expr, UnaryOp::Neg);
+    assert_expr::literal_int(operand, -5);
+
+    // Non-literal operands and exponents keep their negation
+    let expr = TestHelper::folded_expr("-x");
+    let operand = assert_expr::unary_op(&expr, UnaryOp::Neg);
+    assert_expr::variable(operand, "x");
+
+    let expr = TestHelper::folded_expr("-2 ** 2");
+    let operand = assert_expr::unary_op(&expr, UnaryOp::Neg);
+    assert_expr::binary_op(operand, BinaryOp::Pow);
+
+    let expr = TestHelper::folded_expr("-(5)");
+    let operand = assert_expr::unary_op(&expr, UnaryOp::Neg);
+    assert_expr::group(operand);
+
+    let expr = TestHelper::folded_expr("!5");
+    assert_expr::unary_op(&expr, UnaryOp::Not);
+}
+
 #[test]
 fn trailing_commas_expr() {
     // Function arguments with trailing comma
@@ -528,3 +921,437 @@ fn trailing_commas_expr() {
     assert_expr::literal_int(&elements[1], 2);
     assert_expr::literal_int(&elements[2], 3);
 }
+
+#[test]
+fn template_string_expr() {
+    // Literal-only templates are plain strings
+    let expr = TestHelper::assert_expr("`hello\nworld`");
+    assert_expr::literal_string(&expr, "hello\nworld");
+
+    let expr = TestHelper::assert_expr("``");
+    assert_expr::literal_string(&expr, "");
+
+    // One interpolation
+    let expr = TestHelper::assert_expr("`hello ${name}!`");
+    let (left, right) = assert_expr::binary_op(&expr, BinaryOp::Add);
+    assert_expr::literal_string(right, "!");
+    let (left, right) = assert_expr::binary_op(left, BinaryOp::Add);
+    assert_expr::literal_string(left, "hello ");
+    assert_expr::variable(right, "name");
+
+    // A leading interpolation still concatenates onto a string
+    let expr = TestHelper::assert_expr("`${a + b}`");
+    let (left, right) = assert_expr::binary_op(&expr, BinaryOp::Add);
+    assert_expr::literal_string(left, "");
+    assert_expr::binary_op(right, BinaryOp::Add);
+
+    // Nested braces inside the interpolated expression
+    let expr = TestHelper::assert_expr("`v: ${ { let x = 1; x } }`");
+    let (_, right) = assert_expr::binary_op(&expr, BinaryOp::Add);
+    let (statements, tail) = assert_expr::block(right, 1);
+    assert_stmt::let_stmt(&statements[0], "x");
+    assert_expr::variable(tail.as_ref().unwrap(), "x");
+
+    TestHelper::assert_expr_err("`${}`", "empty interpolation in template string");
+    TestHelper::assert_expr_err("`${a b}`", "Expected '}', found Identifier");
+    TestHelper::assert_expr_err("`${a", "Lexer error");
+}
+
+#[test]
+fn assignment_target_expr() {
+    for source in ["x = 1", "a.b = 1", "a[0] = 1", "a.b[c].d -= 2", "a = b = c"] {
+        TestHelper::assert_expr(source);
+    }
+
+    // The error covers the whole target
+    let cases = [
+        ("5 = x", 0..1),
+        ("(a + b) = 1", 0..7),
+        ("f() = 1", 0..3),
+        ("a?.b = 1", 0..4),
+        ("\"s\" += 1", 0..3),
+        ("a = 5 = 1", 4..5),
+    ];
+
+    for (source, expected) in cases {
+        match TestHelper::expr(source) {
+            Err(ParseError::InvalidSyntax { message, span, .. }) => {
+                assert_eq!(message, "Invalid assignment target");
+                assert_eq!(span, expected, "span for '{}'", source);
+            }
+            result => panic!("Expected invalid target for '{}', got {:?}", source, result),
+        }
+    }
+}
+
+#[test]
+fn dotted_float_expr() {
+    assert_expr::literal_float(&TestHelper::assert_expr(".5"), 0.5);
+    assert_expr::literal_float(&TestHelper::assert_expr("5."), 5.0);
+
+    let expr = TestHelper::assert_expr("5. + .25");
+    let (left, right) = assert_expr::binary_op(&expr, BinaryOp::Add);
+    assert_expr::literal_float(left, 5.0);
+    assert_expr::literal_float(right, 0.25);
+
+    let expr = TestHelper::assert_expr("f(.5, 1.)");
+    let (_, args) = assert_expr::call(&expr, "f", 2);
+    assert_expr::literal_float(&args[0], 0.5);
+    assert_expr::literal_float(&args[1], 1.0);
+
+    // A dot after an operand is still member access, so a number can't follow it
+    let expr = TestHelper::assert_expr("obj.field");
+    assert_expr::variable(assert_expr::member(&expr, "field"), "obj");
+    assert_expr::member(&TestHelper::assert_expr("1.5.field"), "field");
+    TestHelper::assert_expr_err("x.5", "Expected property name after '.', found number");
+    TestHelper::assert_expr_err("a[0].5", "Expected property name after '.', found number");
+}
+
+#[test]
+fn member_assignment_expr() {
+    let assignment = |source: &str| match TestHelper::assert_expr(source) {
+        Expr::Assignment { target, value } => (*target, *value),
+        expr => panic!("Expected assignment for '{}', got {:?}", source, expr),
+    };
+
+    let (target, value) = assignment("obj.x = 1");
+    assert_expr::variable(assert_expr::member(&target, "x"), "obj");
+    assert_expr::literal_int(&value, 1);
+
+    match TestHelper::assert_expr("arr[i] += 2") {
+        Expr::CompoundAssignment { target, op, value } => {
+            let (object, index) = assert_expr::index(&target);
+            assert_expr::variable(object, "arr");
+            assert_expr::variable(index, "i");
+            assert_eq!(op, BinaryOp::Add);
+            assert_expr::literal_int(&value, 2);
+        }
+        expr => panic!("Expected compound assignment, got {:?}", expr),
+    }
+
+    // The whole postfix chain is the target, not just its last link
+    let (target, value) = assignment("obj.arr[i].y = 3");
+    let (object, index) = assert_expr::index(assert_expr::member(&target, "y"));
+    assert_expr::variable(assert_expr::member(object, "arr"), "obj");
+    assert_expr::variable(index, "i");
+    assert_expr::literal_int(&value, 3);
+}
+
+#[test]
+fn increment_operand_expr() {
+    for source in ["i++", "arr[i]++", "obj.x--", "++i", "--obj.items[0]"] {
+        TestHelper::assert_expr(source);
+    }
+
+    let cases = [
+        ("5++", "Invalid increment operand", 0..1),
+        ("(a)++", "Invalid increment operand", 0..3),
+        ("foo()--", "Invalid decrement operand", 0..5),
+        ("++5", "Invalid increment operand", 2..3),
+        ("--f(x)", "Invalid decrement operand", 2..6),
+        ("i++ ++", "Invalid increment operand", 0..3),
+    ];
+
+    for (source, expected, expected_span) in cases {
+        match TestHelper::expr(source) {
+            Err(ParseError::InvalidSyntax { message, span, .. }) => {
+                assert_eq!(message, expected);
+                assert_eq!(span, expected_span, "span for '{}'", source);
+            }
+            result => panic!(
+                "Expected invalid operand for '{}', got {:?}",
+                source, result
+            ),
+        }
+    }
+}
+
+#[test]
+fn prefix_increment_place_expr() {
+    // The prefix operator wraps the whole member/index chain, not just the primary
+    match TestHelper::assert_expr("++obj.count") {
+        Expr::PreIncrement { operand } => {
+            let object = assert_expr::member(&operand, "count");
+            assert_expr::variable(object, "obj");
+        }
+        expr => panic!("Expected pre-increment, got {:?}", expr),
+    }
+
+    match TestHelper::assert_expr("--arr[i]") {
+        Expr::PreDecrement { operand } => {
+            let (object, index) = assert_expr::index(&operand);
+            assert_expr::variable(object, "arr");
+            assert_expr::variable(index, "i");
+        }
+        expr => panic!("Expected pre-decrement, got {:?}", expr),
+    }
+
+    match TestHelper::assert_expr("++a.b.c") {
+        Expr::PreIncrement { operand } => {
+            let object = assert_expr::member(&operand, "c");
+            let object = assert_expr::member(object, "b");
+            assert_expr::variable(object, "a");
+        }
+        expr => panic!("Expected pre-increment, got {:?}", expr),
+    }
+
+    // Binds tighter than binary operators
+    let expr = TestHelper::assert_expr("++obj.count * 2");
+    let (left, _) = assert_expr::binary_op(&expr, BinaryOp::Mul);
+    assert!(matches!(left, Expr::PreIncrement { .. }));
+}
+
+#[test]
+fn disabled_operator_expr() {
+    let parse = |source: &str| {
+        Parser::builder(source)
+            .disable_operator(BinaryOp::Pow)
+            .disable_operator(BinaryOp::BitXor)
+            .build()
+            .unwrap()
+            .parse()
+    };
+
+    let cases = [
+        ("let x = 2 ** 3;", "Operator '**' is disabled", 10..12),
+        ("let x = a + b ^ c;", "Operator '^' is disabled", 14..15),
+        ("x **= 2;", "Operator '**=' is disabled", 2..5),
+        ("x ^= 1;", "Operator '^=' is disabled", 2..4),
+    ];
+
+    for (source, expected, expected_span) in cases {
+        match parse(source) {
+            Err(ParseError::InvalidSyntax { message, span, .. }) => {
+                assert_eq!(message, expected);
+                assert_eq!(span, expected_span, "span for '{}'", source);
+            }
+            result => panic!(
+                "Expected disabled operator for '{}', got {:?}",
+                source, result
+            ),
+        }
+    }
+
+    // Other operators, including other bitwise ones, still parse
+    parse("let x = 2 * 3 + (a & b) | c << 1; x += 1; x &= 3;").unwrap();
+}
+
+#[test]
+fn string_quote_expr() {
+    let literal = |source: &str, quote: StringQuote| {
+        let result = Parser::builder(source)
+            .string_quote(quote)
+            .build()
+            .and_then(|mut parser| parser.parse());
+
+        match result.as_ref().map(|result| result.statements()) {
+            Ok([stmt]) => match assert_stmt::expression_stmt(stmt) {
+                Expr::Literal(value) => Ok(value.clone()),
+                expr => panic!("Expected literal for '{}', got {:?}", source, expr),
+            },
+            _ => Err(result.unwrap_err().to_string()),
+        }
+    };
+    let string = |s: &str| Ok(Value::Str(s.to_string()));
+    let invalid = |text: &str| Err(format!("Lexer error: Invalid token ('{text}')"));
+
+    // Double quotes only, single quotes are chars
+    assert_eq!(literal("\"hello\";", StringQuote::Double), string("hello"));
+    assert_eq!(literal("'c';", StringQuote::Double), Ok(Value::Char('c')));
+    assert_eq!(literal("'hello';", StringQuote::Double), invalid("'hello'"));
+
+    // Single quotes only
+    assert_eq!(literal("'hello';", StringQuote::Single), string("hello"));
+    assert_eq!(literal("'c';", StringQuote::Single), string("c"));
+    assert_eq!(literal(r"'it\'s';", StringQuote::Single), string("it's"));
+    assert_eq!(
+        literal("\"hello\";", StringQuote::Single),
+        invalid("\"hello\"")
+    );
+
+    // Either quote, and no char literals
+    assert_eq!(literal("\"hello\";", StringQuote::Both), string("hello"));
+    assert_eq!(literal("'hello';", StringQuote::Both), string("hello"));
+    assert_eq!(literal("'c';", StringQuote::Both), string("c"));
+    assert_eq!(literal("'';", StringQuote::Both), string(""));
+}
+
+#[test]
+fn expression_node_limit_expr() {
+    // Flat enough to pass the recursion check, but with 2000 nodes
+    let source = format!("let total = {};", vec!["x"; 1000].join(" + "));
+    let parse = |max| {
+        Parser::builder(&source)
+            .max_expression_nodes(max)
+            .build()
+            .unwrap()
+            .parse()
+    };
+
+    parse(2000).unwrap();
+
+    match parse(100) {
+        Err(ParseError::LimitExceeded { kind, limit, .. }) => {
+            assert_eq!(kind, LimitKind::ExpressionNodes);
+            assert_eq!(limit, 100);
+        }
+        result => panic!("Expected LimitExceeded, got {:?}", result),
+    }
+
+    let error = parse(100).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Too many expression nodes, the limit is 100"
+    );
+    // Reported at the 51st operand, the 101st node after 50 operands and 50 operators
+    assert_eq!(Diagnostic::from(error).start(), (1, 213));
+}
+
+#[test]
+fn recursion_limit_expr() {
+    let source = format!(
+        "let a = 1;\nlet b =\n    {}1{};",
+        "(".repeat(50),
+        ")".repeat(50)
+    );
+    let mut parser = Parser::builder(&source)
+        .max_recursion_depth(20)
+        .build()
+        .unwrap();
+
+    let error = parser.parse().err().unwrap();
+    match &error {
+        ParseError::TooMuchRecursion {
+            max_depth, context, ..
+        } => {
+            assert_eq!(*max_depth, 20);
+            assert_eq!(context.line_number, 3);
+            assert!(context.column_start > 4);
+        }
+        _ => panic!("Expected TooMuchRecursion, got {:?}", error),
+    }
+
+    assert_eq!(error.to_string(), "Maximum recursion depth (20) exceeded");
+
+    // Diagnostics report the line and column rather than the byte position
+    let diagnostic = Diagnostic::from(error).to_string();
+    assert!(
+        diagnostic.starts_with("3:"),
+        "Unexpected diagnostic: {}",
+        diagnostic
+    );
+}
+
+#[test]
+fn precedence_table() {
+    let table = BinaryOp::precedence_table();
+
+    for (op, _) in table {
+        assert_eq!(table.iter().filter(|(other, _)| other == op).count(), 1);
+    }
+
+    assert!(BinaryOp::Mul.precedence() > BinaryOp::Add.precedence());
+    assert_eq!(BinaryOp::Lt.precedence(), BinaryOp::Ge.precedence());
+
+    // Fails to compile when an operator is added, as a reminder to extend the table
+    let count = |op: BinaryOp| match op {
+        BinaryOp::Add
+        | BinaryOp::Sub
+        | BinaryOp::Mul
+        | BinaryOp::Div
+        | BinaryOp::FloorDiv
+        | BinaryOp::Mod
+        | BinaryOp::Pow
+        | BinaryOp::Eq
+        | BinaryOp::Neq
+        | BinaryOp::Lt
+        | BinaryOp::Le
+        | BinaryOp::Gt
+        | BinaryOp::Ge
+        | BinaryOp::And
+        | BinaryOp::Or
+        | BinaryOp::BitAnd
+        | BinaryOp::BitOr
+        | BinaryOp::BitXor
+        | BinaryOp::Shl
+        | BinaryOp::Shr => 20,
+    };
+    assert_eq!(table.len(), count(BinaryOp::Add));
+
+    assert!(table.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+}
+
+#[test]
+fn associativity() {
+    assert_eq!(BinaryOp::Pow.associativity(), Associativity::Right);
+
+    for op in [BinaryOp::Sub, BinaryOp::Div, BinaryOp::Or, BinaryOp::Shl] {
+        assert_eq!(op.associativity(), Associativity::Left, "{op:?}");
+    }
+
+    // 2 ** 3 ** 2 groups as 2 ** (3 ** 2)
+    let expr = TestHelper::assert_expr("2 ** 3 ** 2");
+    let (left, right) = assert_expr::binary_op(&expr, BinaryOp::Pow);
+    assert_expr::literal_int(left, 2);
+    assert_expr::binary_op(right, BinaryOp::Pow);
+
+    // 8 - 4 - 2 groups as (8 - 4) - 2
+    let expr = TestHelper::assert_expr("8 - 4 - 2");
+    let (left, right) = assert_expr::binary_op(&expr, BinaryOp::Sub);
+    assert_expr::binary_op(left, BinaryOp::Sub);
+    assert_expr::literal_int(right, 2);
+}
+
+#[test]
+fn partial_expr() {
+    let source = "1 + 2; 3";
+    let (expr, offset) = Parser::parse_expr_partial(source).unwrap();
+
+    let (left, right) = assert_expr::binary_op(&expr, BinaryOp::Add);
+    assert_expr::literal_int(left, 1);
+    assert_expr::literal_int(right, 2);
+    assert_eq!(offset, 5);
+    assert_eq!(&source[offset..], "; 3");
+
+    // Nothing left over
+    let (_, offset) = Parser::parse_expr_partial("a.b(1)").unwrap();
+    assert_eq!(offset, "a.b(1)".len());
+
+    // Parsing stops before the first token that cannot continue the expression
+    let (expr, offset) = Parser::parse_expr_partial("x  y").unwrap();
+    assert_expr::variable(&expr, "x");
+    assert_eq!(offset, 3);
+
+    assert!(Parser::parse_expr_partial("1 +").is_err());
+}
+
+#[test]
+fn sequence_expr() {
+    let expr = TestHelper::expr("(a, b, c)").unwrap();
+    let exprs = assert_expr::sequence(assert_expr::group(&expr), 3);
+    assert_expr::variable(&exprs[0], "a");
+    assert_expr::variable(&exprs[2], "c");
+
+    // Lowest precedence, below assignment
+    let expr = TestHelper::expr("(x = 1, y += 2)").unwrap();
+    let exprs = assert_expr::sequence(assert_expr::group(&expr), 2);
+    assert!(matches!(exprs[0], Expr::Assignment { .. }));
+    assert!(matches!(exprs[1], Expr::CompoundAssignment { .. }));
+
+    // As a statement
+    let stmt = TestHelper::stmt("i++, j--;").unwrap();
+    assert_expr::sequence(assert_stmt::expression_stmt(&stmt), 2);
+
+    // Commas still separate arguments and elements
+    let expr = TestHelper::expr("f(a, b)").unwrap();
+    assert_expr::call(&expr, "f", 2);
+    let expr = TestHelper::expr("f((a, b))").unwrap();
+    let (_, args) = assert_expr::call(&expr, "f", 1);
+    assert_expr::sequence(assert_expr::group(&args[0]), 2);
+    let expr = TestHelper::expr("[a, b]").unwrap();
+    assert_expr::array(&expr, 2);
+
+    // A bare sequence is not an expression on its own
+    assert!(TestHelper::expr("a, b").is_err());
+    TestHelper::assert_expr_err("(a, )", "Expected expression");
+}
@@ -1,10 +1,17 @@
 use qbit_lang::{
-    ast::{expr::Expr, stmt::Stmt},
-    parser::{ParseError, ParseResult, Parser},
+    ast::{
+        expr::Expr,
+        stmt::{Binding, Param, Stmt},
+    },
+    parser::{Parse, ParseError, ParseResult, Parser},
 };
 
+mod analyzer;
+mod checkpoint;
+mod diagnostic;
 mod expr;
 mod stmt;
+mod stream;
 
 struct TestHelper;
 
@@ -21,6 +28,31 @@ impl TestHelper {
         Parser::parse_src(source)
     }
 
+    pub fn folded_expr(source: &str) -> Expr {
+        let mut parser = Parser::builder(source)
+            .fold_negative_literals(true)
+            .build()
+            .unwrap_or_else(|e| panic!("Failed to lex '{}': {}", source, e));
+
+        Expr::parse(&mut parser)
+            .unwrap_or_else(|e| panic!("Failed to parse expression '{}': {}", source, e))
+    }
+
+    pub fn strict_stmt(source: &str) -> Result<Stmt, ParseError> {
+        let mut parser = Parser::builder(source)
+            .require_let_initializer(true)
+            .build()?;
+
+        Stmt::parse(&mut parser)
+    }
+
+    pub fn optional_semicolons_src(source: &str) -> Result<ParseResult, ParseError> {
+        Parser::builder(source)
+            .allow_optional_semicolons(true)
+            .build()?
+            .parse()
+    }
+
     pub fn assert_expr(source: &str) -> Expr {
         Self::expr(source)
             .unwrap_or_else(|e| panic!("Failed to parse expression '{}': {}", source, e))
@@ -55,6 +87,7 @@ mod assert_expr {
     use qbit_lang::ast::{
         expr::Expr,
         op::{BinaryOp, UnaryOp},
+        stmt::Stmt,
         value::Value,
     };
 
@@ -86,6 +119,13 @@ mod assert_expr {
         }
     }
 
+    pub fn literal_char(expr: &Expr, expected: char) {
+        match expr {
+            Expr::Literal(Value::Char(actual)) => assert_eq!(*actual, expected),
+            _ => panic!("Expected Char literal {:?}, got {:?}", expected, expr),
+        }
+    }
+
     pub fn variable(expr: &Expr, expected: &str) {
         match expr {
             Expr::Variable(actual) => assert_eq!(actual, expected),
@@ -127,7 +167,7 @@ mod assert_expr {
         expected_arg_count: usize,
     ) -> (&'a Expr, &'a Vec<Expr>) {
         match expr {
-            Expr::Call { callee, args } => {
+            Expr::Call { callee, args, .. } => {
                 variable(callee, expected_callee);
                 assert_eq!(args.len(), expected_arg_count);
                 (callee, args)
@@ -146,9 +186,27 @@ mod assert_expr {
         }
     }
 
+    pub fn object<'a>(expr: &'a Expr, expected_keys: &[&str]) -> Vec<&'a Expr> {
+        match expr {
+            Expr::Object { entries } => {
+                let keys = entries
+                    .iter()
+                    .map(|(key, _)| key.as_str())
+                    .collect::<Vec<_>>();
+                assert_eq!(keys, expected_keys);
+                entries.iter().map(|(_, value)| value).collect()
+            }
+            _ => panic!("Expected Object, got {:?}", expr),
+        }
+    }
+
     pub fn member<'a>(expr: &'a Expr, expected_property: &'a str) -> &'a Expr {
         match expr {
-            Expr::Member { object, property } => {
+            Expr::Member {
+                object,
+                property,
+                optional: false,
+            } => {
                 assert_eq!(property, expected_property);
                 object
             }
@@ -156,6 +214,20 @@ mod assert_expr {
         }
     }
 
+    pub fn optional_member<'a>(expr: &'a Expr, expected_property: &'a str) -> &'a Expr {
+        match expr {
+            Expr::Member {
+                object,
+                property,
+                optional: true,
+            } => {
+                assert_eq!(property, expected_property);
+                object
+            }
+            _ => panic!("Expected optional Member access, got {:?}", expr),
+        }
+    }
+
     pub fn index(expr: &Expr) -> (&Expr, &Expr) {
         match expr {
             Expr::Index { object, index } => (object, index),
@@ -163,12 +235,39 @@ mod assert_expr {
         }
     }
 
+    pub fn spread(expr: &Expr) -> &Expr {
+        match expr {
+            Expr::Spread { expr } => expr,
+            _ => panic!("Expected Spread, got {:?}", expr),
+        }
+    }
+
+    pub fn block(expr: &Expr, expected_len: usize) -> (&Vec<Stmt>, &Option<Box<Expr>>) {
+        match expr {
+            Expr::Block { statements, tail } => {
+                assert_eq!(statements.len(), expected_len);
+                (statements, tail)
+            }
+            _ => panic!("Expected Block expression, got {:?}", expr),
+        }
+    }
+
     pub fn group(expr: &Expr) -> &Expr {
         match expr {
             Expr::Group(inner) => inner,
             _ => panic!("Expected Group, got {:?}", expr),
         }
     }
+
+    pub fn sequence(expr: &Expr, expected_len: usize) -> &Vec<Expr> {
+        match expr {
+            Expr::Sequence(exprs) => {
+                assert_eq!(exprs.len(), expected_len);
+                exprs
+            }
+            _ => panic!("Expected Sequence, got {:?}", expr),
+        }
+    }
 }
 
 mod assert_stmt {
@@ -176,7 +275,7 @@ mod assert_stmt {
 
     pub fn let_stmt<'a>(stmt: &'a Stmt, expected_name: &'a str) -> &'a Expr {
         match stmt {
-            Stmt::Let { name, value } => {
+            Stmt::Let { name, value, .. } => {
                 assert_eq!(name, expected_name);
                 value
             }
@@ -184,9 +283,20 @@ mod assert_stmt {
         }
     }
 
+    pub fn let_multi_stmt<'a>(stmt: &'a Stmt, expected_names: &[&str]) -> &'a [Binding] {
+        match stmt {
+            Stmt::LetMulti { bindings, .. } => {
+                let names: Vec<&str> = bindings.iter().map(|b| b.name.as_str()).collect();
+                assert_eq!(names, expected_names);
+                bindings
+            }
+            _ => panic!("Expected LetMulti statement, got {:?}", stmt),
+        }
+    }
+
     pub fn const_stmt<'a>(stmt: &'a Stmt, expected_name: &str) -> &'a Expr {
         match stmt {
-            Stmt::Const { name, value } => {
+            Stmt::Const { name, value, .. } => {
                 assert_eq!(name, expected_name);
                 value
             }
@@ -198,9 +308,11 @@ mod assert_stmt {
         stmt: &'a Stmt,
         expected_name: &'a str,
         expected_param_count: usize,
-    ) -> (&'a Vec<String>, &'a Stmt) {
+    ) -> (&'a Vec<Param>, &'a Stmt) {
         match stmt {
-            Stmt::Function { name, params, body } => {
+            Stmt::Function {
+                name, params, body, ..
+            } => {
                 assert_eq!(name, expected_name);
                 assert_eq!(params.len(), expected_param_count);
                 (params, body)
@@ -209,12 +321,32 @@ mod assert_stmt {
         }
     }
 
+    pub fn leading_comments(stmt: &Stmt) -> Vec<&str> {
+        match stmt {
+            Stmt::Let {
+                leading_comments, ..
+            }
+            | Stmt::Const {
+                leading_comments, ..
+            }
+            | Stmt::Function {
+                leading_comments, ..
+            } => leading_comments.iter().map(String::as_str).collect(),
+            _ => panic!("Expected a declaration, got {:?}", stmt),
+        }
+    }
+
+    pub fn param_names(params: &[Param]) -> Vec<&str> {
+        params.iter().map(|param| param.name.as_str()).collect()
+    }
+
     pub fn if_stmt(stmt: &Stmt) -> (&Expr, &Stmt, &Option<Box<Stmt>>) {
         match stmt {
             Stmt::If {
                 condition,
                 then_branch,
                 else_branch,
+                ..
             } => (condition, then_branch, else_branch),
             _ => panic!("Expected If statement, got {:?}", stmt),
         }
@@ -222,18 +354,21 @@ mod assert_stmt {
 
     pub fn while_stmt(stmt: &Stmt) -> (&Expr, &Stmt) {
         match stmt {
-            Stmt::While { condition, body } => (condition, body),
+            Stmt::While {
+                condition, body, ..
+            } => (condition, body),
             _ => panic!("Expected While statement, got {:?}", stmt),
         }
     }
 
-    pub fn for_stmt(stmt: &Stmt) -> (&Option<Box<Stmt>>, &Option<Expr>, &Option<Expr>, &Stmt) {
+    pub fn for_stmt(stmt: &Stmt) -> (&Option<Box<Stmt>>, &Option<Expr>, &Vec<Expr>, &Stmt) {
         match stmt {
             Stmt::For {
                 init,
                 condition,
                 update,
                 body,
+                ..
             } => (init, condition, update, body),
             _ => panic!("Expected For statement, got {:?}", stmt),
         }
@@ -241,14 +376,14 @@ mod assert_stmt {
 
     pub fn return_stmt(stmt: &Stmt) -> &Option<Expr> {
         match stmt {
-            Stmt::Return { value } => value,
+            Stmt::Return { value, .. } => value,
             _ => panic!("Expected Return statement, got {:?}", stmt),
         }
     }
 
     pub fn block_stmt(stmt: &Stmt, expected_len: usize) -> &Vec<Stmt> {
         match stmt {
-            Stmt::Block { statements } => {
+            Stmt::Block { statements, .. } => {
                 assert_eq!(statements.len(), expected_len);
                 statements
             }
@@ -258,20 +393,36 @@ mod assert_stmt {
 
     pub fn expression_stmt(stmt: &Stmt) -> &Expr {
         match stmt {
-            Stmt::Expression { expr } => expr,
+            Stmt::Expression { expr, .. } => expr,
             _ => panic!("Expected Expression statement, got {:?}", stmt),
         }
     }
 
-    pub fn import_stmt(stmt: &Stmt, expected_module: &str) {
+    pub fn import_stmt<'a>(
+        stmt: &'a Stmt,
+        expected_module: &str,
+    ) -> (&'a Vec<(String, Option<String>)>, &'a Option<String>) {
         match stmt {
-            Stmt::Import { module } => {
+            Stmt::Import {
+                module,
+                names,
+                alias,
+                ..
+            } => {
                 assert_eq!(module, expected_module);
+                (names, alias)
             }
             _ => panic!("Expected Import statement, got {:?}", stmt),
         }
     }
 
+    pub fn import_names(names: &[(String, Option<String>)]) -> Vec<(&str, Option<&str>)> {
+        names
+            .iter()
+            .map(|(name, alias)| (name.as_str(), alias.as_deref()))
+            .collect()
+    }
+
     pub fn export_stmt(stmt: &Stmt) -> &Stmt {
         match stmt {
             Stmt::Export { statement } => statement,
@@ -279,16 +430,30 @@ mod assert_stmt {
         }
     }
 
-    pub fn break_stmt(stmt: &Stmt) {
+    pub fn export_default_stmt(stmt: &Stmt) -> &Expr {
+        match stmt {
+            Stmt::ExportDefault { expr, .. } => expr,
+            _ => panic!("Expected ExportDefault statement, got {:?}", stmt),
+        }
+    }
+
+    pub fn loop_label(stmt: &Stmt) -> Option<&str> {
+        match stmt {
+            Stmt::While { label, .. } | Stmt::For { label, .. } => label.as_deref(),
+            _ => panic!("Expected loop statement, got {:?}", stmt),
+        }
+    }
+
+    pub fn break_stmt(stmt: &Stmt) -> Option<&str> {
         match stmt {
-            Stmt::Break => {}
+            Stmt::Break { label, .. } => label.as_deref(),
             _ => panic!("Expected Break statement, got {:?}", stmt),
         }
     }
 
-    pub fn continue_stmt(stmt: &Stmt) {
+    pub fn continue_stmt(stmt: &Stmt) -> Option<&str> {
         match stmt {
-            Stmt::Continue => {}
+            Stmt::Continue { label, .. } => label.as_deref(),
             _ => panic!("Expected Continue statement, got {:?}", stmt),
         }
     }
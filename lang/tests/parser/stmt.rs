@@ -1,5 +1,15 @@
 use cases::LET_CASES;
-use qbit_lang::ast::{expr::Expr, op::BinaryOp};
+use qbit_lang::{
+    ast::{
+        expr::Expr,
+        op::BinaryOp,
+        span::Span,
+        stmt::{Program, Stmt},
+        value::Value,
+    },
+    lexer::Token,
+    parser::{LimitKind, Parse, ParseError, Parser},
+};
 
 use super::{TestHelper, assert_expr, assert_stmt};
 
@@ -107,6 +117,22 @@ mod cases {
             source: "fn test(a b) { }",
             expected: "Expected ',' or ')'",
         },
+        ErrorTestCase {
+            source: "fn test(a = 1, b) { }",
+            expected: "parameter 'b' without a default cannot follow parameters with defaults",
+        },
+        ErrorTestCase {
+            source: "fn test(a = ) { }",
+            expected: "Expected expression",
+        },
+        ErrorTestCase {
+            source: "fn test(...rest, a) { }",
+            expected: "parameter 'a' cannot follow a rest parameter",
+        },
+        ErrorTestCase {
+            source: "fn test(...) { }",
+            expected: "Expected parameter name",
+        },
         ErrorTestCase {
             source: "if;",
             expected: "Expected expression",
@@ -131,6 +157,26 @@ mod cases {
             source: "import;",
             expected: "Expected module name",
         },
+        ErrorTestCase {
+            source: "import {} from \"math\";",
+            expected: "Expected imported name",
+        },
+        ErrorTestCase {
+            source: "import { add } \"math\";",
+            expected: "Expected From",
+        },
+        ErrorTestCase {
+            source: "import { add as } from \"math\";",
+            expected: "Expected alias",
+        },
+        ErrorTestCase {
+            source: "import { add } from \"math\" as m;",
+            expected: "Expected Semicolon",
+        },
+        ErrorTestCase {
+            source: "import { add sub } from \"math\";",
+            expected: "Expected ',' or '}', found Identifier",
+        },
         ErrorTestCase {
             source: "export;",
             expected: "Expected expression",
@@ -202,7 +248,7 @@ fn fn_stmt() {
             assert_stmt::function_stmt(&stmt, case.func_name, case.param_names.len());
 
         for (i, expected_param) in case.param_names.iter().enumerate() {
-            assert_eq!(&params[i], expected_param);
+            assert_eq!(&params[i].name, expected_param);
         }
     }
 
@@ -219,7 +265,7 @@ fn fn_stmt() {
     .unwrap();
 
     let (params, body) = assert_stmt::function_stmt(&stmt, "calculate", 2);
-    assert_eq!(params, &["x", "y"]);
+    assert_eq!(assert_stmt::param_names(params), ["x", "y"]);
 
     let statements = assert_stmt::block_stmt(body, 3);
 
@@ -249,6 +295,161 @@ fn fn_stmt() {
     assert_expr::variable(ret_right, "product");
 }
 
+#[test]
+fn default_param_stmt() {
+    let stmt = TestHelper::stmt("fn f(a, b = 10, c = a + 1) { return a; }").unwrap();
+    let (params, _) = assert_stmt::function_stmt(&stmt, "f", 3);
+    assert_eq!(assert_stmt::param_names(params), ["a", "b", "c"]);
+
+    assert!(params[0].default.is_none());
+    assert_expr::literal_int(params[1].default.as_ref().unwrap(), 10);
+
+    let (left, right) = assert_expr::binary_op(params[2].default.as_ref().unwrap(), BinaryOp::Add);
+    assert_expr::variable(left, "a");
+    assert_expr::literal_int(right, 1);
+}
+
+#[test]
+fn rest_param_stmt() {
+    let stmt = TestHelper::stmt("fn f(a, ...rest) { return rest; }").unwrap();
+    let (params, _) = assert_stmt::function_stmt(&stmt, "f", 2);
+    assert_eq!(assert_stmt::param_names(params), ["a", "rest"]);
+    assert!(!params[0].rest);
+    assert!(params[1].rest);
+
+    // A rest parameter may follow defaults and take a trailing comma
+    let stmt = TestHelper::stmt("fn f(a = 1, ...rest,) { }").unwrap();
+    let (params, _) = assert_stmt::function_stmt(&stmt, "f", 2);
+    assert!(params[1].rest);
+    assert!(params[1].default.is_none());
+}
+
+#[test]
+fn type_annotation_stmt() {
+    let stmt = TestHelper::stmt("let x: int = 5;").unwrap();
+    assert_expr::literal_int(assert_stmt::let_stmt(&stmt, "x"), 5);
+    match &stmt {
+        Stmt::Let { ty, .. } => assert_eq!(ty.as_deref(), Some("int")),
+        _ => unreachable!(),
+    }
+
+    let stmt = TestHelper::stmt("let x: string;").unwrap();
+    match &stmt {
+        Stmt::Let { ty, .. } => assert_eq!(ty.as_deref(), Some("string")),
+        _ => unreachable!(),
+    }
+
+    let stmt = TestHelper::stmt("const MAX: int = 10;").unwrap();
+    match &stmt {
+        Stmt::Const { ty, .. } => assert_eq!(ty.as_deref(), Some("int")),
+        _ => unreachable!(),
+    }
+
+    // Annotations stay optional
+    let stmt = TestHelper::stmt("let x = 5;").unwrap();
+    match &stmt {
+        Stmt::Let { ty, .. } => assert!(ty.is_none()),
+        _ => unreachable!(),
+    }
+
+    let stmt = TestHelper::stmt("fn f(a: int, b, c: string = \"x\", ...rest: int) { }").unwrap();
+    let (params, _) = assert_stmt::function_stmt(&stmt, "f", 4);
+    let types: Vec<_> = params.iter().map(|param| param.ty.as_deref()).collect();
+    assert_eq!(types, [Some("int"), None, Some("string"), Some("int")]);
+    assert_expr::literal_string(params[2].default.as_ref().unwrap(), "x");
+
+    TestHelper::assert_stmt_err("let x: = 5;", "Expected type name");
+    TestHelper::assert_stmt_err("fn f(a:) { }", "Expected type name");
+}
+
+#[test]
+fn return_type_stmt() {
+    let annotated = TestHelper::stmt("fn f(a) : int { return a; }").unwrap();
+    let unannotated = TestHelper::stmt("fn f(a) { return a; }").unwrap();
+
+    match (&annotated, &unannotated) {
+        (
+            Stmt::Function {
+                return_ty: Some(ty),
+                body: annotated_body,
+                ..
+            },
+            Stmt::Function {
+                return_ty: None,
+                body: unannotated_body,
+                ..
+            },
+        ) => {
+            assert_eq!(ty, "int");
+            assert_eq!(annotated_body, unannotated_body);
+        }
+        _ => panic!("Expected annotated and unannotated functions"),
+    }
+
+    let (_, body) = assert_stmt::function_stmt(&annotated, "f", 1);
+    let statements = assert_stmt::block_stmt(body, 1);
+    assert_stmt::return_stmt(&statements[0]);
+
+    TestHelper::assert_stmt_err("fn f(a) : { }", "Expected type name");
+}
+
+#[test]
+fn concise_function_stmt() {
+    // Desugars to a block returning the expression
+    let stmt = TestHelper::stmt("fn double(x) => x * 2;").unwrap();
+    let (params, body) = assert_stmt::function_stmt(&stmt, "double", 1);
+    assert_eq!(assert_stmt::param_names(params), ["x"]);
+
+    let statements = assert_stmt::block_stmt(body, 1);
+    let value = assert_stmt::return_stmt(&statements[0]).as_ref().unwrap();
+    let (left, right) = assert_expr::binary_op(value, BinaryOp::Mul);
+    assert_expr::variable(left, "x");
+    assert_expr::literal_int(right, 2);
+
+    // Same as the block form it stands for
+    assert_eq!(
+        TestHelper::stmt("fn f(a, b): int => a + b;").unwrap(),
+        TestHelper::stmt("fn f(a, b): int { return a + b; }").unwrap()
+    );
+
+    let result = TestHelper::src("export fn one() => 1;\none();").unwrap();
+    assert_eq!(result.statements().len(), 2);
+
+    TestHelper::assert_stmt_err("fn f() => ;", "Expected expression");
+    TestHelper::assert_stmt_err("fn f() => 1", "expected Semicolon");
+}
+
+#[test]
+fn ternary_boundary_stmt() {
+    // There is no conditional expression yet, so a bare `?` is rejected up front
+    // rather than being mistaken for the start of anything else
+    TestHelper::assert_stmt_err("a ? b : c;", "Invalid token ('?')");
+    TestHelper::assert_stmt_err("x = cond ? 1 : 2;", "Invalid token ('?')");
+
+    // `?.` directly after an operand stays optional member access
+    let stmt = TestHelper::stmt("a?.b;").unwrap();
+    let object = assert_expr::optional_member(assert_stmt::expression_stmt(&stmt), "b");
+    assert_expr::variable(object, "a");
+
+    // `if` is only ever a statement
+    let stmt = TestHelper::stmt("if cond { x = 1; } else { x = 2; }").unwrap();
+    let (condition, _, else_branch) = assert_stmt::if_stmt(&stmt);
+    assert_expr::variable(condition, "cond");
+    assert!(else_branch.is_some());
+
+    TestHelper::assert_stmt_err(
+        "x = if cond { 1 } else { 2 };",
+        "Expected expression, found If",
+    );
+
+    // A condition assigned outright is a plain expression statement
+    let stmt = TestHelper::stmt("x = cond;").unwrap();
+    assert!(matches!(
+        assert_stmt::expression_stmt(&stmt),
+        Expr::Assignment { .. }
+    ));
+}
+
 #[test]
 fn if_stmt() {
     // Simple if statement
@@ -415,8 +616,8 @@ fn for_stmt() {
     assert_expr::literal_int(cond_right, 10);
 
     // Check update: i++
-    assert!(update.is_some());
-    match update.as_ref().unwrap() {
+    assert_eq!(update.len(), 1);
+    match &update[0] {
         Expr::PostIncrement { operand } => {
             assert_expr::variable(&operand, "i");
         }
@@ -435,7 +636,7 @@ fn for_stmt() {
 
     assert!(init.is_none());
     assert!(condition.is_some());
-    assert!(update.is_none());
+    assert!(update.is_empty());
 
     // Infinite for loop
     let stmt = TestHelper::stmt("for (;;) { break; }").unwrap();
@@ -443,7 +644,23 @@ fn for_stmt() {
 
     assert!(init.is_none());
     assert!(condition.is_none());
-    assert!(update.is_none());
+    assert!(update.is_empty());
+
+    // Several updates, evaluated in order
+    let stmt = TestHelper::stmt("for (let i = 0; i < j; i++, j--, step()) {}").unwrap();
+    let (_init, _condition, update, _body) = assert_stmt::for_stmt(&stmt);
+
+    assert_eq!(update.len(), 3);
+    match (&update[0], &update[1]) {
+        (Expr::PostIncrement { operand: i }, Expr::PostDecrement { operand: j }) => {
+            assert_expr::variable(i, "i");
+            assert_expr::variable(j, "j");
+        }
+        _ => panic!("Expected i++, j-- in for update"),
+    }
+    assert_expr::call(&update[2], "step", 0);
+
+    TestHelper::assert_stmt_err("for (;; i++,) {}", "Expected expression");
 }
 
 #[test]
@@ -593,7 +810,37 @@ fn import_stmt() {
 
     // Import with path-like string
     let stmt = TestHelper::stmt(r#"import "lib/collections";"#).unwrap();
-    assert_stmt::import_stmt(&stmt, "lib/collections");
+    let (names, alias) = assert_stmt::import_stmt(&stmt, "lib/collections");
+    assert!(names.is_empty());
+    assert!(alias.is_none());
+
+    // Named imports
+    let stmt = TestHelper::stmt(r#"import { add, sub } from "math";"#).unwrap();
+    let (names, _) = assert_stmt::import_stmt(&stmt, "math");
+    assert_eq!(
+        assert_stmt::import_names(names),
+        [("add", None), ("sub", None)]
+    );
+
+    // Named imports with trailing comma and identifier module
+    let stmt = TestHelper::stmt("import { max, } from utils;").unwrap();
+    let (names, _) = assert_stmt::import_stmt(&stmt, "utils");
+    assert_eq!(assert_stmt::import_names(names), [("max", None)]);
+
+    // Aliased names keep both the original and the local name
+    let stmt = TestHelper::stmt(r#"import { add as plus, sub } from "math";"#).unwrap();
+    let (names, alias) = assert_stmt::import_stmt(&stmt, "math");
+    assert_eq!(
+        assert_stmt::import_names(names),
+        [("add", Some("plus")), ("sub", None)]
+    );
+    assert!(alias.is_none());
+
+    // Aliased module
+    let stmt = TestHelper::stmt("import utils as u;").unwrap();
+    let (names, alias) = assert_stmt::import_stmt(&stmt, "utils");
+    assert!(names.is_empty());
+    assert_eq!(alias.as_deref(), Some("u"));
 }
 
 #[test]
@@ -616,6 +863,19 @@ fn export_stmt() {
     assert_expr::literal_int(value, 1);
 }
 
+#[test]
+fn export_default_stmt() {
+    let stmt = TestHelper::stmt("export default 42;").unwrap();
+    let expr = assert_stmt::export_default_stmt(&stmt);
+    assert_expr::literal_int(expr, 42);
+
+    let stmt = TestHelper::stmt("export default foo;").unwrap();
+    let expr = assert_stmt::export_default_stmt(&stmt);
+    assert_expr::variable(expr, "foo");
+
+    assert!(TestHelper::stmt("export default;").is_err());
+}
+
 #[test]
 fn break_continue_stmt() {
     // Break statement
@@ -649,7 +909,7 @@ fn comment_stmt() {
     .unwrap();
 
     let (params, body) = assert_stmt::function_stmt(&stmt, "test", 1);
-    assert_eq!(params[0], "x");
+    assert_eq!(params[0].name, "x");
 
     let statements = assert_stmt::block_stmt(body, 2);
     assert_stmt::let_stmt(&statements[0], "y");
@@ -676,12 +936,408 @@ fn comment_stmt() {
     assert_stmt::let_stmt(&statements[1], "b");
 }
 
+#[test]
+fn let_initializer_stmt() {
+    // Permissive by default, the value defaults to null
+    let stmt = TestHelper::stmt("let x;").unwrap();
+    let value = assert_stmt::let_stmt(&stmt, "x");
+    assert!(matches!(value, Expr::Literal(Value::Null)));
+
+    // Strict mode requires an initializer
+    let error = TestHelper::strict_stmt("let x;").unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("variable 'x' must be initialized"),
+        "Unexpected error: {}",
+        error
+    );
+
+    let stmt = TestHelper::strict_stmt("let x = 1;").unwrap();
+    assert_expr::literal_int(assert_stmt::let_stmt(&stmt, "x"), 1);
+}
+
+#[test]
+fn statement_limit_stmt() {
+    let source = "let x = 1;\n".repeat(50);
+    let parse = |max| {
+        Parser::builder(&source)
+            .max_statements(max)
+            .build()
+            .unwrap()
+            .parse()
+    };
+
+    assert_eq!(parse(50).unwrap().statements().len(), 50);
+
+    match parse(20) {
+        Err(ParseError::LimitExceeded {
+            kind,
+            limit,
+            context,
+            ..
+        }) => {
+            assert_eq!(kind, LimitKind::Statements);
+            assert_eq!(limit, 20);
+            assert_eq!(context.line_number, 21);
+        }
+        result => panic!("Expected LimitExceeded, got {:?}", result),
+    }
+
+    // Nested statements count too
+    let source = format!("fn f() {{ {} }}", "g();".repeat(10));
+    let error = Parser::builder(&source)
+        .max_statements(5)
+        .build()
+        .unwrap()
+        .parse()
+        .unwrap_err();
+    assert_eq!(error.to_string(), "Too many statements, the limit is 5");
+}
+
+#[test]
+fn parse_budget_stmt() {
+    let source = "let value = (1 + 2) * 3;\n".repeat(10_000);
+
+    let error = Parser::builder(&source)
+        .max_parse_steps(500)
+        .build()
+        .unwrap()
+        .parse()
+        .unwrap_err();
+
+    match &error {
+        ParseError::BudgetExhausted {
+            max_steps,
+            position,
+            ..
+        } => {
+            assert_eq!(*max_steps, 500);
+            // Each statement is 11 tokens, charged once even though the parser backtracks
+            // over `(`, so the budget runs out at the `+` on the 46th line
+            assert_eq!(*position, 45 * 25 + 15);
+        }
+        _ => panic!("Expected BudgetExhausted, got {:?}", error),
+    }
+
+    assert_eq!(error.to_string(), "Parse budget of 500 steps exhausted");
+
+    // Streaming parsers share the budget
+    let error = Parser::builder(&source)
+        .max_parse_steps(500)
+        .build_streaming()
+        .parse()
+        .unwrap_err();
+    assert!(matches!(error, ParseError::BudgetExhausted { .. }));
+
+    // A budget covering every token is not an error
+    let source = "let value = (1 + 2) * 3;\n".repeat(10);
+    let mut parser = Parser::builder(&source)
+        .max_parse_steps(110)
+        .build()
+        .unwrap();
+    assert_eq!(parser.parse().unwrap().statements().len(), 10);
+
+    // Nested brackets are backtracked over at every level, each token still costs one step
+    let budget = |max_steps| {
+        Parser::builder("x = [[[1]]];")
+            .max_parse_steps(max_steps)
+            .build()
+            .unwrap()
+            .parse()
+    };
+    assert!(budget(10).is_ok());
+    assert!(matches!(
+        budget(9).err().unwrap(),
+        ParseError::BudgetExhausted { max_steps: 9, .. }
+    ));
+
+    // Template interpolations are charged to the same budget, five tokens outside
+    // the template and five inside it
+    let budget = |max_steps| {
+        Parser::builder("let s = `${a + b + c}`;")
+            .max_parse_steps(max_steps)
+            .build()
+            .unwrap()
+            .parse()
+    };
+    assert!(budget(10).is_ok());
+    for max_steps in [3, 6, 9] {
+        assert!(matches!(
+            budget(max_steps).err().unwrap(),
+            ParseError::BudgetExhausted { .. }
+        ));
+    }
+}
+
+#[test]
+fn let_multi_stmt() {
+    // Two bindings
+    let stmt = TestHelper::stmt("let a = 1, b = 2;").unwrap();
+    let bindings = assert_stmt::let_multi_stmt(&stmt, &["a", "b"]);
+    assert_expr::literal_int(&bindings[0].value, 1);
+    assert_expr::literal_int(&bindings[1].value, 2);
+
+    // Three bindings, with a type annotation
+    let stmt = TestHelper::stmt("let a = 1, b: int = 2, c = a + b;").unwrap();
+    let bindings = assert_stmt::let_multi_stmt(&stmt, &["a", "b", "c"]);
+    assert_eq!(bindings[1].ty.as_deref(), Some("int"));
+    assert_expr::binary_op(&bindings[2].value, BinaryOp::Add);
+
+    // Initializers may be omitted independently
+    let stmt = TestHelper::stmt("let a = 1, b, c = 3;").unwrap();
+    let bindings = assert_stmt::let_multi_stmt(&stmt, &["a", "b", "c"]);
+    assert_expr::literal_int(&bindings[0].value, 1);
+    assert!(matches!(bindings[1].value, Expr::Literal(Value::Null)));
+    assert_expr::literal_int(&bindings[2].value, 3);
+
+    let error = TestHelper::strict_stmt("let a = 1, b;").unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("variable 'b' must be initialized")
+    );
+
+    // Trailing comma follows the list configuration
+    let stmt = TestHelper::stmt("let a = 1, b = 2,;").unwrap();
+    assert_stmt::let_multi_stmt(&stmt, &["a", "b"]);
+
+    let mut parser = Parser::builder("let a = 1, b = 2,;")
+        .allow_trailing_commas(false)
+        .build()
+        .unwrap();
+    assert!(Stmt::parse(&mut parser).is_err());
+
+    // A single binding stays a plain let
+    let stmt = TestHelper::stmt("let a = 1;").unwrap();
+    assert_stmt::let_stmt(&stmt, "a");
+}
+
+#[test]
+fn optional_semicolons_stmt() {
+    let program = TestHelper::optional_semicolons_src(
+        r#"
+            let x = 1
+            const Y = x
+                + 2
+            print(x) // trailing comment
+            fn f() { return }
+            fn g() {
+                if x { break }
+                return x
+            }
+            import math
+            let z = { f()
+                g() }
+        "#,
+    )
+    .unwrap();
+
+    let statements = program.statements();
+    assert_eq!(statements.len(), 7);
+    assert_expr::literal_int(assert_stmt::let_stmt(&statements[0], "x"), 1);
+
+    // A binary operator continues the expression on the next line
+    let value = assert_stmt::const_stmt(&statements[1], "Y");
+    assert_expr::binary_op(value, BinaryOp::Add);
+
+    assert_expr::call(assert_stmt::expression_stmt(&statements[2]), "print", 1);
+
+    let (_, body) = assert_stmt::function_stmt(&statements[3], "f", 0);
+    let body = assert_stmt::block_stmt(body, 1);
+    assert!(assert_stmt::return_stmt(&body[0]).is_none());
+
+    let (_, body) = assert_stmt::function_stmt(&statements[4], "g", 0);
+    let body = assert_stmt::block_stmt(body, 2);
+    assert!(assert_stmt::return_stmt(&body[1]).is_some());
+
+    let (statements, tail) = assert_expr::block(assert_stmt::let_stmt(&statements[6], "z"), 1);
+    assert_expr::call(assert_stmt::expression_stmt(&statements[0]), "f", 0);
+    assert_expr::call(tail.as_ref().unwrap(), "g", 0);
+
+    // Statements sharing a line still need a semicolon
+    let error = TestHelper::optional_semicolons_src("let a = 1 let b = 2").unwrap_err();
+    assert_eq!(error.to_string(), "Missing semicolon");
+
+    // A parenthesis on the next line continues the previous expression as a call
+    let program = TestHelper::optional_semicolons_src("let a = f\n(1)").unwrap();
+    assert_expr::call(assert_stmt::let_stmt(&program.statements()[0], "a"), "f", 1);
+
+    // Semicolons stay required by default
+    TestHelper::src("let x = 1\nlet y = 2").unwrap_err();
+}
+
+#[test]
+fn missing_semicolon_stmt() {
+    // The next statement starting is reported as a missing `;` after the previous one
+    for (source, end) in [
+        ("let x = 1 let y = 2;", 9),
+        ("let x = 1;\nconst y = f(x) return y;", 25),
+        ("x += 1 if x { }", 6),
+        ("fn f() { return 1 }\n", 17),
+        ("import math\nlet x = 1;", 11),
+        ("let x = 1\nlet y = 2;", 9),
+        ("f()\ng()", 3),
+    ] {
+        match TestHelper::src(source).unwrap_err() {
+            ParseError::MissingToken { expected, span, .. } => {
+                assert_eq!(expected, "semicolon", "Wrong token for {source:?}");
+                assert_eq!(span, end..end, "Wrong location for {source:?}");
+            }
+            other => panic!("Expected MissingToken for {source:?}, got {other:?}"),
+        }
+    }
+
+    // A stray token on the same line is still reported as itself
+    let error = TestHelper::src("let x = 1 y;").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Expected Semicolon, found Identifier(\"y\")"
+    );
+    let error = TestHelper::src("let x = 42").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Unexpected end of file, expected Semicolon"
+    );
+}
+
+#[test]
+fn reserved_name_stmt() {
+    for (source, keyword) in [
+        ("let for = 1;", "for"),
+        ("let a = 1, if = 2;", "if"),
+        ("const return = 1;", "return"),
+        ("fn while() { }", "while"),
+        ("fn f(a, default) { }", "default"),
+        ("fn f(...null) { }", "null"),
+    ] {
+        let error = TestHelper::src(source).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!(
+                "Syntax error: '{keyword}' is a reserved keyword and cannot be used as an identifier"
+            ),
+            "Wrong error for {source:?}"
+        );
+    }
+
+    // The error points at the keyword
+    match TestHelper::src("let x = 1;\nlet as = 2;").unwrap_err() {
+        ParseError::InvalidSyntax { span, .. } => assert_eq!(span, 15..17),
+        other => panic!("Expected InvalidSyntax, got {other:?}"),
+    }
+
+    // Names merely starting with a keyword are fine
+    let stmt = TestHelper::stmt("let format = 1;").unwrap();
+    assert_stmt::let_stmt(&stmt, "format");
+}
+
+#[test]
+fn labeled_loop_stmt() {
+    let stmt = TestHelper::stmt(
+        r#"
+            outer: for (let i = 0; i < 3; i++) {
+                inner: while true {
+                    if i == 1 { continue outer; }
+                    break inner;
+                }
+                break;
+            }
+        "#,
+    )
+    .unwrap();
+
+    assert_eq!(assert_stmt::loop_label(&stmt), Some("outer"));
+    let (_, _, _, body) = assert_stmt::for_stmt(&stmt);
+    let statements = assert_stmt::block_stmt(body, 2);
+    assert_eq!(assert_stmt::loop_label(&statements[0]), Some("inner"));
+    assert_eq!(assert_stmt::break_stmt(&statements[1]), None);
+
+    let (_, body) = assert_stmt::while_stmt(&statements[0]);
+    let inner = assert_stmt::block_stmt(body, 2);
+    let (_, then_branch, _) = assert_stmt::if_stmt(&inner[0]);
+    let then_statements = assert_stmt::block_stmt(then_branch, 1);
+    assert_eq!(
+        assert_stmt::continue_stmt(&then_statements[0]),
+        Some("outer")
+    );
+    assert_eq!(assert_stmt::break_stmt(&inner[1]), Some("inner"));
+
+    // Unlabeled loops and jumps are unchanged
+    let stmt = TestHelper::stmt("while x { continue; }").unwrap();
+    assert_eq!(assert_stmt::loop_label(&stmt), None);
+
+    // Labels in block expressions
+    let stmt = TestHelper::stmt("let x = { outer: while true { break outer; } 1 };").unwrap();
+    let (statements, _) = assert_expr::block(assert_stmt::let_stmt(&stmt, "x"), 1);
+    assert_eq!(assert_stmt::loop_label(&statements[0]), Some("outer"));
+
+    // Only loops can be labeled
+    TestHelper::assert_stmt_err("outer: x = 1;", "Expected loop after label");
+    TestHelper::assert_stmt_err("outer: { break outer; }", "Expected loop after label");
+}
+
+#[test]
+fn leading_comments_stmt() {
+    let program = TestHelper::src(
+        r#"
+            // Adds two numbers
+            /* and returns the sum */
+            fn foo(a, b) { return a + b; }
+
+            let x = 1; // trailing, not attached
+            const Y = 2;
+
+            /// Exported helper
+            export fn bar() {}
+        "#,
+    )
+    .unwrap();
+
+    let statements = program.statements();
+    assert_stmt::function_stmt(&statements[0], "foo", 2);
+    assert_eq!(
+        assert_stmt::leading_comments(&statements[0]),
+        [" Adds two numbers", " and returns the sum "]
+    );
+    assert!(assert_stmt::leading_comments(&statements[1]).is_empty());
+    assert!(assert_stmt::leading_comments(&statements[2]).is_empty());
+
+    let exported = assert_stmt::export_stmt(&statements[3]);
+    assert_eq!(
+        assert_stmt::leading_comments(exported),
+        [" Exported helper"]
+    );
+
+    // Nested declarations keep their comments too
+    let stmt = TestHelper::stmt("fn f() {\n    // counter\n    let i = 0;\n}").unwrap();
+    let (_, body) = assert_stmt::function_stmt(&stmt, "f", 0);
+    let statements = assert_stmt::block_stmt(body, 1);
+    assert_eq!(assert_stmt::leading_comments(&statements[0]), [" counter"]);
+}
+
+#[test]
+fn comment_runs_stmt() {
+    let run = "// filler\n".repeat(20_000);
+    let source = format!("let x = 1 +\n{run}2;\n{run}let y = x;\n{run}");
+    let program = TestHelper::src(&source).unwrap();
+
+    let statements = program.statements();
+    assert_eq!(statements.len(), 2);
+    assert_expr::binary_op(assert_stmt::let_stmt(&statements[0], "x"), BinaryOp::Add);
+
+    // Comments in the middle of the first statement are not attached to the second
+    let comments = assert_stmt::leading_comments(&statements[1]);
+    assert_eq!(comments.len(), 20_000);
+    assert!(comments.iter().all(|comment| *comment == " filler"));
+}
+
 #[test]
 fn trail_commas_stmt() {
     // Function parameters with trailing comma
     let stmt = TestHelper::stmt("fn test(a, b, c,) { return a + b + c; }").unwrap();
     let (params, _) = assert_stmt::function_stmt(&stmt, "test", 3);
-    assert_eq!(params, &["a", "b", "c"]);
+    assert_eq!(assert_stmt::param_names(params), ["a", "b", "c"]);
 
     // This should still work even if trailing commas are disabled
     // (since it's a parser config test, we'd need to test with custom config)
@@ -739,8 +1395,9 @@ fn nesting_stmt() {
     assert_eq!(program.statements().len(), 3);
 
     // Test fibonacci function
-    let (fib_params, fib_body) = assert_stmt::function_stmt(&program.statements()[0], "fibonacci", 1);
-    assert_eq!(fib_params[0], "n");
+    let (fib_params, fib_body) =
+        assert_stmt::function_stmt(&program.statements()[0], "fibonacci", 1);
+    assert_eq!(fib_params[0].name, "n");
 
     let fib_statements = assert_stmt::block_stmt(fib_body, 1);
     let (fib_condition, fib_then, fib_else) = assert_stmt::if_stmt(&fib_statements[0]);
@@ -801,8 +1458,8 @@ fn nesting_stmt() {
     assert_expr::variable(for_cond_left, "i");
     assert_expr::variable(for_cond_right, "count");
 
-    assert!(for_update.is_some());
-    match for_update.as_ref().unwrap() {
+    assert_eq!(for_update.len(), 1);
+    match &for_update[0] {
         Expr::PostIncrement { operand } => assert_expr::variable(&operand, "i"),
         _ => panic!("Expected post-increment"),
     }
@@ -987,8 +1644,8 @@ fn edge_cases() {
     assert_expr::literal_int(max_right, 1);
 
     // Check complex update: i += step * 2
-    assert!(update.is_some());
-    match update.as_ref().unwrap() {
+    assert_eq!(update.len(), 1);
+    match &update[0] {
         Expr::CompoundAssignment { target, op, value } => {
             assert_expr::variable(target, "i");
             assert_eq!(*op, BinaryOp::Add);
@@ -1008,3 +1665,98 @@ fn edge_cases() {
     assert_expr::variable(arr_obj, "arr");
     assert_expr::variable(arr_index, "i");
 }
+
+#[test]
+fn program_parse() {
+    let source = "let x = 1;\nfn double(n) { return n * 2; }\ndouble(x);";
+    let mut parser = Parser::builder(source).build().unwrap();
+    let Program(statements) = Program::parse(&mut parser).unwrap();
+
+    assert_eq!(statements.len(), 3);
+    assert_stmt::let_stmt(&statements[0], "x");
+    assert_stmt::function_stmt(&statements[1], "double", 1);
+    assert_expr::call(assert_stmt::expression_stmt(&statements[2]), "double", 1);
+
+    // Same statements as a full parse
+    let result = Parser::parse_src(source).unwrap();
+    assert_eq!(result.statements(), statements.as_slice());
+
+    let mut parser = Parser::builder("").build().unwrap();
+    assert_eq!(Program::parse(&mut parser).unwrap(), Program::default());
+
+    let mut parser = Parser::builder("let x = 1;\nlet = 2;").build().unwrap();
+    assert!(Program::parse(&mut parser).is_err());
+}
+
+#[test]
+fn incomplete_input() {
+    for source in ["if x {", "let x = 1 +", "fn f(a,", "x = [1, 2", "while (x"] {
+        assert!(Parser::is_incomplete(source), "{source}");
+    }
+
+    // Errors more input cannot fix
+    for source in ["if x } ", "let = 1;", "1 + 2 3"] {
+        assert!(!Parser::is_incomplete(source), "{source}");
+    }
+
+    // Complete input is not incomplete either
+    assert!(!Parser::is_incomplete("if x { y(); }"));
+    assert!(!Parser::is_incomplete(""));
+}
+
+#[test]
+fn trivia_stmt() {
+    let source = "let x = 1;\n// between\nlet y = 2; /* after */";
+
+    for streaming in [false, true] {
+        let builder = Parser::builder(source).record_trivia(true);
+        let mut parser = match streaming {
+            true => builder.build_streaming(),
+            false => builder.build().unwrap(),
+        };
+        let result = parser.parse().unwrap();
+        let trivia = result.trivia();
+
+        assert_eq!(trivia.len(), 2);
+
+        // Between the `;` of the first statement and the `let` of the second
+        assert_eq!(
+            trivia[0].comment,
+            Token::LineComment(" between".to_string())
+        );
+        assert_eq!(&source[trivia[0].span.clone()], "// between");
+        assert_eq!(trivia[0].next_token, 5);
+        assert_eq!(trivia[0].previous_token(), Some(4));
+
+        // After the last token
+        assert_eq!(&source[trivia[1].span.clone()], "/* after */");
+        assert_eq!(trivia[1].next_token, 10);
+    }
+
+    // Only recorded when asked for
+    assert!(TestHelper::src(source).unwrap().trivia().is_empty());
+}
+
+#[test]
+fn empty_stmt() {
+    let empty = Stmt::Empty {
+        span: Span::default(),
+    };
+    assert_eq!(TestHelper::stmt(";").unwrap(), empty);
+
+    let program = TestHelper::src(";;").unwrap();
+    assert_eq!(program.statements(), [empty.clone(), empty.clone()]);
+
+    let stmt = TestHelper::stmt("{ ; }").unwrap();
+    assert_eq!(assert_stmt::block_stmt(&stmt, 1)[0], empty);
+
+    // Stray semicolons around other statements
+    let program = TestHelper::src("let x = 1;; f(x);").unwrap();
+    let statements = program.statements();
+    assert_eq!(statements.len(), 3);
+    assert_stmt::let_stmt(&statements[0], "x");
+    assert_eq!(statements[1], empty);
+
+    // Nothing to export
+    TestHelper::assert_stmt_err("export;", "Expected expression");
+}
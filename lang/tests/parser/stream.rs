@@ -0,0 +1,94 @@
+use qbit_lang::{
+    ast::stmt::Stmt,
+    parser::{ParseError, Parser},
+};
+
+/// Statements and rendered diagnostics, or the rendered error
+fn outcome(
+    result: Result<qbit_lang::parser::ParseResult, ParseError>,
+) -> Result<(Vec<Stmt>, Vec<String>), String> {
+    result
+        .map(|result| {
+            let diagnostics = result.diagnostics().iter().map(|d| d.to_string()).collect();
+            (result.statements().to_vec(), diagnostics)
+        })
+        .map_err(|error| error.to_string())
+}
+
+fn assert_same(source: &str) {
+    let buffered = outcome(Parser::parse_src(source));
+    let streaming = outcome(Parser::builder(source).build_streaming().parse());
+
+    assert_eq!(buffered, streaming, "source: {source:?}");
+}
+
+#[test]
+fn streaming_matches_buffered() {
+    let sources = [
+        "",
+        "// only a comment",
+        "let x = 1 + 2 * 3; x;",
+        "fn add(a, b) {\n    // sum\n    return a + b;\n}\nadd(1, 2);",
+        "outer: while (true) { for (let i = 0; i < 3; i = i + 1) { break outer; } }",
+        "let name = \"qbit\"; let greeting = `hello ${name}!`;",
+        "/** docs */\nconst LIMIT = 10;\n/// more\nexport fn limit() { return LIMIT; }",
+        "break missing;",
+        // Telling a labeled loop in a block from an object key looks three tokens ahead
+        "let x = { outer: while true { break outer; } };",
+        "x = { outer: for (;;) { break outer; } };",
+        "let point = { x: 1, y: { z: 2 } };",
+        // Parse errors at the end of input and in the middle
+        "let x = ",
+        "let = 1; let y = 2;",
+        // Invalid tokens win over parse errors, wherever they appear
+        "let x = 1; #",
+        "let = 1; let y = #;",
+    ];
+
+    for source in sources {
+        assert_same(source);
+    }
+
+    let error = Parser::builder("let = 1; let y = #;")
+        .build_streaming()
+        .parse()
+        .unwrap_err();
+    assert!(matches!(error, ParseError::BuildError { .. }));
+
+    let relaxed = "let x = 1\nlet y = x\nreturn";
+    let buffered = outcome(
+        Parser::builder(relaxed)
+            .allow_optional_semicolons(true)
+            .build()
+            .and_then(|mut parser| parser.parse()),
+    );
+    let streaming = outcome(
+        Parser::builder(relaxed)
+            .allow_optional_semicolons(true)
+            .build_streaming()
+            .parse(),
+    );
+
+    assert_eq!(buffered, streaming);
+}
+
+#[test]
+fn streaming_buffer_stays_small() {
+    let source: String = (0..500)
+        .map(|i| format!("// helper {i}\nfn helper_{i}(a, b) {{ return a + b * {i}; }}\n"))
+        .collect();
+
+    let mut parser = Parser::builder(&source).build_streaming();
+    assert!(parser.tokens().len() <= 4);
+
+    let result = parser.parse().unwrap();
+    assert_eq!(result.statements().len(), 500);
+
+    // Only the tokens of the last statement are left over
+    assert!(
+        parser.tokens().len() < 32,
+        "{} tokens buffered",
+        parser.tokens().len()
+    );
+    assert_same(&source);
+}
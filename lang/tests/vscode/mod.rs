@@ -0,0 +1,195 @@
+use qbit_lang::vscode::{DocumentSymbol, document_symbols, format_code, hover_at, tokenize_code};
+
+#[test]
+fn format_code_success() {
+    let result = format_code("let   x=1+2;");
+
+    assert_eq!(
+        serde_json::to_string(&result).unwrap(),
+        r#"{"formatted":"let x = 1 + 2;\n","errors":[]}"#
+    );
+}
+
+#[test]
+fn format_code_comments() {
+    // Trailing, doc and inner comments keep their place
+    let source = concat!(
+        "let x=1; // trailing\n",
+        "/// doc\n",
+        "fn f(a){\n",
+        "  /* inner */\n",
+        "  let y=a; // y\n",
+        "  while y<3 { y+=1; } // loop\n",
+        "  // end of body\n",
+        "}\n",
+        "// before while\n",
+        "while x { x-=1; }\n",
+        "// eof\n",
+    );
+    let result = format_code(source);
+
+    assert!(result.errors.is_empty());
+    assert_eq!(
+        result.formatted,
+        concat!(
+            "let x = 1; // trailing\n",
+            "/// doc\n",
+            "fn f(a) {\n",
+            "    /* inner */\n",
+            "    let y = a; // y\n",
+            "    while y < 3 {\n",
+            "        y += 1;\n",
+            "    } // loop\n",
+            "    // end of body\n",
+            "}\n",
+            "// before while\n",
+            "while x {\n",
+            "    x -= 1;\n",
+            "}\n",
+            "// eof\n",
+        )
+    );
+
+    // A block holding nothing but a comment keeps it
+    let result = format_code("fn f() { // todo\n}");
+    assert_eq!(result.formatted, "fn f() {\n    // todo\n}\n");
+
+    // Comments inside expressions or headers have no place in the output, so the
+    // source is left as it is
+    for source in [
+        "let a = [1, // one\n 2];",
+        "if x /* c */ { a; }",
+        "if x { a; } // c\nelse { b; }",
+        "fn f(a /* c */) {}",
+    ] {
+        let result = format_code(source);
+        assert_eq!(result.formatted, source);
+        assert!(result.errors.is_empty());
+    }
+}
+
+#[test]
+fn format_code_failure() {
+    let source = "let x = ;";
+    let result = format_code(source);
+    let json = serde_json::to_string(&result).unwrap();
+
+    assert_eq!(result.formatted, source);
+    assert_eq!(result.errors.len(), 1);
+    assert!(
+        json.starts_with(r#"{"formatted":"let x = ;","errors":[{"line":1,"#),
+        "{json}"
+    );
+
+    for key in [
+        "length",
+        "column",
+        "end_line",
+        "end_column",
+        "message",
+        "level",
+    ] {
+        assert!(
+            json.contains(&format!("\"{key}\":")),
+            "missing {key}: {json}"
+        );
+    }
+}
+
+#[test]
+fn tokenize_code_json() {
+    let tokens = tokenize_code("x = 1; // one");
+
+    assert_eq!(
+        serde_json::to_string(&tokens).unwrap(),
+        concat!(
+            r#"[{"kind":"variable","category":"identifier","start":0,"end":1},"#,
+            r#"{"kind":"operator","category":"operator","start":2,"end":3},"#,
+            r#"{"kind":"number","category":"literal-number","start":4,"end":5},"#,
+            r#"{"kind":"punctuation","category":"punctuation","start":5,"end":6},"#,
+            r#"{"kind":"comment","category":"comment","start":7,"end":13}]"#,
+        )
+    );
+}
+
+#[test]
+fn hover_descriptions() {
+    let source = "fn add(a, b) { return a + b; }\nconst LIMIT = 3;\nlet x = add(LIMIT, 2);\nx;";
+    let describe = |needle: &str, nth: usize| {
+        let offset = source.match_indices(needle).nth(nth).unwrap().0;
+        hover_at(source, offset).map(|hover| hover.description)
+    };
+
+    assert_eq!(describe("add", 0).as_deref(), Some("function 'add'"));
+    assert_eq!(describe("a +", 0).as_deref(), Some("parameter 'a'"));
+    assert_eq!(describe("LIMIT", 0).as_deref(), Some("constant 'LIMIT'"));
+    assert_eq!(describe("add", 1).as_deref(), Some("function call 'add'"));
+    assert_eq!(describe("LIMIT", 1).as_deref(), Some("constant 'LIMIT'"));
+    assert_eq!(describe("x;", 0).as_deref(), Some("variable 'x'"));
+    assert_eq!(describe("2)", 0), None);
+    assert_eq!(describe(" = add", 0), None);
+}
+
+#[test]
+fn hover_span() {
+    let hover = hover_at("let value = 1;", 6).unwrap();
+
+    assert_eq!((hover.start, hover.end), (4, 9));
+    assert_eq!(hover.description, "variable 'value'");
+    assert_eq!(hover_at("let value = ;", 6), None);
+}
+
+#[test]
+fn document_symbol_list() {
+    let source = "const LIMIT = 3;\nexport fn add(a, b) {\n    let sum = a + b;\n    return sum;\n}\nlet x = add(LIMIT, 2);";
+    let summary = |symbols: &[DocumentSymbol]| {
+        symbols
+            .iter()
+            .map(|symbol| {
+                let text = &source[symbol.start..symbol.end];
+                (
+                    symbol.name.clone(),
+                    symbol.kind.clone(),
+                    symbol.exported,
+                    text.lines().next().unwrap().to_string(),
+                )
+            })
+            .collect::<Vec<_>>()
+    };
+
+    let symbols = document_symbols(source);
+
+    assert_eq!(
+        summary(&symbols),
+        [
+            (
+                "LIMIT".into(),
+                "constant".into(),
+                false,
+                "const LIMIT = 3;".into()
+            ),
+            (
+                "add".into(),
+                "function".into(),
+                true,
+                "fn add(a, b) {".into()
+            ),
+            (
+                "x".into(),
+                "variable".into(),
+                false,
+                "let x = add(LIMIT, 2);".into()
+            ),
+        ]
+    );
+    assert_eq!(
+        summary(&symbols[1].children),
+        [(
+            "sum".into(),
+            "variable".into(),
+            false,
+            "let sum = a + b;".into()
+        )]
+    );
+    assert!(document_symbols("let = 1;").is_empty());
+}
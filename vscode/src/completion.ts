@@ -25,6 +25,7 @@ export class CompletionProvider implements vscode.CompletionItemProvider {
             { name: 'continue', kind: vscode.CompletionItemKind.Keyword, detail: 'Continue statement' },
             { name: 'import', kind: vscode.CompletionItemKind.Keyword, detail: 'Import statement' },
             { name: 'export', kind: vscode.CompletionItemKind.Keyword, detail: 'Export statement' },
+            { name: 'default', kind: vscode.CompletionItemKind.Keyword, detail: 'Default export' },
             { name: 'true', kind: vscode.CompletionItemKind.Keyword, detail: 'Boolean true' },
             { name: 'false', kind: vscode.CompletionItemKind.Keyword, detail: 'Boolean false' },
             { name: 'null', kind: vscode.CompletionItemKind.Keyword, detail: 'Null value' }
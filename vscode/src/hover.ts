@@ -41,7 +41,7 @@ export class HoverProvider implements vscode.HoverProvider {
             return new vscode.Hover(markdown);
         }
 
-        const keywords = ['let', 'const', 'fn', 'if', 'else', 'while', 'for', 'return', 'break', 'continue', 'import', 'export', 'true', 'false', 'null'];
+        const keywords = ['let', 'const', 'fn', 'if', 'else', 'while', 'for', 'return', 'break', 'continue', 'import', 'export', 'default', 'true', 'false', 'null'];
         
         if (keywords.includes(symbol)) {
             const descriptions: { [key: string]: string } = {
@@ -57,6 +57,7 @@ export class HoverProvider implements vscode.HoverProvider {
                 'continue': 'Skips to the next iteration of a loop',
                 'import': 'Imports a module',
                 'export': 'Exports a declaration',
+                'default': 'Marks the default export of a module',
                 'true': 'Boolean true value',
                 'false': 'Boolean false value',
                 'null': 'Null value'